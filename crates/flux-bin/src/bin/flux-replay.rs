@@ -0,0 +1,145 @@
+//! Re-runs a fixpoint task dumped with `FLUX_DUMP_CONSTRAINT=1` (the `.smt2` files in the log
+//! directory) without having to check the crate that generated it. With `--shrink`, the task is
+//! reduced to a smaller one with the same outcome, which is useful to report solver bugs.
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::exit,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Result};
+use flux_bin::utils::EXIT_ERR;
+use liquid_fixpoint::{FixpointStatus, RenderedTask, ResourceLimits, SmtSolver};
+
+const USAGE: &str = "\
+Usage: flux-replay [OPTIONS] <FILE>
+
+Runs `fixpoint` on a task dumped by flux (a `.smt2` file in the log directory).
+
+Options:
+    --solver <z3|cvc5>    SMT solver used by fixpoint [default: z3]
+    --timeout <SECS>      Time limit for each run of fixpoint
+    --shrink              Reduce the task while preserving its outcome
+    -o, --output <FILE>   Where to write the reduced task [default: <FILE>.min.smt2]";
+
+fn main() {
+    let exit_code = match run() {
+        Ok(()) => 0,
+        Err(e) => {
+            println!("failed to run `flux-replay`, error={e}");
+            EXIT_ERR
+        }
+    };
+    exit(exit_code)
+}
+
+struct Args {
+    file: PathBuf,
+    solver: SmtSolver,
+    limits: ResourceLimits,
+    shrink: bool,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut file = None;
+    let mut solver = SmtSolver::Z3;
+    let mut limits = ResourceLimits::default();
+    let mut shrink = false;
+    let mut output = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow!("missing value for `{arg}`"))
+        };
+        match arg.as_str() {
+            "--solver" => {
+                solver = match value()?.as_str() {
+                    "z3" => SmtSolver::Z3,
+                    "cvc5" => SmtSolver::CVC5,
+                    s => bail!("unknown solver `{s}`"),
+                }
+            }
+            "--timeout" => limits.timeout = Some(Duration::from_secs(value()?.parse()?)),
+            "--shrink" => shrink = true,
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                exit(0);
+            }
+            _ if file.is_none() && !arg.starts_with('-') => file = Some(PathBuf::from(arg)),
+            _ => bail!("unexpected argument `{arg}`\n\n{USAGE}"),
+        }
+    }
+    let file = file.ok_or_else(|| anyhow!("missing task file\n\n{USAGE}"))?;
+    Ok(Args { file, solver, limits, shrink, output })
+}
+
+/// The outcome of running a task, which is what we preserve when shrinking. The tags of the
+/// failing constraints may change.
+#[derive(PartialEq, Eq, Debug)]
+enum Outcome {
+    Safe,
+    Unsafe,
+    Crash,
+    /// `fixpoint` couldn't be run or its output couldn't be parsed, e.g., because it timed out
+    Error,
+}
+
+fn replay(args: &Args, text: String) -> (Outcome, String) {
+    let task = RenderedTask::new(text, args.solver, args.limits);
+    match task.run::<String>().map(|result| result.status) {
+        Ok(FixpointStatus::Safe(_)) => (Outcome::Safe, "safe".to_string()),
+        Ok(FixpointStatus::Unsafe(_, errors)) => {
+            let tags: Vec<_> = errors.into_iter().map(|err| err.tag).collect();
+            (Outcome::Unsafe, format!("unsafe, failing tags: {}", tags.join(", ")))
+        }
+        Ok(FixpointStatus::Crash(info)) => (Outcome::Crash, format!("crash: {info:?}")),
+        Err(err) => (Outcome::Error, format!("error: {err}")),
+    }
+}
+
+fn run() -> Result<()> {
+    let args = parse_args()?;
+    if args.file.extension().is_some_and(|ext| ext == "fluxc") {
+        bail!(
+            "`{}` is a refinement tree, replay the `.smt2` file dumped for the same item instead",
+            args.file.display()
+        );
+    }
+    let text = fs::read_to_string(&args.file)?;
+
+    let start = Instant::now();
+    let (outcome, msg) = replay(&args, text.clone());
+    println!("{msg} ({:.2?})", start.elapsed());
+
+    if !args.shrink {
+        return Ok(());
+    }
+    if outcome == Outcome::Safe || outcome == Outcome::Error {
+        bail!("only tasks that are unsafe or crash can be shrunk");
+    }
+
+    let mut runs = 0;
+    let shrunk = liquid_fixpoint::shrink::shrink(&text, |candidate| {
+        runs += 1;
+        replay(&args, candidate.to_string()).0 == outcome
+    })?;
+
+    let output = args
+        .output
+        .clone()
+        .unwrap_or_else(|| args.file.with_extension("min.smt2"));
+    fs::write(&output, &shrunk)?;
+    println!(
+        "wrote reduced task to `{}` ({} -> {} lines, {runs} runs)",
+        output.display(),
+        text.lines().count(),
+        shrunk.lines().count(),
+    );
+    Ok(())
+}
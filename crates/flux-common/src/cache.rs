@@ -1,16 +1,32 @@
-use std::{fs::File, path::PathBuf};
+//! An on-disk cache for query results addressed by the hash of the query.
+//!
+//! Each entry is stored in its own file inside the cache directory, named after the hash of the
+//! query. Entries are written to a temporary file and then renamed, so concurrent processes
+//! pointing to the same directory never observe a partially written entry. This makes it possible
+//! to share a cache directory between several workspaces or parallel invocations.
+//!
+//! The size of the directory is bounded by [`config::cache_max_size`]. When the bound is exceeded,
+//! [`QueryCache::evict`] removes the least recently used entries. The last use of an entry is
+//! tracked with the modification time of its file, which is updated when the entry is written or
+//! looked up. Updating the time is best effort: if it fails, e.g., because the file is not owned by
+//! the current user, the entry is only considered used when it was last written and may be evicted
+//! earlier than it should.
 
-use flux_config as config;
-use rustc_hash::FxHashMap;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-struct QueryVal<R> {
-    constr_hash: u64,
-    result: R,
-}
+use flux_config as config;
 
 pub struct QueryCache<R> {
-    entries: FxHashMap<String, QueryVal<R>>,
+    /// The cache directory or `None` if caching is disabled
+    dir: Option<PathBuf>,
+    _result: PhantomData<R>,
 }
 
 impl<R> Default for QueryCache<R> {
@@ -20,58 +36,228 @@ impl<R> Default for QueryCache<R> {
 }
 
 impl<R> QueryCache<R> {
+    /// Creates a cache that doesn't store anything
     pub fn new() -> Self {
-        QueryCache { entries: FxHashMap::default() }
+        QueryCache { dir: None, _result: PhantomData }
     }
 
-    pub fn insert(&mut self, key: String, constr_hash: u64, result: R) {
-        let val = QueryVal { constr_hash, result };
-        self.entries.insert(key, val);
+    fn at(dir: PathBuf) -> Self {
+        QueryCache { dir: Some(dir), _result: PhantomData }
     }
 
-    pub fn lookup(&self, key: &String, constr_hash: u64) -> Option<&R> {
-        let val = self.entries.get(key)?;
-        if val.constr_hash == constr_hash {
-            Some(&val.result)
-        } else {
-            None
-        }
+    fn entry_path(&self, hash: u64) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("{hash:016x}.json")))
     }
 
-    fn path() -> Result<PathBuf, std::io::Error> {
-        if config::is_cache_enabled() {
-            let path = config::cache_path();
-            if let Some(parent) = path.parent() {
-                std::fs::create_dir_all(parent)?;
-                return Ok(path);
+    /// Removes the least recently used entries until the size of the cache directory is below
+    /// [`config::cache_max_size`].
+    pub fn evict(&self) -> Result<(), io::Error> {
+        self.evict_to(config::cache_max_size())
+    }
+
+    fn evict_to(&self, max_size: u64) -> Result<(), io::Error> {
+        let Some(dir) = &self.dir else { return Err(no_cache_err()) };
+
+        let mut entries = vec![];
+        let mut total_size = 0;
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
             }
+            let metadata = entry.metadata()?;
+            total_size += metadata.len();
+            let last_used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((last_used, metadata.len(), path));
+        }
+
+        if total_size <= max_size {
+            return Ok(());
+        }
+        entries.sort_by_key(|(last_used, ..)| *last_used);
+        for (_, size, path) in entries {
+            if total_size <= max_size {
+                break;
+            }
+            // Another process may have already removed the entry
+            if fs::remove_file(path).is_ok() {
+                total_size -= size;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: serde::Serialize + serde::de::DeserializeOwned> QueryCache<R> {
+    /// Opens the cache directory if caching is enabled, creating it if necessary.
+    pub fn load() -> Self {
+        if !config::is_cache_enabled() {
+            return Self::new();
         }
-        Err(Self::no_cache_err())
+        let dir = config::cache_dir();
+        match fs::create_dir_all(&dir) {
+            Ok(()) => Self::at(dir),
+            Err(_) => Self::new(),
+        }
+    }
+
+    pub fn lookup(&self, hash: u64) -> Option<R> {
+        let path = self.entry_path(hash)?;
+        let file = File::open(&path).ok()?;
+        let result = serde_json::from_reader(BufReader::new(&file)).ok()?;
+        // Mark the entry as recently used. This is best effort (see the module docs).
+        let _ = file.set_modified(SystemTime::now());
+        Some(result)
     }
 
-    fn no_cache_err() -> std::io::Error {
-        std::io::Error::new(std::io::ErrorKind::Other, "cache not enabled")
+    pub fn insert(&mut self, hash: u64, result: &R) {
+        if let Some(path) = self.entry_path(hash) {
+            // Failing to write an entry is not an error, we'll just solve the query again.
+            let _ = write_atomic(&path, result);
+        }
     }
 }
 
-impl<R: std::fmt::Debug + serde::Serialize + serde::de::DeserializeOwned> QueryCache<R> {
-    pub fn save(&self) -> Result<(), std::io::Error> {
-        let path = Self::path()?;
-        let mut file = File::create(path).unwrap();
-        serde_json::to_writer(&mut file, &self.entries).unwrap();
-        Ok(())
+/// Writes `value` to a temporary file in the same directory as `path` and then renames it to
+/// `path`, which atomically replaces any previous entry.
+fn write_atomic(path: &Path, value: &impl serde::Serialize) -> Result<(), io::Error> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let tmp = path.with_extension(format!(
+        "tmp.{}.{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        serde_json::to_writer(&mut writer, value)?;
+        writer
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
     }
+    result
+}
 
-    pub fn load() -> Self {
-        let path = Self::path();
-        if let Ok(path) = path {
-            if let Ok(file) = File::open(path) {
-                let entries = serde_json::from_reader(file);
-                if let Ok(entries) = entries {
-                    return QueryCache { entries };
-                }
-            }
+fn no_cache_err() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "cache not enabled")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// A fresh cache directory which is removed when dropped
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("flux-cache-test-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TestDir(dir)
+        }
+
+        fn cache(&self) -> QueryCache<Vec<u32>> {
+            QueryCache::at(self.0.clone())
+        }
+
+        fn set_last_used(&self, hash: u64, secs: u64) {
+            let path = self.cache().entry_path(hash).unwrap();
+            File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        }
+
+        fn entry_size(&self, hash: u64) -> u64 {
+            fs::metadata(self.cache().entry_path(hash).unwrap())
+                .unwrap()
+                .len()
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn insert_and_lookup() {
+        let dir = TestDir::new("insert");
+        let mut cache = dir.cache();
+        assert_eq!(cache.lookup(1), None);
+        cache.insert(1, &vec![1, 2, 3]);
+        cache.insert(2, &vec![]);
+        assert_eq!(cache.lookup(1), Some(vec![1, 2, 3]));
+        assert_eq!(cache.lookup(2), Some(vec![]));
+
+        // Entries can be replaced and are visible to other instances on the same directory
+        cache.insert(1, &vec![4]);
+        assert_eq!(dir.cache().lookup(1), Some(vec![4]));
+    }
+
+    #[test]
+    fn lookup_corrupted_entry() {
+        let dir = TestDir::new("corrupted");
+        let cache = dir.cache();
+        fs::write(cache.entry_path(1).unwrap(), "not json").unwrap();
+        assert_eq!(cache.lookup(1), None);
+    }
+
+    #[test]
+    fn disabled_cache() {
+        let mut cache = QueryCache::<Vec<u32>>::new();
+        cache.insert(1, &vec![1]);
+        assert_eq!(cache.lookup(1), None);
+        assert!(cache.evict().is_err());
+    }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let dir = TestDir::new("evict");
+        let mut cache = dir.cache();
+        for hash in 1..=3 {
+            cache.insert(hash, &vec![hash as u32]);
+            dir.set_last_used(hash, hash * 100);
         }
-        Self::default()
+        // Files that are not entries are not counted nor removed
+        fs::write(dir.0.join("README"), "not an entry").unwrap();
+
+        let size = dir.entry_size(1);
+        cache.evict_to(3 * size).unwrap();
+        assert!((1..=3).all(|hash| cache.entry_path(hash).unwrap().exists()));
+
+        cache.evict_to(2 * size).unwrap();
+        assert!(!cache.entry_path(1).unwrap().exists());
+        assert!(cache.entry_path(2).unwrap().exists());
+        assert!(cache.entry_path(3).unwrap().exists());
+        assert!(dir.0.join("README").exists());
+    }
+
+    #[test]
+    fn lookup_marks_entry_as_used() {
+        let dir = TestDir::new("lookup-used");
+        let mut cache = dir.cache();
+        cache.insert(1, &vec![1]);
+        cache.insert(2, &vec![2]);
+        dir.set_last_used(1, 100);
+        dir.set_last_used(2, 200);
+
+        assert_eq!(cache.lookup(1), Some(vec![1]));
+        cache.evict_to(dir.entry_size(1)).unwrap();
+        assert!(cache.entry_path(1).unwrap().exists());
+        assert!(!cache.entry_path(2).unwrap().exists());
     }
 }
@@ -60,6 +60,24 @@ macro_rules! _check_fn_span {
 }
 pub use crate::_check_fn_span as check_fn_span;
 
+#[macro_export]
+macro_rules! _solution_span {
+    ($tcx:expr, $def_id:expr) => {{
+        let path = $tcx.def_path(rustc_hir::def_id::DefId::from($def_id));
+        let def_id = path.data.iter().join("::");
+        tracing::info_span!("solution", def_id = def_id.as_str())
+    }};
+}
+pub use crate::_solution_span as solution_span;
+
+#[macro_export]
+macro_rules! _kvar_solution {
+    ($kvid:expr, $solution:expr) => {{
+        tracing::info!(event = "kvar_solution", kvar = ?$kvid, solution = ?$solution)
+    }};
+}
+pub use crate::_kvar_solution as kvar_solution;
+
 #[macro_export]
 macro_rules! _basic_block_start {
     ($bb:expr, $rcx:expr, $env:expr) => {{
@@ -1,6 +1,10 @@
 #![feature(closure_track_caller, never_type, rustc_private, try_blocks, try_trait_v2)]
 
 extern crate rustc_borrowck;
+// Unit tests are linked into an executable, which needs the compiler's dylib
+#[cfg(test)]
+#[allow(unused_extern_crates)]
+extern crate rustc_driver;
 extern crate rustc_errors;
 
 extern crate rustc_hir;
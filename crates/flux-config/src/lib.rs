@@ -1,4 +1,4 @@
-use std::{io::Read, path::PathBuf, str::FromStr, sync::LazyLock};
+use std::{fmt, io::Read, path::PathBuf, str::FromStr, sync::LazyLock};
 
 use config::{Environment, File};
 use serde::Deserialize;
@@ -15,6 +15,10 @@ pub fn dump_timings() -> bool {
     CONFIG.dump_timings
 }
 
+pub fn dump_stats() -> bool {
+    CONFIG.dump_stats
+}
+
 pub fn dump_checker_trace() -> bool {
     CONFIG.dump_checker_trace
 }
@@ -51,8 +55,15 @@ pub fn is_checked_file(file: &str) -> bool {
     CONFIG.check_files.is_checked_file(file)
 }
 
-pub fn cache_path() -> PathBuf {
-    log_dir().join(&CONFIG.cache_file)
+/// Directory where query results are cached. A relative path is interpreted relative to the log
+/// directory, an absolute path can be used to share the cache between several workspaces.
+pub fn cache_dir() -> PathBuf {
+    log_dir().join(&CONFIG.cache_dir)
+}
+
+/// Maximum size in bytes of the cache directory
+pub fn cache_max_size() -> u64 {
+    CONFIG.cache_max_size * 1024 * 1024
 }
 
 fn check_overflow() -> bool {
@@ -63,10 +74,45 @@ fn scrape_quals() -> bool {
     CONFIG.scrape_quals
 }
 
+fn precise_bit_ops() -> bool {
+    CONFIG.precise_bit_ops
+}
+
+fn check_raw_pointers() -> bool {
+    CONFIG.check_raw_pointers
+}
+
+fn assume_panic_abort() -> bool {
+    CONFIG.assume_panic_abort
+}
+
 fn solver() -> SmtSolver {
     CONFIG.solver
 }
 
+fn timeout() -> u64 {
+    CONFIG.timeout
+}
+
+fn memory_limit() -> u64 {
+    CONFIG.memory_limit
+}
+
+fn suggest_sigs() -> bool {
+    CONFIG.suggest_sigs
+}
+
+fn fixpoint_backend() -> FixpointBackend {
+    CONFIG.fixpoint_backend
+}
+
+/// Number of workers used to solve fixpoint constraints. With a single worker constraints are
+/// solved sequentially as soon as they are generated. Workers are only used by the `fixpoint`
+/// backend; the native backend always solves constraints as soon as they are generated.
+pub fn jobs() -> usize {
+    CONFIG.jobs.max(1)
+}
+
 pub fn catch_bugs() -> bool {
     CONFIG.catch_bugs
 }
@@ -77,6 +123,7 @@ struct Config {
     dump_constraint: bool,
     dump_checker_trace: bool,
     dump_timings: bool,
+    dump_stats: bool,
     dump_fhir: bool,
     dump_rty: bool,
     dump_mir: bool,
@@ -85,10 +132,22 @@ struct Config {
     check_def: String,
     check_files: Paths,
     cache: bool,
-    cache_file: String,
+    cache_dir: PathBuf,
+    /// In megabytes
+    cache_max_size: u64,
     check_overflow: bool,
     scrape_quals: bool,
+    precise_bit_ops: bool,
+    check_raw_pointers: bool,
+    assume_panic_abort: bool,
     solver: SmtSolver,
+    /// In seconds
+    timeout: u64,
+    /// In megabytes
+    memory_limit: u64,
+    suggest_sigs: bool,
+    fixpoint_backend: FixpointBackend,
+    jobs: usize,
 }
 
 #[derive(Default)]
@@ -158,7 +217,23 @@ pub struct InferOpts {
     pub check_overflow: bool,
     /// Whether qualifiers should be scraped from the constraint.
     pub scrape_quals: bool,
+    /// Give precise refinements to bitwise and shift operators on primitive integers by encoding
+    /// them with the bit-vector theory.
+    pub precise_bit_ops: bool,
+    /// Check that dereferenced raw pointers are in bounds of their allocation.
+    pub check_raw_pointers: bool,
+    /// Assume panics abort instead of unwinding, i.e., don't check cleanup code.
+    pub assume_panic_abort: bool,
     pub solver: SmtSolver,
+    /// Maximum time in seconds spent solving a single constraint. Zero means no limit.
+    pub timeout: u64,
+    /// Maximum memory in megabytes used to solve a single constraint. Zero means no limit.
+    pub memory_limit: u64,
+    /// Whether to suggest a signature for functions without one, inferring a refinement for the
+    /// return type.
+    pub suggest_sigs: bool,
+    /// The backend used to solve the constraints.
+    pub fixpoint_backend: FixpointBackend,
 }
 
 impl From<PartialInferOpts> for InferOpts {
@@ -166,7 +241,14 @@ impl From<PartialInferOpts> for InferOpts {
         InferOpts {
             check_overflow: opts.check_overflow.unwrap_or_else(check_overflow),
             scrape_quals: opts.scrape_quals.unwrap_or_else(scrape_quals),
+            precise_bit_ops: opts.precise_bit_ops.unwrap_or_else(precise_bit_ops),
+            check_raw_pointers: opts.check_raw_pointers.unwrap_or_else(check_raw_pointers),
+            assume_panic_abort: opts.assume_panic_abort.unwrap_or_else(assume_panic_abort),
             solver: opts.solver.unwrap_or_else(solver),
+            timeout: opts.timeout.unwrap_or_else(timeout),
+            memory_limit: opts.memory_limit.unwrap_or_else(memory_limit),
+            suggest_sigs: opts.suggest_sigs.unwrap_or_else(suggest_sigs),
+            fixpoint_backend: opts.fixpoint_backend.unwrap_or_else(fixpoint_backend),
         }
     }
 }
@@ -175,14 +257,28 @@ impl From<PartialInferOpts> for InferOpts {
 pub struct PartialInferOpts {
     pub check_overflow: Option<bool>,
     pub scrape_quals: Option<bool>,
+    pub precise_bit_ops: Option<bool>,
+    pub check_raw_pointers: Option<bool>,
+    pub assume_panic_abort: Option<bool>,
     pub solver: Option<SmtSolver>,
+    pub timeout: Option<u64>,
+    pub memory_limit: Option<u64>,
+    pub suggest_sigs: Option<bool>,
+    pub fixpoint_backend: Option<FixpointBackend>,
 }
 
 impl PartialInferOpts {
     pub fn merge(&mut self, other: &Self) {
         self.check_overflow = self.check_overflow.or(other.check_overflow);
         self.scrape_quals = self.scrape_quals.or(other.scrape_quals);
+        self.precise_bit_ops = self.precise_bit_ops.or(other.precise_bit_ops);
+        self.check_raw_pointers = self.check_raw_pointers.or(other.check_raw_pointers);
+        self.assume_panic_abort = self.assume_panic_abort.or(other.assume_panic_abort);
         self.solver = self.solver.or(other.solver);
+        self.timeout = self.timeout.or(other.timeout);
+        self.memory_limit = self.memory_limit.or(other.memory_limit);
+        self.suggest_sigs = self.suggest_sigs.or(other.suggest_sigs);
+        self.fixpoint_backend = self.fixpoint_backend.or(other.fixpoint_backend);
     }
 }
 
@@ -215,6 +311,47 @@ impl TryFrom<String> for SmtSolver {
     }
 }
 
+/// Which implementation is used to solve fixpoint constraints
+#[derive(Clone, Copy, Debug, Deserialize, Default)]
+#[serde(try_from = "String")]
+pub enum FixpointBackend {
+    /// The external `fixpoint` binary
+    #[default]
+    Fixpoint,
+    /// The in-process solver in `liquid-fixpoint` which only requires an SMT solver
+    Native,
+}
+
+impl FromStr for FixpointBackend {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        match s.as_str() {
+            "fixpoint" => Ok(FixpointBackend::Fixpoint),
+            "native" => Ok(FixpointBackend::Native),
+            _ => Err("fixpoint backend must be one of `fixpoint` or `native`"),
+        }
+    }
+}
+
+impl fmt::Display for FixpointBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FixpointBackend::Fixpoint => write!(f, "fixpoint"),
+            FixpointBackend::Native => write!(f, "native"),
+        }
+    }
+}
+
+impl TryFrom<String> for FixpointBackend {
+    type Error = &'static str;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 static CONFIG: LazyLock<Config> = LazyLock::new(|| {
     fn build() -> Result<Config, config::ConfigError> {
         let mut config_builder = config::Config::builder()
@@ -223,6 +360,7 @@ static CONFIG: LazyLock<Config> = LazyLock::new(|| {
             .set_default("dump_constraint", false)?
             .set_default("dump_checker_trace", false)?
             .set_default("dump_timings", false)?
+            .set_default("dump_stats", false)?
             .set_default("dump_mir", false)?
             .set_default("dump_fhir", false)?
             .set_default("dump_rty", false)?
@@ -231,10 +369,19 @@ static CONFIG: LazyLock<Config> = LazyLock::new(|| {
             .set_default("check_def", "")?
             .set_default("check_files", "")?
             .set_default("cache", false)?
-            .set_default("cache_file", "cache.json")?
+            .set_default("cache_dir", "cache")?
+            .set_default("cache_max_size", 512)?
             .set_default("check_overflow", false)?
             .set_default("scrape_quals", false)?
-            .set_default("solver", "z3")?;
+            .set_default("precise_bit_ops", false)?
+            .set_default("check_raw_pointers", false)?
+            .set_default("assume_panic_abort", false)?
+            .set_default("solver", "z3")?
+            .set_default("timeout", 0)?
+            .set_default("memory_limit", 0)?
+            .set_default("suggest_sigs", false)?
+            .set_default("fixpoint_backend", "fixpoint")?
+            .set_default("jobs", 1)?;
 
         // Config comes first, environment settings override it.
         if let Some(config_path) = CONFIG_PATH.as_ref() {
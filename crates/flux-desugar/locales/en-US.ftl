@@ -7,6 +7,10 @@ desugar_invalid_alias_reft =
 desugar_int_too_large =
     integer literal is too large
 
+desugar_invalid_real_lit =
+    invalid real literal
+    .note = real literals must be written in decimal notation without an exponent
+
 desugar_unexpected_literal =
     unexpected literal
 
@@ -20,6 +24,10 @@ desugar_invalid_func_as_var =
     invalid use of function
     .label = function not supported in this position
 
+desugar_unsupported_fn_ptr =
+    unsupported function pointer type
+    .label = function pointers are only supported in the signature of a Rust item
+
 desugar_invalid_func =
     invalid name in function position
     .label = expected a function or parameter
@@ -61,6 +69,10 @@ desugar_unresolved_var =
     cannot find value `{$var}` in this scope
     .label = not found in this scope
 
+desugar_unresolved_ctor =
+    cannot find variant `{$ctor}` of any data sort
+    .label = not found
+
 desugar_unresolved_sort =
     cannot find sort `{$name}` in this scope
     .label = not found in this scope
@@ -6,7 +6,7 @@ use flux_errors::FluxSession;
 use flux_middle::{
     fhir::{self, lift::LiftCtxt, ExprRes, FhirId, FluxOwnerId, Res},
     global_env::GlobalEnv,
-    try_alloc_slice, MaybeExternId, ResolverOutput,
+    rty, try_alloc_slice, MaybeExternId, ResolverOutput,
 };
 use flux_syntax::{
     surface::{self, visit::Visitor as _, ConstructorArg, NodeId},
@@ -24,6 +24,7 @@ use rustc_span::{
     symbol::kw,
     Span, Symbol, DUMMY_SP,
 };
+use rustc_target::spec::abi;
 
 type Result<T = ()> = std::result::Result<T, ErrorGuaranteed>;
 
@@ -64,6 +65,24 @@ pub(crate) fn desugar_spec_func<'genv>(
     Ok(fhir::SpecFunc { name, params, args, sort, body })
 }
 
+pub(crate) fn desugar_data_sort<'genv>(
+    genv: GlobalEnv<'genv, '_>,
+    resolver_output: &'genv ResolverOutput,
+    name: surface::Ident,
+    variants: &[surface::SortVariant],
+) -> fhir::DataSortDecl<'genv> {
+    let variants = genv.alloc_slice_fill_iter(variants.iter().map(|variant| {
+        let fields = genv.alloc_slice_fill_iter(
+            variant
+                .fields
+                .iter()
+                .map(|sort| desugar_base_sort(genv, resolver_output, sort, None)),
+        );
+        fhir::DataSortVariant { name: variant.name, fields, span: variant.span }
+    }));
+    fhir::DataSortDecl { name: name.name, variants, span: name.span }
+}
+
 /// Collect all sorts resolved to a generic type in a list of refinement parameters. Return the set
 /// of generic def_ids used (sorted by their position in the list of generics).
 fn collect_generics_in_params(
@@ -835,6 +854,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
         node_id: NodeId,
         bounds: &[surface::TraitRef],
     ) -> Result<fhir::TyKind<'genv>>;
+    fn desugar_bare_fn(&mut self, fn_sig: &surface::FnSig) -> Result<fhir::TyKind<'genv>>;
 
     fn sess(&self) -> &'genv FluxSession {
         self.genv().sess()
@@ -881,7 +901,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
     #[track_caller]
     fn desugar_func(&self, func: surface::Ident, node_id: NodeId) -> Result<fhir::PathExpr<'genv>> {
         let res = self.resolver_output().expr_path_res_map[&node_id];
-        if let ExprRes::Param(..) | ExprRes::GlobalFunc(..) = res {
+        if let ExprRes::Param(..) | ExprRes::GlobalFunc(..) | ExprRes::DataCtor { .. } = res {
             let segments = self.genv().alloc_slice(&[func]);
             Ok(fhir::PathExpr { segments, res, fhir_id: self.next_fhir_id(), span: func.span })
         } else {
@@ -1092,6 +1112,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
             surface::TyKind::ImplTrait(node_id, bounds) => {
                 self.desugar_impl_trait(*node_id, bounds)?
             }
+            surface::TyKind::BareFn(fn_sig) => self.desugar_bare_fn(fn_sig)?,
             surface::TyKind::Hole => fhir::TyKind::Infer,
         };
         Ok(fhir::Ty { kind, span })
@@ -1116,6 +1137,11 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                 let kind = fhir::BaseTyKind::Slice(self.genv().alloc(ty));
                 Ok(fhir::BaseTy { kind, fhir_id: self.next_fhir_id(), span: bty.span })
             }
+            surface::BaseTyKind::RawPtr(mutbl, ty) => {
+                let ty = self.desugar_ty(ty)?;
+                let kind = fhir::BaseTyKind::RawPtr(self.genv().alloc(ty), *mutbl);
+                Ok(fhir::BaseTy { kind, fhir_id: self.next_fhir_id(), span: bty.span })
+            }
         }
     }
 
@@ -1383,11 +1409,48 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                 };
                 fhir::ExprKind::Constructor(path, field_exprs, spread)
             }
+            surface::ExprKind::Quant(kind, params, box body) => {
+                let body = self.genv().alloc(self.desugar_expr(body)?);
+                let params = self.desugar_refine_params(params);
+                fhir::ExprKind::Quant(*kind, params, body)
+            }
+            surface::ExprKind::Let(param, box [init, body]) => {
+                let init = self.desugar_expr(init);
+                let body = self.desugar_expr(body);
+                let param = self.desugar_refine_param(param);
+                fhir::ExprKind::Let(
+                    self.genv().alloc(param),
+                    self.genv().alloc(init?),
+                    self.genv().alloc(body?),
+                )
+            }
+            surface::ExprKind::Match(box scrutinee, arms) => {
+                let scrutinee = self.desugar_expr(scrutinee);
+                let arms = try_alloc_slice!(self.genv(), arms, |arm| self.desugar_match_arm(arm));
+                fhir::ExprKind::Match(self.genv().alloc(scrutinee?), arms?)
+            }
         };
 
         Ok(fhir::Expr { kind, span: expr.span, fhir_id: self.next_fhir_id() })
     }
 
+    fn desugar_match_arm(&mut self, arm: &surface::MatchArm) -> Result<fhir::MatchArm<'genv>> {
+        let body = self.desugar_expr(&arm.body)?;
+        let pat = match &arm.pat {
+            surface::MatchPat::Ctor { ctor, params, node_id } => {
+                let path = fhir::PathExpr {
+                    segments: self.genv().alloc_slice(&[*ctor]),
+                    res: self.resolver_output().expr_path_res_map[node_id],
+                    fhir_id: self.next_fhir_id(),
+                    span: ctor.span,
+                };
+                fhir::MatchPat::Ctor(path, self.desugar_refine_params(params))
+            }
+            surface::MatchPat::Wildcard(span) => fhir::MatchPat::Wildcard(*span),
+        };
+        Ok(fhir::MatchArm { pat, body, span: arm.span })
+    }
+
     fn desugar_constructor_path(&self, path: &surface::ExprPath) -> Result<fhir::PathExpr<'genv>> {
         let res = self.resolver_output().expr_path_res_map[&path.node_id];
         if let ExprRes::Ctor(..) = res {
@@ -1433,13 +1496,32 @@ trait DesugarCtxt<'genv, 'tcx: 'genv> {
                 if suffix == SORTS.int {
                     Ok(fhir::Lit::Int(n))
                 } else if suffix == SORTS.real {
-                    Ok(fhir::Lit::Real(n))
+                    Ok(fhir::Lit::Real(rty::Real::from_int(n)))
                 } else {
                     Err(self.emit_err(errors::InvalidNumericSuffix::new(span, suffix)))
                 }
             }
+            surface::LitKind::Float => {
+                if let Some(suffix) = lit.suffix
+                    && suffix != SORTS.real
+                {
+                    return Err(self.emit_err(errors::InvalidNumericSuffix::new(span, suffix)));
+                }
+                let Some(r) = rty::Real::from_decimal_str(lit.symbol.as_str()) else {
+                    return Err(self.emit_err(errors::InvalidRealLit { span }));
+                };
+                Ok(fhir::Lit::Real(r))
+            }
             surface::LitKind::Bool => Ok(fhir::Lit::Bool(lit.symbol == kw::True)),
-            surface::LitKind::Str => Ok(fhir::Lit::Str(lit.symbol)),
+            surface::LitKind::Str => {
+                // The symbol in the token is the literal as written in the source, so we have to
+                // unescape it to get its contents.
+                if let Ok(rustc_ast::LitKind::Str(s, _)) = rustc_ast::LitKind::from_token_lit(lit) {
+                    Ok(fhir::Lit::Str(s))
+                } else {
+                    Err(self.emit_err(errors::UnexpectedLiteral { span }))
+                }
+            }
             surface::LitKind::Char => {
                 Ok(fhir::Lit::Char(lit.symbol.as_str().parse::<char>().unwrap()))
             }
@@ -1538,6 +1620,22 @@ impl<'genv, 'tcx> DesugarCtxt<'genv, 'tcx> for RustItemCtxt<'_, 'genv, 'tcx> {
 
         Ok(fhir::TyKind::OpaqueDef(opaque_ty))
     }
+
+    /// Function pointers written in the surface syntax are always safe and use the Rust ABI.
+    fn desugar_bare_fn(&mut self, fn_sig: &surface::FnSig) -> Result<fhir::TyKind<'genv>> {
+        let inputs = try_alloc_slice!(self.genv, &fn_sig.inputs, |arg| self.desugar_fn_input(arg))?;
+        let output = self.desugar_fn_output(fn_sig.asyncness, &fn_sig.output)?;
+        let decl = fhir::FnDecl { requires: &[], inputs, output, span: fn_sig.span, lifted: false };
+        let bare_fn = fhir::BareFnTy {
+            safety: hir::Safety::Safe,
+            abi: abi::Abi::Rust,
+            generic_params: &[],
+            refinement_params: self.desugar_fn_sig_refine_params(fn_sig),
+            decl: self.genv.alloc(decl),
+            param_names: &[],
+        };
+        Ok(fhir::TyKind::BareFn(self.genv.alloc(bare_fn)))
+    }
 }
 
 impl<'genv, 'tcx> DesugarCtxt<'genv, 'tcx> for FluxItemCtxt<'genv, 'tcx> {
@@ -1560,4 +1658,8 @@ impl<'genv, 'tcx> DesugarCtxt<'genv, 'tcx> for FluxItemCtxt<'genv, 'tcx> {
     ) -> Result<fhir::TyKind<'genv>> {
         unimplemented!("`impl Trait` not supported in this item")
     }
+
+    fn desugar_bare_fn(&mut self, fn_sig: &surface::FnSig) -> Result<fhir::TyKind<'genv>> {
+        Err(self.emit_err(errors::UnsupportedFnPtr { span: fn_sig.span }))
+    }
 }
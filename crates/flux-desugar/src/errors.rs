@@ -10,6 +10,14 @@ pub(super) struct IntTooLarge {
     pub(super) span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(desugar_invalid_real_lit, code = E0999)]
+#[note]
+pub(super) struct InvalidRealLit {
+    #[primary_span]
+    pub(super) span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(desugar_unexpected_literal, code = E0999)]
 pub(super) struct UnexpectedLiteral {
@@ -39,6 +47,14 @@ pub(super) struct InvalidFuncAsVar {
     pub(super) span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(desugar_unsupported_fn_ptr, code = E0999)]
+pub(super) struct UnsupportedFnPtr {
+    #[primary_span]
+    #[label]
+    pub(super) span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(desugar_invalid_func, code = E0999)]
 pub(super) struct InvalidFunc {
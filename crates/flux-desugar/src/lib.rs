@@ -2,12 +2,14 @@
 
 #![feature(rustc_private, min_specialization, box_patterns, let_chains, never_type)]
 
+extern crate rustc_ast;
 extern crate rustc_data_structures;
 extern crate rustc_errors;
 
 extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
+extern crate rustc_target;
 
 use desugar::RustItemCtxt;
 use flux_common::{
@@ -298,7 +300,19 @@ impl CrateDesugar<'_, '_> {
                 surface::Item::FuncDef(defn) => {
                     self.desugar_func_defn(defn).collect_err(&mut self.err);
                 }
-                surface::Item::SortDecl(_) => {}
+                surface::Item::SortDecl(sort_decl) => {
+                    if let surface::SortDeclKind::Data(variants) = &sort_decl.kind {
+                        let data_sort = desugar::desugar_data_sort(
+                            self.genv,
+                            self.resolver_output,
+                            sort_decl.name,
+                            variants,
+                        );
+                        self.fhir
+                            .items
+                            .insert(data_sort.name, fhir::FluxItem::DataSort(data_sort));
+                    }
+                }
             }
         }
     }
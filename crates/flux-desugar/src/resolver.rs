@@ -54,6 +54,8 @@ pub(crate) struct CrateResolver<'genv, 'tcx> {
     prelude: PerNS<Rib>,
     func_decls: UnordMap<Symbol, fhir::SpecFuncKind>,
     sort_decls: UnordMap<Symbol, fhir::SortDecl>,
+    /// Maps the name of each variant of a data sort to the name of the sort
+    data_ctors: UnordMap<Symbol, Symbol>,
     err: Option<ErrorGuaranteed>,
     /// The most recent module we have visited. Used to check for visibility of other items from
     /// this module.
@@ -76,6 +78,7 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
             err: None,
             func_decls: Default::default(),
             sort_decls: Default::default(),
+            data_ctors: Default::default(),
             current_module: CRATE_OWNER_ID,
         }
     }
@@ -93,14 +96,32 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
                     self.func_decls.insert(defn.name.name, kind);
                 }
                 surface::Item::SortDecl(sort_decl) => {
+                    let is_data = matches!(sort_decl.kind, surface::SortDeclKind::Data(_));
                     self.sort_decls.insert(
                         sort_decl.name.name,
-                        fhir::SortDecl { name: sort_decl.name.name, span: sort_decl.name.span },
+                        fhir::SortDecl {
+                            name: sort_decl.name.name,
+                            span: sort_decl.name.span,
+                            is_data,
+                        },
                     );
+                    if let surface::SortDeclKind::Data(variants) = &sort_decl.kind {
+                        for variant in variants {
+                            self.data_ctors
+                                .insert(variant.name.name, sort_decl.name.name);
+                        }
+                    }
                 }
             }
         }
 
+        for def_id in &self.specs.reflected {
+            let name = self.genv.tcx().item_name(def_id.to_def_id());
+            self.func_decls
+                .entry(name)
+                .or_insert(fhir::SpecFuncKind::Def);
+        }
+
         self.func_decls.extend_unord(
             flux_middle::THEORY_FUNCS
                 .items()
@@ -205,7 +226,10 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
                 surface::Item::FuncDef(defn) => {
                     RefinementResolver::resolve_defn(self, defn).collect_err(&mut self.err);
                 }
-                surface::Item::SortDecl(_) => {}
+                surface::Item::SortDecl(sort_decl) => {
+                    RefinementResolver::resolve_sort_decl(self, sort_decl)
+                        .collect_err(&mut self.err);
+                }
             }
         }
     }
@@ -20,7 +20,7 @@ use rustc_hir::def::{
     Namespace::{TypeNS, ValueNS},
 };
 use rustc_middle::ty::TyCtxt;
-use rustc_span::{sym, ErrorGuaranteed, Symbol};
+use rustc_span::{sym, symbol::kw, ErrorGuaranteed, Symbol};
 
 use super::{CrateResolver, Segment};
 
@@ -72,6 +72,7 @@ pub(crate) trait ScopedVisitor: Sized {
     fn on_loc(&mut self, _loc: Ident, _node_id: NodeId) {}
     fn on_func(&mut self, _func: Ident, _node_id: NodeId) {}
     fn on_path(&mut self, _path: &surface::ExprPath) {}
+    fn on_match_ctor(&mut self, _ctor: Ident, _node_id: NodeId) {}
     fn on_base_sort(&mut self, _sort: &surface::BaseSort) {}
 }
 
@@ -265,7 +266,7 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
                     surface::visit::walk_ty(this, ty);
                 });
             }
-            surface::TyKind::Array(..) => {
+            surface::TyKind::Array(..) | surface::TyKind::BareFn(..) => {
                 self.with_scope(ScopeKind::Misc, |this| {
                     surface::visit::walk_ty(this, ty);
                 });
@@ -276,7 +277,7 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
 
     fn visit_bty(&mut self, bty: &surface::BaseTy) {
         match &bty.kind {
-            surface::BaseTyKind::Slice(_) => {
+            surface::BaseTyKind::Slice(_) | surface::BaseTyKind::RawPtr(..) => {
                 self.with_scope(ScopeKind::Misc, |this| {
                     surface::visit::walk_bty(this, bty);
                 });
@@ -288,10 +289,34 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
     }
 
     fn visit_expr(&mut self, expr: &surface::Expr) {
-        if let surface::ExprKind::App(func, _) = &expr.kind {
-            self.on_func(*func, expr.node_id);
+        match &expr.kind {
+            surface::ExprKind::App(func, _) => {
+                self.on_func(*func, expr.node_id);
+                surface::visit::walk_expr(self, expr);
+            }
+            surface::ExprKind::Quant(..) => {
+                self.with_scope(ScopeKind::Misc, |this| {
+                    surface::visit::walk_expr(this, expr);
+                });
+            }
+            surface::ExprKind::Let(param, box [init, body]) => {
+                self.visit_expr(init);
+                self.with_scope(ScopeKind::Misc, |this| {
+                    this.visit_refine_param(param);
+                    this.visit_expr(body);
+                });
+            }
+            _ => surface::visit::walk_expr(self, expr),
+        }
+    }
+
+    fn visit_match_arm(&mut self, arm: &surface::MatchArm) {
+        if let surface::MatchPat::Ctor { ctor, node_id, .. } = &arm.pat {
+            self.on_match_ctor(*ctor, *node_id);
         }
-        surface::visit::walk_expr(self, expr);
+        self.with_scope(ScopeKind::Misc, |this| {
+            surface::visit::walk_match_arm(this, arm);
+        });
     }
 
     fn visit_path_expr(&mut self, path: &surface::ExprPath) {
@@ -404,6 +429,13 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
         Self::for_flux_item(resolver, &defn.sort_vars).run(|r| r.visit_defn(defn))
     }
 
+    pub(crate) fn resolve_sort_decl(
+        resolver: &'a mut CrateResolver<'genv, 'tcx>,
+        sort_decl: &surface::SortDecl,
+    ) -> Result {
+        Self::for_flux_item(resolver, &[]).run(|r| r.visit_sort_decl(sort_decl))
+    }
+
     pub(crate) fn resolve_fn_sig(
         resolver: &'a mut CrateResolver<'genv, 'tcx>,
         fn_sig: &surface::FnSig,
@@ -488,6 +520,11 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
         self.param_defs
             .insert(param_id, ParamDef { ident, kind, scope });
 
+        // A `_` (e.g., in a match pattern) defines a parameter that cannot be referenced
+        if ident.name == kw::Underscore {
+            return;
+        }
+
         let scope = self.scopes.last_mut().unwrap();
         match scope.bindings.entry(ident) {
             IndexEntry::Occupied(entry) => {
@@ -533,7 +570,9 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             return;
         }
         if let [segment] = &path.segments[..]
-            && let Some(res) = self.try_resolve_global_func(segment.ident)
+            && let Some(res) = self
+                .try_resolve_data_ctor(segment.ident)
+                .or_else(|| self.try_resolve_global_func(segment.ident))
         {
             self.path_res_map.insert(path.node_id, res);
             return;
@@ -550,7 +589,10 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             self.path_res_map.insert(node_id, res);
             return;
         }
-        if let Some(res) = self.try_resolve_global_func(ident) {
+        if let Some(res) = self
+            .try_resolve_data_ctor(ident)
+            .or_else(|| self.try_resolve_global_func(ident))
+        {
             self.path_res_map.insert(node_id, res);
             return;
         }
@@ -585,6 +627,11 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
         Some(ExprRes::Param(res.kind(), res.param_id()))
     }
 
+    fn try_resolve_data_ctor(&self, ident: Ident) -> Option<ExprRes<NodeId>> {
+        let sort = self.resolver.data_ctors.get(&ident.name)?;
+        Some(ExprRes::DataCtor { sort: *sort, ctor: ident.name })
+    }
+
     fn try_resolve_global_func(&mut self, ident: Ident) -> Option<ExprRes<NodeId>> {
         let kind = self.resolver.func_decls.get(&ident.name)?;
         Some(ExprRes::GlobalFunc(*kind, ident.name))
@@ -639,10 +686,13 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
 
     fn try_resolve_user_sort(&self, path: &surface::SortPath) -> Option<fhir::SortRes> {
         let [segment] = &path.segments[..] else { return None };
-        self.resolver
-            .sort_decls
-            .get(&segment.name)
-            .map(|decl| fhir::SortRes::User { name: decl.name })
+        self.resolver.sort_decls.get(&segment.name).map(|decl| {
+            if decl.is_data {
+                fhir::SortRes::Data { name: decl.name }
+            } else {
+                fhir::SortRes::User { name: decl.name }
+            }
+        })
     }
 
     fn try_resolve_prim_sort(&self, path: &surface::SortPath) -> Option<fhir::SortRes> {
@@ -657,6 +707,8 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Set))
         } else if segment.name == SORTS.map {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Map))
+        } else if segment.name == SORTS.seq {
+            Some(fhir::SortRes::PrimSort(fhir::PrimSort::Seq))
         } else {
             None
         }
@@ -784,6 +836,14 @@ impl ScopedVisitor for RefinementResolver<'_, '_, '_> {
         self.resolve_path(path);
     }
 
+    fn on_match_ctor(&mut self, ctor: Ident, node_id: NodeId) {
+        if let Some(res) = self.try_resolve_data_ctor(ctor) {
+            self.path_res_map.insert(node_id, res);
+        } else {
+            self.errors.emit(errors::UnresolvedCtor::new(ctor));
+        }
+    }
+
     fn on_base_sort(&mut self, sort: &surface::BaseSort) {
         match sort {
             surface::BaseSort::Path(path) => {
@@ -820,6 +880,7 @@ pub(crate) struct Sorts {
     pub real: Symbol,
     pub set: Symbol,
     pub map: Symbol,
+    pub seq: Symbol,
 }
 
 pub(crate) static SORTS: std::sync::LazyLock<Sorts> = std::sync::LazyLock::new(|| {
@@ -828,6 +889,7 @@ pub(crate) static SORTS: std::sync::LazyLock<Sorts> = std::sync::LazyLock::new(|
         real: Symbol::intern("real"),
         set: Symbol::intern("Set"),
         map: Symbol::intern("Map"),
+        seq: Symbol::intern("Seq"),
     }
 });
 
@@ -968,6 +1030,21 @@ mod errors {
         }
     }
 
+    #[derive(Diagnostic)]
+    #[diag(desugar_unresolved_ctor, code = E0999)]
+    pub(super) struct UnresolvedCtor {
+        #[primary_span]
+        #[label]
+        span: Span,
+        ctor: Ident,
+    }
+
+    impl UnresolvedCtor {
+        pub(super) fn new(ctor: Ident) -> Self {
+            Self { span: ctor.span, ctor }
+        }
+    }
+
     #[derive(Diagnostic)]
     #[diag(desugar_invalid_unrefined_param, code = E0999)]
     pub(super) struct InvalidUnrefinedParam {
@@ -22,6 +22,16 @@ driver_missing_variant =
     .label = this variant doesn't have a refinement annotation
     .note = all variants in a refined enum must be annotated
 
+driver_reflect_with_sig =
+    reflected functions cannot have a refinement signature
+    .note = the signature of a reflected function is derived from its body
+
+driver_reflect_on_assoc_fn =
+    only free functions can be reflected
+
+driver_stats_report_failed =
+    failed to write statistics report to `{$path}`: {$err}
+
 
 driver_mismatched_spec_name =
     name in {$def_descr} spec doesn't match item's name
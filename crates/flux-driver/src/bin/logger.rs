@@ -35,7 +35,11 @@ pub fn install() -> io::Result<impl FnOnce() -> io::Result<()>> {
             tracing_subscriber::fmt::layer()
                 .with_writer(writer)
                 .json()
-                .with_filter(Targets::new().with_target("flux_refineck::checker", Level::DEBUG)),
+                .with_filter(
+                    Targets::new()
+                        .with_target("flux_refineck::checker", Level::DEBUG)
+                        .with_target("flux_infer::fixpoint_encoding", Level::INFO),
+                ),
         );
     }
 
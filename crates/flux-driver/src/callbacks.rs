@@ -4,7 +4,7 @@ use flux_errors::FluxSession;
 use flux_infer::fixpoint_encoding::FixQueryCache;
 use flux_metadata::CStore;
 use flux_middle::{fhir, global_env::GlobalEnv, queries::Providers, Specs};
-use flux_refineck as refineck;
+use flux_refineck::{self as refineck, stats::CheckStats, DeferredChecks, FixpointMode};
 use itertools::Itertools;
 use rustc_borrowck::consumers::ConsumerOptions;
 use rustc_driver::{Callbacks, Compilation};
@@ -20,6 +20,8 @@ use rustc_span::FileName;
 
 use crate::{collector::SpecCollector, DEFAULT_LOCALE_RESOURCES};
 
+const STATS_FILE: &str = "stats.json";
+
 #[derive(Default)]
 pub struct FluxCallbacks {
     pub full_compilation: bool,
@@ -100,7 +102,17 @@ fn check_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
             .definitions()
             .try_for_each_exhaust(|def_id| ck.check_def_catching_bugs(def_id));
 
-        ck.cache.save().unwrap_or(());
+        let result = result.and(ck.report_deferred_catching_bugs());
+
+        ck.cache.evict().unwrap_or(());
+
+        if config::dump_stats() {
+            let path = config::log_dir().join(STATS_FILE);
+            if let Err(err) = ck.stats.write_report(genv, &path) {
+                genv.sess()
+                    .emit_warn(errors::StatsReportFailed { path, err: err.to_string() });
+            }
+        }
 
         tracing::info!("Callbacks::check_crate");
 
@@ -135,11 +147,17 @@ fn encode_and_save_metadata(genv: GlobalEnv) {
 struct CrateChecker<'genv, 'tcx> {
     genv: GlobalEnv<'genv, 'tcx>,
     cache: FixQueryCache,
+    /// Functions whose constraints are being solved in the background. This is `None` when
+    /// constraints are solved sequentially.
+    deferred: Option<DeferredChecks>,
+    stats: CheckStats,
 }
 
 impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
     fn new(genv: GlobalEnv<'genv, 'tcx>) -> Self {
-        CrateChecker { genv, cache: QueryCache::load() }
+        let jobs = config::jobs();
+        let deferred = if jobs > 1 { Some(DeferredChecks::new(jobs)) } else { None };
+        CrateChecker { genv, cache: QueryCache::load(), deferred, stats: CheckStats::default() }
     }
 
     fn matches_check_def(&self, def_id: DefId) -> bool {
@@ -172,6 +190,18 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
         flux_common::bug::catch_bugs(&msg, move || this.check_def(def_id))?
     }
 
+    fn report_deferred_catching_bugs(&mut self) -> Result<(), ErrorGuaranteed> {
+        let mut this = std::panic::AssertUnwindSafe(self);
+        flux_common::bug::catch_bugs("solving deferred constraints", move || {
+            this.report_deferred()
+        })?
+    }
+
+    fn report_deferred(&mut self) -> Result<(), ErrorGuaranteed> {
+        let Some(deferred) = self.deferred.take() else { return Ok(()) };
+        deferred.report(self.genv, &mut self.cache, &mut self.stats)
+    }
+
     fn check_def(&mut self, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
         let def_id = self.genv.maybe_extern_id(def_id);
 
@@ -187,7 +217,11 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
 
         match self.genv.def_kind(def_id) {
             DefKind::Fn | DefKind::AssocFn => {
-                refineck::check_fn(self.genv, &mut self.cache, def_id)
+                let mode = match &mut self.deferred {
+                    Some(deferred) => FixpointMode::Deferred(deferred),
+                    None => FixpointMode::Immediate,
+                };
+                refineck::check_fn(self.genv, &mut self.cache, def_id, mode, &mut self.stats)
             }
             DefKind::Enum => {
                 let adt_def = self.genv.adt_def(def_id).emit(&self.genv)?;
@@ -204,6 +238,7 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
                     def_id,
                     enum_def.invariants,
                     &adt_def,
+                    &mut self.stats,
                 )
             }
             DefKind::Struct => {
@@ -224,6 +259,7 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
                     def_id,
                     struct_def.invariants,
                     &adt_def,
+                    &mut self.stats,
                 )
             }
             DefKind::Impl { of_trait } => {
@@ -274,3 +310,16 @@ fn mir_borrowck<'tcx>(
     let original_mir_borrowck = providers.mir_borrowck;
     original_mir_borrowck(tcx, def_id)
 }
+
+mod errors {
+    use std::path::PathBuf;
+
+    use flux_macros::Diagnostic;
+
+    #[derive(Diagnostic)]
+    #[diag(driver_stats_report_failed)]
+    pub(super) struct StatsReportFailed {
+        pub path: PathBuf,
+        pub err: String,
+    }
+}
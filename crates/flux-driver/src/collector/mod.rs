@@ -8,7 +8,7 @@ use flux_common::{
     result::{ErrorCollector, ResultExt},
     tracked_span_assert_eq,
 };
-use flux_config::{self as config, PartialInferOpts, SmtSolver};
+use flux_config::{self as config, FixpointBackend, PartialInferOpts, SmtSolver};
 use flux_errors::{Errors, FluxSession};
 use flux_middle::{
     fhir::{Ignored, Trusted},
@@ -103,6 +103,9 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
 
         match &item.kind {
             ItemKind::Fn(..) => {
+                if attrs.reflect() {
+                    self.collect_reflected_fn(owner_id, &attrs)?;
+                }
                 self.collect_fn_spec(owner_id, attrs)?;
             }
             ItemKind::Struct(variant, ..) => {
@@ -148,6 +151,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         self.collect_ignore_and_trusted(&mut attrs, owner_id.def_id);
         self.collect_infer_opts(&mut attrs, owner_id.def_id);
         if let rustc_hir::TraitItemKind::Fn(_, _) = trait_item.kind {
+            self.check_not_reflected(owner_id, &attrs)?;
             self.collect_fn_spec(owner_id, attrs)?;
         }
         hir::intravisit::walk_trait_item(self, trait_item);
@@ -162,6 +166,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         self.collect_infer_opts(&mut attrs, owner_id.def_id);
 
         if let ImplItemKind::Fn(..) = &impl_item.kind {
+            self.check_not_reflected(owner_id, &attrs)?;
             self.collect_fn_spec(owner_id, attrs)?;
         }
         hir::intravisit::walk_impl_item(self, impl_item);
@@ -317,6 +322,25 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         self.parse_constant_spec(owner_id, attrs)
     }
 
+    fn collect_reflected_fn(&mut self, owner_id: OwnerId, attrs: &FluxAttrs) -> Result {
+        if attrs.has_fn_sig() {
+            let span = self.tcx.def_ident_span(owner_id.def_id).unwrap();
+            return Err(self.errors.emit(errors::ReflectWithSig { span }));
+        }
+        self.specs.reflected.insert(owner_id.def_id);
+        Ok(())
+    }
+
+    /// Only free functions can be reflected because reflected functions are referred to by their
+    /// (unqualified) name in the refinement logic.
+    fn check_not_reflected(&mut self, owner_id: OwnerId, attrs: &FluxAttrs) -> Result {
+        if attrs.reflect() {
+            let span = self.tcx.def_ident_span(owner_id.def_id).unwrap();
+            return Err(self.errors.emit(errors::ReflectOnAssocFn { span }));
+        }
+        Ok(())
+    }
+
     fn collect_fn_spec(
         &mut self,
         owner_id: OwnerId,
@@ -472,6 +496,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             ("opaque", AttrArgs::Empty) => FluxAttrKind::Opaque,
             ("extern_spec", AttrArgs::Empty) => FluxAttrKind::ExternSpec,
             ("should_fail", AttrArgs::Empty) => FluxAttrKind::ShouldFail,
+            ("reflect", AttrArgs::Empty) => FluxAttrKind::Reflect,
             _ => return Err(invalid_attr_err(self)),
         };
         Ok(FluxAttr { kind, span: attr_item.span() })
@@ -598,6 +623,7 @@ enum FluxAttrKind {
     Invariant(surface::Expr),
     Ignore(Ignored),
     ShouldFail,
+    Reflect,
     ExternSpec,
 }
 
@@ -723,6 +749,14 @@ impl FluxAttrs {
     fn should_fail(&self) -> bool {
         read_flag!(self, ShouldFail)
     }
+
+    fn reflect(&self) -> bool {
+        read_flag!(self, Reflect)
+    }
+
+    fn has_fn_sig(&self) -> bool {
+        read_flag!(self, FnSig)
+    }
 }
 
 impl FluxAttrKind {
@@ -746,6 +780,7 @@ impl FluxAttrKind {
             FluxAttrKind::Ignore(_) => attr_name!(Ignore),
             FluxAttrKind::Invariant(_) => attr_name!(Invariant),
             FluxAttrKind::ShouldFail => attr_name!(ShouldFail),
+            FluxAttrKind::Reflect => attr_name!(Reflect),
             FluxAttrKind::ExternSpec => attr_name!(ExternSpec),
         }
     }
@@ -846,7 +881,14 @@ impl AttrMap {
         let mut infer_opts = PartialInferOpts::default();
         try_read_setting!(self, check_overflow, bool, infer_opts);
         try_read_setting!(self, scrape_quals, bool, infer_opts);
+        try_read_setting!(self, precise_bit_ops, bool, infer_opts);
+        try_read_setting!(self, check_raw_pointers, bool, infer_opts);
+        try_read_setting!(self, assume_panic_abort, bool, infer_opts);
         try_read_setting!(self, solver, SmtSolver, infer_opts);
+        try_read_setting!(self, timeout, u64, infer_opts);
+        try_read_setting!(self, memory_limit, u64, infer_opts);
+        try_read_setting!(self, suggest_sigs, bool, infer_opts);
+        try_read_setting!(self, fixpoint_backend, FixpointBackend, infer_opts);
 
         if let Some((name, setting)) = self.map.iter().next() {
             return Err(errors::AttrMapErr {
@@ -941,6 +983,21 @@ mod errors {
         }
     }
 
+    #[derive(Diagnostic)]
+    #[diag(driver_reflect_with_sig, code = E0999)]
+    #[note]
+    pub(super) struct ReflectWithSig {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_reflect_on_assoc_fn, code = E0999)]
+    pub(super) struct ReflectOnAssocFn {
+        #[primary_span]
+        pub span: Span,
+    }
+
     #[derive(Diagnostic)]
     #[diag(driver_mismatched_spec_name, code = E0999)]
     pub(super) struct MismatchedSpecName {
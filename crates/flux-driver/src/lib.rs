@@ -21,6 +21,7 @@ pub static DEFAULT_LOCALE_RESOURCES: &[&str] = &[
     DEFAULT_LOCALE_RESOURCE,
     flux_desugar::DEFAULT_LOCALE_RESOURCE,
     flux_fhir_analysis::DEFAULT_LOCALE_RESOURCE,
+    flux_infer::DEFAULT_LOCALE_RESOURCE,
     flux_metadata::DEFAULT_LOCALE_RESOURCE,
     flux_middle::DEFAULT_LOCALE_RESOURCE,
     flux_refineck::DEFAULT_LOCALE_RESOURCE,
@@ -50,6 +50,11 @@ impl FluxSession {
         self.parse_sess.dcx().emit_err(err)
     }
 
+    #[track_caller]
+    pub fn emit_warn<'a>(&'a self, warn: impl Diagnostic<'a, ()>) {
+        self.parse_sess.dcx().emit_warn(warn);
+    }
+
     #[track_caller]
     pub fn emit_fatal<'a>(&'a self, fatal: impl Diagnostic<'a, FatalAbort>) -> ! {
         self.parse_sess.dcx().emit_fatal(fatal)
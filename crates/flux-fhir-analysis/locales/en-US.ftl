@@ -67,6 +67,19 @@ fhir_analysis_field_not_found =
 fhir_analysis_constructor_missing_fields =
     missing fields in constructor: {$missing_fields}
 
+fhir_analysis_non_exhaustive_match =
+    non-exhaustive patterns in match
+    .label = variants not covered: {$missing}
+
+fhir_analysis_cannot_prove_termination =
+    cannot prove termination of `{$name}`
+    .label = no parameter decreases in this recursive call
+    .note = recursive calls must take a variable bound by matching on a parameter or decrease an integer parameter that is guarded to be large enough
+
+fhir_analysis_polymorphic_recursive_defn =
+    recursive function `{$name}` cannot be polymorphic
+    .label = recursive call
+
 fhir_analysis_duplicate_field_used =
     field `{$fld}` was previously used in constructor
     .help = field `{$fld}` previously used here, consider removing it
@@ -94,6 +107,11 @@ fhir_analysis_constant_annotation_needed =
     constant annotation required
     .label = help: non-integral constants need a `constant` annotation that specifies their refinement value
 
+fhir_analysis_invalid_bv_sizes =
+    invalid bit-vector sizes for `{$name}`
+    .label = instantiated with sizes {$sizes}
+    .note = extensions cannot shrink a bit-vector, extractions cannot grow it, and the size of a concatenation must be the sum of the sizes of its arguments
+
 fhir_analysis_cannot_infer_sort =
     sort annotation needed
     .label = cannot infer sort
@@ -154,6 +172,16 @@ fhir_analysis_definition_cycle =
     cycle in definitions
     .label = {$msg}
 
+# Reflection
+
+fhir_analysis_cannot_reflect =
+    cannot reflect `{$name}` into the refinement logic
+    .label = {$reason}
+
+fhir_analysis_duplicate_reflected_fn =
+    the name `{$name}` is used by more than one refinement function
+    .label = reflected function defined here
+
 # Conv errors
 
 fhir_analysis_assoc_type_not_found =
@@ -117,9 +117,13 @@ pub trait WfckResultsProvider: Sized {
 
     fn lambda_output(&self, fhir_id: FhirId) -> rty::Sort;
 
-    fn record_ctor(&self, fhir_id: FhirId) -> DefId;
+    /// The constructor of a record. Records checked against a tuple sort (e.g., the indices of a
+    /// raw pointer) don't have one.
+    fn record_ctor(&self, fhir_id: FhirId) -> Option<DefId>;
 
     fn param_sort(&self, param: &fhir::RefineParam) -> rty::Sort;
+
+    fn theory_func_instance(&self, fhir_id: FhirId) -> Option<Symbol>;
 }
 
 impl<'genv, 'tcx> ConvPhase<'genv, 'tcx> for AfterSortck<'_, 'genv, 'tcx> {
@@ -193,11 +197,8 @@ impl WfckResultsProvider for WfckResults {
             .clone()
     }
 
-    fn record_ctor(&self, fhir_id: FhirId) -> DefId {
-        *self
-            .record_ctors()
-            .get(fhir_id)
-            .unwrap_or_else(|| bug!("unelaborated record constructor `{fhir_id:?}`"))
+    fn record_ctor(&self, fhir_id: FhirId) -> Option<DefId> {
+        self.record_ctors().get(fhir_id).copied()
     }
 
     fn param_sort(&self, param: &fhir::RefineParam) -> rty::Sort {
@@ -206,6 +207,10 @@ impl WfckResultsProvider for WfckResults {
             .unwrap_or_else(|| bug!("unresolved sort for param `{param:?}`"))
             .clone()
     }
+
+    fn theory_func_instance(&self, fhir_id: FhirId) -> Option<Symbol> {
+        self.theory_func_instances().get(fhir_id).copied()
+    }
 }
 
 #[derive(Debug)]
@@ -779,6 +784,10 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 self.check_prim_sort_generics(path, fhir::PrimSort::Map)?;
                 rty::SortCtor::Map
             }
+            fhir::SortRes::PrimSort(fhir::PrimSort::Seq) => {
+                self.check_prim_sort_generics(path, fhir::PrimSort::Seq)?;
+                rty::SortCtor::Seq
+            }
             fhir::SortRes::User { name } => {
                 if !path.args.is_empty() {
                     let err = errors::GenericsOnUserDefinedOpaqueSort::new(
@@ -789,6 +798,16 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 }
                 rty::SortCtor::User { name }
             }
+            fhir::SortRes::Data { name } => {
+                if !path.args.is_empty() {
+                    let err = errors::GenericsOnUserDefinedOpaqueSort::new(
+                        path.segments.last().unwrap().span,
+                        path.args.len(),
+                    );
+                    Err(self.emit(err))?;
+                }
+                rty::SortCtor::Data(name)
+            }
             fhir::SortRes::Adt(def_id) => {
                 let sort_def = self.genv().adt_sort_def_of(def_id)?;
                 if path.args.len() > sort_def.param_count() {
@@ -1079,14 +1098,16 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 env.push_layer(Layer::list(
                     self.results(),
                     bare_fn.generic_params.len() as u32,
-                    &[],
+                    bare_fn.refinement_params,
                 ));
                 let fn_sig =
                     self.conv_fn_decl(&mut env, bare_fn.safety, bare_fn.abi, bare_fn.decl)?;
+                let refine_vars = env.pop_layer().into_bound_vars(self.genv())?;
                 let vars = bare_fn
                     .generic_params
                     .iter()
                     .map(|param| self.param_as_bound_var(param))
+                    .chain(refine_vars.iter().cloned().map(Ok))
                     .try_collect()?;
                 let poly_fn_sig = rty::Binder::bind_with_vars(fn_sig, vars);
                 Ok(rty::BaseTy::FnPtr(poly_fn_sig).to_ty())
@@ -1104,12 +1125,6 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 let pred = self.conv_expr(env, pred)?;
                 Ok(rty::Ty::constr(pred, self.conv_ty(env, ty)?))
             }
-            fhir::TyKind::RawPtr(ty, mutability) => {
-                Ok(rty::Ty::indexed(
-                    rty::BaseTy::RawPtr(self.conv_ty(env, ty)?, *mutability),
-                    rty::Expr::unit(),
-                ))
-            }
             fhir::TyKind::OpaqueDef(opaque_ty) => self.conv_opaque_def(env, opaque_ty, ty.span),
             fhir::TyKind::TraitObject(trait_bounds, lft, syn) => {
                 if matches!(syn, rustc_ast::TraitObjectSyntax::Dyn) {
@@ -1282,6 +1297,12 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 let ty = rty::Ty::indexed(bty, rty::Expr::nu());
                 Ok(rty::TyOrCtor::Ctor(rty::Binder::bind_with_sort(ty, sort)))
             }
+            fhir::BaseTyKind::RawPtr(ty, mutbl) => {
+                let bty = rty::BaseTy::RawPtr(self.conv_ty(env, ty)?, *mutbl).shift_in_escaping(1);
+                let sort = bty.sort();
+                let ty = rty::Ty::indexed(bty, rty::Expr::nu());
+                Ok(rty::TyOrCtor::Ctor(rty::Binder::bind_with_sort(ty, sort)))
+            }
         }
     }
 
@@ -1791,7 +1812,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 found: path.refine.len(),
                 kind: self.tcx().def_descr(def_id),
             };
-            Err(self.emit(err))?
+            Err(self.emit(err))?;
         }
         Ok(())
     }
@@ -1875,6 +1896,9 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                     ExprRes::Ctor(..) => {
                         span_bug!(var.span, "unexpected constructor in var position")
                     }
+                    ExprRes::DataCtor { sort, ctor } => {
+                        rty::Expr::ctor(rty::DataCtor { sort, name: ctor }, List::empty()).at(espan)
+                    }
                 }
             }
             fhir::ExprKind::Literal(lit) => rty::Expr::constant(conv_lit(*lit)).at(espan),
@@ -1890,7 +1914,12 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 rty::Expr::unary_op(conv_un_op(*op), self.conv_expr(env, e)?).at(espan)
             }
             fhir::ExprKind::App(func, args) => {
-                rty::Expr::app(self.conv_func(env, func), self.conv_exprs(env, args)?).at(espan)
+                if let ExprRes::DataCtor { sort, ctor } = func.res {
+                    let ctor = rty::DataCtor { sort, name: ctor };
+                    rty::Expr::ctor(ctor, self.conv_exprs(env, args)?).at(espan)
+                } else {
+                    rty::Expr::app(self.conv_func(env, func), self.conv_exprs(env, args)?).at(espan)
+                }
             }
             fhir::ExprKind::Alias(alias, args) => {
                 let args = args
@@ -1921,13 +1950,39 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 let lam = rty::Lambda::bind_with_vars(pred, inputs, output);
                 rty::Expr::abs(lam)
             }
+            fhir::ExprKind::Let(param, init, body) => {
+                // Let bindings are eliminated by substituting the bound expression in the body
+                let init = self.conv_expr(env, init)?;
+                env.push_layer(Layer::list(self.results(), 0, std::slice::from_ref(param)));
+                let body = self.conv_expr(env, body)?;
+                let vars = env.pop_layer().into_bound_vars(self.genv())?;
+                rty::Binder::bind_with_vars(body, vars).replace_bound_reft(&init)
+            }
+            fhir::ExprKind::Match(scrutinee, arms) => {
+                let scrutinee = self.conv_expr(env, scrutinee)?;
+                self.conv_match(env, &scrutinee, arms)?.at(espan)
+            }
+            fhir::ExprKind::Quant(kind, params, body) => {
+                env.push_layer(Layer::list(self.results(), 0, params));
+                let pred = self.conv_expr(env, body)?;
+                let vars = env.pop_layer().into_bound_vars(self.genv())?;
+                let pred = rty::Binder::bind_with_vars(pred, vars);
+                match kind {
+                    fhir::QuantKind::Forall => rty::Expr::forall(pred),
+                    fhir::QuantKind::Exists => rty::Expr::exists(pred),
+                }
+                .at(espan)
+            }
             fhir::ExprKind::Record(flds) => {
-                let def_id = self.results().record_ctor(expr.fhir_id);
                 let flds = flds
                     .iter()
                     .map(|expr| self.conv_expr(env, expr))
                     .try_collect()?;
-                rty::Expr::adt(def_id, flds)
+                if let Some(def_id) = self.results().record_ctor(expr.fhir_id) {
+                    rty::Expr::adt(def_id, flds)
+                } else {
+                    rty::Expr::tuple(flds)
+                }
             }
             fhir::ExprKind::Constructor(path, exprs, spread) => {
                 let def_id = if let Some(path) = path {
@@ -1936,7 +1991,9 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                         _ => span_bug!(path.span, "unexpected path in constructor"),
                     }
                 } else {
-                    self.results().record_ctor(expr.fhir_id)
+                    self.results()
+                        .record_ctor(expr.fhir_id)
+                        .unwrap_or_else(|| bug!("unelaborated record constructor `{fhir_id:?}`"))
                 };
                 let assns = self.conv_constructor_exprs(def_id, env, exprs, spread)?;
                 rty::Expr::adt(def_id, assns)
@@ -1945,6 +2002,44 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
         Ok(self.add_coercions(expr, fhir_id))
     }
 
+    /// Matches are eliminated by testing the variants of the scrutinee in order. The fields bound
+    /// by each arm are substituted by the corresponding selector on the scrutinee. Sort checking
+    /// guarantees arms are exhaustive, thus the last arm doesn't need to be tested.
+    fn conv_match(
+        &mut self,
+        env: &mut Env,
+        scrutinee: &rty::Expr,
+        arms: &[fhir::MatchArm],
+    ) -> QueryResult<rty::Expr> {
+        let mut expr = None;
+        for arm in arms.iter().rev() {
+            let (ctor, body) = match arm.pat {
+                fhir::MatchPat::Wildcard(_) => (None, self.conv_expr(env, &arm.body)?),
+                fhir::MatchPat::Ctor(path, params) => {
+                    let ExprRes::DataCtor { sort, ctor } = path.res else {
+                        span_bug!(path.span, "unexpected path in match pattern")
+                    };
+                    let ctor = rty::DataCtor { sort, name: ctor };
+                    env.push_layer(Layer::list(self.results(), 0, params));
+                    let body = self.conv_expr(env, &arm.body)?;
+                    let vars = env.pop_layer().into_bound_vars(self.genv())?;
+                    let flds = (0..params.len())
+                        .map(|i| rty::Expr::ctor_field(ctor, i as u32, scrutinee))
+                        .collect_vec();
+                    (Some(ctor), rty::Binder::bind_with_vars(body, vars).replace_bound_refts(&flds))
+                }
+            };
+            expr = match (ctor, expr) {
+                (Some(ctor), Some(els)) => {
+                    Some(rty::Expr::ite(rty::Expr::is_ctor(ctor, scrutinee), body, els))
+                }
+                (_, _) => Some(body),
+            };
+        }
+        let Some(expr) = expr else { bug!("match without arms") };
+        Ok(expr)
+    }
+
     fn conv_constructor_exprs(
         &mut self,
         struct_def_id: DefId,
@@ -2017,6 +2112,15 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
     fn conv_func(&self, env: &Env, func: &fhir::PathExpr) -> rty::Expr {
         let expr = match func.res {
             ExprRes::Param(..) => env.lookup(func).to_expr(),
+            ExprRes::GlobalFunc(fhir::SpecFuncKind::Thy(itf), sym)
+                if flux_middle::is_sized_theory_func(itf) =>
+            {
+                let itf = self
+                    .results()
+                    .theory_func_instance(func.fhir_id)
+                    .unwrap_or(itf);
+                rty::Expr::global_func(sym, fhir::SpecFuncKind::Thy(itf))
+            }
             ExprRes::GlobalFunc(kind, sym) => rty::Expr::global_func(sym, kind),
             _ => span_bug!(func.span, "unexpected path in function position"),
         };
@@ -2271,10 +2375,31 @@ pub fn conv_func_decl(genv: GlobalEnv, func: &fhir::SpecFunc) -> QueryResult<rty
     Ok(rty::SpecFuncDecl { name: func.name, sort, kind })
 }
 
+pub fn conv_data_sort_def(
+    genv: GlobalEnv,
+    data_sort: &fhir::DataSortDecl,
+) -> QueryResult<rty::DataSortDef> {
+    let wfckresults = WfckResults::new(FluxOwnerId::Flux(data_sort.name));
+    let mut cx = AfterSortck::new(genv, &wfckresults).into_conv_ctxt();
+    let variants = data_sort
+        .variants
+        .iter()
+        .map(|variant| -> QueryResult<_> {
+            let fields = variant
+                .fields
+                .iter()
+                .map(|sort| cx.conv_sort(sort))
+                .try_collect()?;
+            Ok(rty::DataVariant { name: variant.name.name, fields })
+        })
+        .try_collect()?;
+    Ok(rty::DataSortDef { name: data_sort.name, variants })
+}
+
 fn conv_lit(lit: fhir::Lit) -> rty::Constant {
     match lit {
         fhir::Lit::Int(n) => rty::Constant::from(n),
-        fhir::Lit::Real(r) => rty::Constant::Real(rty::Real(r)),
+        fhir::Lit::Real(r) => rty::Constant::Real(r),
         fhir::Lit::Bool(b) => rty::Constant::from(b),
         fhir::Lit::Str(s) => rty::Constant::from(s),
         fhir::Lit::Char(c) => rty::Constant::from(c),
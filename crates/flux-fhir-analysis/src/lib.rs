@@ -12,6 +12,7 @@ extern crate rustc_trait_selection;
 extern crate rustc_type_ir;
 
 mod conv;
+mod reflect;
 mod wf;
 
 use std::rc::Rc;
@@ -51,6 +52,7 @@ pub fn provide(providers: &mut Providers) {
     providers.spec_func_decl = spec_func_decl;
     providers.qualifiers = qualifiers;
     providers.adt_sort_def_of = adt_sort_def_of;
+    providers.data_sort_def = data_sort_def;
     providers.check_wf = check_wf;
     providers.adt_def = adt_def;
     providers.constant_info = constant_info;
@@ -72,9 +74,16 @@ fn adt_sort_def_of(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::AdtS
     conv::conv_adt_sort_def(genv, def_id, genv.map().refined_by(def_id.local_id())?)
 }
 
+fn data_sort_def(genv: GlobalEnv, name: Symbol) -> QueryResult<rty::DataSortDef> {
+    let Some(decl) = genv.map().data_sort(name) else { bug!("unknown data sort `{name}`") };
+    conv::conv_data_sort_def(genv, decl)
+}
+
 fn spec_func_decl(genv: GlobalEnv, name: Symbol) -> QueryResult<rty::SpecFuncDecl> {
     if let Some(func) = genv.map().spec_func(name) {
         conv::conv_func_decl(genv, func)
+    } else if let Some(def_id) = genv.reflected_fn(name) {
+        reflect::func_decl(genv, def_id)
     } else {
         let itf = flux_middle::THEORY_FUNCS.get(&name).unwrap();
         Ok(rty::SpecFuncDecl {
@@ -101,11 +110,27 @@ fn spec_func_defns(genv: GlobalEnv) -> QueryResult<rty::SpecFuncDefns> {
             defns.insert(defn.name, defn);
         }
     }
+    for def_id in genv.reflected_fns() {
+        let name = genv.tcx().item_name(def_id.to_def_id());
+        if genv.map().spec_func(name).is_some() || genv.reflected_fn(name) != Some(def_id) {
+            let span = genv.tcx().def_ident_span(def_id).unwrap();
+            errors.emit(errors::DuplicateReflectedFn::new(span, name));
+            continue;
+        }
+        let Ok(defn) = reflect::func_defn(genv, def_id).emit(&errors) else {
+            continue;
+        };
+        defns.insert(defn.name, defn);
+    }
     errors.into_result()?;
 
     let defns = rty::SpecFuncDefns::new(defns)
         .map_err(|cycle| {
-            let span = genv.map().spec_func(cycle[0]).unwrap().body.unwrap().span;
+            let span = if let Some(func) = genv.map().spec_func(cycle[0]) {
+                func.body.unwrap().span
+            } else {
+                genv.tcx().def_span(genv.reflected_fn(cycle[0]).unwrap())
+            };
             errors::DefinitionCycle::new(span, cycle)
         })
         .emit(&genv)?;
@@ -537,6 +562,11 @@ fn fn_sig(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::EarlyBinder<r
         .into_conv_ctxt()
         .conv_fn_sig(def_id, fhir_fn_sig)?;
     let fn_sig = struct_compat::fn_sig(genv, fhir_fn_sig.decl, &fn_sig, def_id)?;
+    let fn_sig = if genv.is_reflected(def_id.local_id()) {
+        reflect::strengthen_fn_sig(genv, def_id.local_id(), fn_sig)?
+    } else {
+        fn_sig
+    };
 
     if config::dump_rty() {
         let generics = genv.generics_of(def_id)?;
@@ -621,4 +651,19 @@ mod errors {
             Self { span, msg }
         }
     }
+
+    #[derive(Diagnostic)]
+    #[diag(fhir_analysis_duplicate_reflected_fn, code = E0999)]
+    pub struct DuplicateReflectedFn {
+        #[primary_span]
+        #[label]
+        span: Span,
+        name: Symbol,
+    }
+
+    impl DuplicateReflectedFn {
+        pub(super) fn new(span: Span, name: Symbol) -> Self {
+            Self { span, name }
+        }
+    }
 }
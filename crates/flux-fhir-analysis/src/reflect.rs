@@ -0,0 +1,448 @@
+//! Reflection of pure Rust functions into the refinement logic.
+//!
+//! A function marked with `#[flux::reflect]` is translated into a refinement function with the
+//! same name by symbolically executing its MIR. The translation supports functions over integers,
+//! booleans and characters whose control flow graph is acyclic. Every path through the body
+//! produces an expression and paths are merged with an if-then-else at each `SwitchInt`. Paths
+//! ending in a diverging call or in `Unreachable` are dropped.
+//!
+//! A reflected function `f` gets the signature `fn(x0: T0[@a0], ..) -> R[f(a0, ..)]`. This
+//! signature is checked against the body like the signature of any other function, so we don't
+//! need to trust the translation: if it doesn't match the semantics of the body, checking the
+//! function will fail.
+use flux_common::bug;
+use flux_config as config;
+use flux_middle::{
+    fhir::SpecFuncKind,
+    global_env::GlobalEnv,
+    queries::{QueryErr, QueryResult},
+    rty::{self, BoundReftKind, BoundVar, Expr, INNERMOST},
+};
+use flux_rustc_bridge::{
+    mir::{
+        BasicBlock, BinOp, Body, CallKind, CastKind, Constant, Operand, Place, Rvalue, Statement,
+        StatementKind, TerminatorKind, UnOp, RETURN_PLACE, START_BLOCK,
+    },
+    ty,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::mir::Local;
+use rustc_span::{symbol::kw, Span, Symbol};
+
+pub(crate) fn func_decl(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::SpecFuncDecl> {
+    let (inputs, output) = signature(genv, def_id)?;
+    let inputs = inputs.iter().map(sort_of_bty).collect();
+    let fsort = rty::FuncSort::new(inputs, sort_of_bty(&output));
+    Ok(rty::SpecFuncDecl {
+        name: genv.tcx().item_name(def_id.to_def_id()),
+        sort: rty::PolyFuncSort::new(rty::List::empty(), fsort),
+        kind: SpecFuncKind::Def,
+    })
+}
+
+pub(crate) fn func_defn(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<rty::SpecFunc> {
+    let name = genv.tcx().item_name(def_id.to_def_id());
+    let (inputs, _) = signature(genv, def_id)?;
+    let body = genv.mir(def_id)?;
+
+    let mut reflector = Reflector { genv, def_id, name, body: &body, path: vec![] };
+    let mut env = FxHashMap::default();
+    let mut vars = vec![];
+    for ((i, bty), local) in inputs.iter().enumerate().zip(body.args_iter()) {
+        let kind = bound_reft_kind(genv, def_id, i);
+        env.insert(local, Expr::bvar(INNERMOST, BoundVar::from_usize(i), kind));
+        vars.push(rty::BoundVariableKind::Refine(sort_of_bty(bty), rty::InferMode::EVar, kind));
+    }
+    let Some(expr) = reflector.block(START_BLOCK, env)? else {
+        return Err(reflector.emit_err(genv.tcx().def_span(def_id), "the function never returns"));
+    };
+    Ok(rty::SpecFunc { name, expr: rty::Binder::bind_with_vars(expr, vars.into()) })
+}
+
+/// Strengthens the signature of a reflected function such that the output is indexed by the
+/// reflected function applied to the indices of the inputs.
+pub(crate) fn strengthen_fn_sig(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+    fn_sig: rty::PolyFnSig,
+) -> QueryResult<rty::PolyFnSig> {
+    let name = genv.tcx().item_name(def_id.to_def_id());
+    let (inputs, output) = signature(genv, def_id)?;
+
+    let mut vars = fn_sig.vars().to_vec();
+    let offset = vars.len();
+    let fn_sig = fn_sig.skip_binder();
+
+    let mut args = vec![];
+    let inputs = inputs
+        .into_iter()
+        .enumerate()
+        .map(|(i, bty)| {
+            let kind = bound_reft_kind(genv, def_id, i);
+            let var = BoundVar::from_usize(offset + i);
+            vars.push(rty::BoundVariableKind::Refine(
+                sort_of_bty(&bty),
+                rty::InferMode::EVar,
+                kind,
+            ));
+            args.push(Expr::bvar(INNERMOST.shifted_in(1), var, kind));
+            rty::Ty::indexed(bty, Expr::bvar(INNERMOST, var, kind))
+        })
+        .collect();
+    let ret = rty::Ty::indexed(
+        output,
+        Expr::app(Expr::global_func(name, SpecFuncKind::Def), rty::List::from_vec(args)),
+    );
+    let output = fn_sig
+        .output()
+        .map(|output| rty::FnOutput::new(ret, output.ensures));
+
+    let fn_sig = rty::FnSig::new(fn_sig.safety, fn_sig.abi, fn_sig.requires, inputs, output);
+    Ok(rty::Binder::bind_with_vars(fn_sig, vars.into()))
+}
+
+/// Returns the base types of the inputs and the output of a reflected function, reporting an
+/// error if the function is generic or if it has a type that cannot be reflected.
+fn signature(genv: GlobalEnv, def_id: LocalDefId) -> QueryResult<(Vec<rty::BaseTy>, rty::BaseTy)> {
+    let span = genv.tcx().def_ident_span(def_id).unwrap();
+    let name = genv.tcx().item_name(def_id.to_def_id());
+    let emit_err = |reason: String| {
+        QueryErr::Emitted(
+            genv.sess()
+                .emit_err(errors::CannotReflect::new(span, name, reason)),
+        )
+    };
+
+    if genv.tcx().generics_of(def_id).count() > 0 {
+        return Err(emit_err("generic functions cannot be reflected".to_string()));
+    }
+    let fn_sig = genv.lower_fn_sig(def_id)?.skip_binder().skip_binder();
+    let reflect_ty = |ty: &ty::Ty| {
+        reflected_bty(ty)
+            .ok_or_else(|| emit_err(format!("values of type `{ty:?}` cannot be reflected")))
+    };
+    let inputs = fn_sig.inputs().iter().map(reflect_ty).try_collect()?;
+    let output = reflect_ty(fn_sig.output())?;
+    Ok((inputs, output))
+}
+
+fn reflected_bty(ty: &ty::Ty) -> Option<rty::BaseTy> {
+    match ty.kind() {
+        ty::TyKind::Bool => Some(rty::BaseTy::Bool),
+        ty::TyKind::Char => Some(rty::BaseTy::Char),
+        ty::TyKind::Int(int_ty) => Some(rty::BaseTy::Int(*int_ty)),
+        ty::TyKind::Uint(uint_ty) => Some(rty::BaseTy::Uint(*uint_ty)),
+        _ => None,
+    }
+}
+
+fn sort_of_bty(bty: &rty::BaseTy) -> rty::Sort {
+    match bty {
+        rty::BaseTy::Bool => rty::Sort::Bool,
+        rty::BaseTy::Char => rty::Sort::Char,
+        rty::BaseTy::Int(_) | rty::BaseTy::Uint(_) => rty::Sort::Int,
+        _ => bug!("unexpected base type in reflected function: `{bty:?}`"),
+    }
+}
+
+fn bound_reft_kind(genv: GlobalEnv, def_id: LocalDefId, i: usize) -> BoundReftKind {
+    match genv.tcx().fn_arg_names(def_id).get(i) {
+        Some(ident) if ident.name != kw::Underscore && ident.name != kw::Empty => {
+            BoundReftKind::Named(ident.name)
+        }
+        _ => BoundReftKind::Annon,
+    }
+}
+
+type Env = FxHashMap<Local, Expr>;
+
+struct Reflector<'a, 'genv, 'tcx> {
+    genv: GlobalEnv<'genv, 'tcx>,
+    def_id: LocalDefId,
+    name: Symbol,
+    body: &'a Body<'tcx>,
+    /// The basic blocks in the path currently being executed. Used to detect loops.
+    path: Vec<BasicBlock>,
+}
+
+impl Reflector<'_, '_, '_> {
+    /// Symbolically executes the body starting at `bb`. Returns the value of the return place for
+    /// all the paths starting at `bb`, or [`None`] if all of them diverge.
+    fn block(&mut self, bb: BasicBlock, mut env: Env) -> QueryResult<Option<Expr>> {
+        let data = &self.body.basic_blocks[bb];
+        let terminator = data.terminator.as_ref().unwrap();
+        let span = terminator.source_info.span;
+        if self.path.contains(&bb) {
+            return Err(self.emit_err(span, "loops are not supported"));
+        }
+        self.path.push(bb);
+
+        for stmt in &data.statements {
+            self.statement(stmt, &mut env)?;
+        }
+
+        let res = match &terminator.kind {
+            TerminatorKind::Return => Some(self.local(RETURN_PLACE, &env).clone()),
+            TerminatorKind::Goto { target }
+            | TerminatorKind::Drop { target, .. }
+            | TerminatorKind::Assert { target, .. }
+            | TerminatorKind::FalseEdge { real_target: target, .. }
+            | TerminatorKind::FalseUnwind { real_target: target, .. } => {
+                self.block(*target, env)?
+            }
+            TerminatorKind::SwitchInt { discr, targets } => {
+                let (discr, bty) = self.operand(discr, &env, span)?;
+                let mut res = self.block(targets.otherwise(), env.clone())?;
+                for (value, target) in targets.iter().collect::<Vec<_>>().into_iter().rev() {
+                    let cond = if let rty::BaseTy::Bool = bty {
+                        if value == 0 {
+                            discr.not()
+                        } else {
+                            discr.clone()
+                        }
+                    } else {
+                        Expr::eq(&discr, Expr::from_bits(&bty, value))
+                    };
+                    res = match (self.block(target, env.clone())?, res) {
+                        (Some(e1), Some(e2)) => Some(Expr::ite(cond, e1, e2)),
+                        (e, None) | (None, e) => e,
+                    };
+                }
+                res
+            }
+            TerminatorKind::Call { kind, args, destination, target, .. } => {
+                // A call that doesn't return doesn't contribute a value
+                let Some(target) = target else {
+                    self.path.pop();
+                    return Ok(None);
+                };
+                let expr = self.call(kind, args, &env, span)?;
+                env.insert(self.destination(destination, span)?, expr);
+                self.block(*target, env)?
+            }
+            TerminatorKind::Unreachable => None,
+            TerminatorKind::Yield { .. }
+            | TerminatorKind::CoroutineDrop
+            | TerminatorKind::UnwindResume => {
+                return Err(self.emit_err(span, "unsupported terminator"));
+            }
+        };
+        self.path.pop();
+        Ok(res)
+    }
+
+    fn statement(&self, stmt: &Statement, env: &mut Env) -> QueryResult {
+        let span = stmt.source_info.span;
+        match &stmt.kind {
+            StatementKind::Assign(place, rvalue) => {
+                let local = self.destination(place, span)?;
+                // Values of type `()` carry no information, so there's no need to track them.
+                if matches!(self.body.local_decls[local].ty.kind(), ty::TyKind::Tuple(tys) if tys.is_empty())
+                {
+                    return Ok(());
+                }
+                let expr = self.rvalue(rvalue, env, span)?;
+                env.insert(local, expr);
+            }
+            StatementKind::FakeRead(_)
+            | StatementKind::AscribeUserType(..)
+            | StatementKind::PlaceMention(_)
+            | StatementKind::Nop => {}
+            StatementKind::SetDiscriminant(..) | StatementKind::Intrinsic(_) => {
+                return Err(self.emit_err(span, "unsupported statement"));
+            }
+        }
+        Ok(())
+    }
+
+    fn rvalue(&self, rvalue: &Rvalue, env: &Env, span: Span) -> QueryResult<Expr> {
+        match rvalue {
+            Rvalue::Use(op) => Ok(self.operand(op, env, span)?.0),
+            Rvalue::BinaryOp(op, op1, op2) => {
+                let (e1, bty) = self.operand(op1, env, span)?;
+                let (e2, _) = self.operand(op2, env, span)?;
+                self.bin_op(*op, &bty, e1, e2, span)
+            }
+            Rvalue::UnaryOp(UnOp::Not, op) => {
+                let (e, bty) = self.operand(op, env, span)?;
+                if let rty::BaseTy::Bool = bty {
+                    Ok(e.not())
+                } else {
+                    Err(self.emit_err(span, "bitwise operations are not supported"))
+                }
+            }
+            Rvalue::UnaryOp(UnOp::Neg, op) => {
+                let (e, bty) = self.operand(op, env, span)?;
+                if bty.is_integral() {
+                    Ok(e.neg())
+                } else {
+                    Err(self.emit_err(span, "unsupported operand for negation"))
+                }
+            }
+            Rvalue::Cast(CastKind::IntToInt, op, to) => {
+                let (e, from) = self.operand(op, env, span)?;
+                self.cast(e, &from, to, span)
+            }
+            _ => Err(self.emit_err(span, "unsupported expression")),
+        }
+    }
+
+    /// Translates a binary operation following the semantics given to primitive operations when
+    /// checking function bodies.
+    fn bin_op(
+        &self,
+        op: BinOp,
+        bty: &rty::BaseTy,
+        e1: Expr,
+        e2: Expr,
+        span: Span,
+    ) -> QueryResult<Expr> {
+        let is_bool = matches!(bty, rty::BaseTy::Bool);
+        let is_char = matches!(bty, rty::BaseTy::Char);
+        let is_integral = bty.is_integral();
+        let op = match op {
+            BinOp::Eq => rty::BinOp::Eq,
+            BinOp::Ne if !is_char => rty::BinOp::Ne,
+            BinOp::Gt if is_integral => rty::BinOp::Gt(rty::Sort::Int),
+            BinOp::Ge if is_integral => rty::BinOp::Ge(rty::Sort::Int),
+            BinOp::Lt if is_integral => rty::BinOp::Lt(rty::Sort::Int),
+            BinOp::Le if is_integral => rty::BinOp::Le(rty::Sort::Int),
+            BinOp::Add if is_integral => rty::BinOp::Add,
+            BinOp::Sub if is_integral => rty::BinOp::Sub,
+            BinOp::Mul if is_integral => rty::BinOp::Mul,
+            BinOp::Div if is_integral => rty::BinOp::Div,
+            // The remainder of signed integers doesn't coincide with the modulo in the logic.
+            BinOp::Rem if matches!(bty, rty::BaseTy::Uint(_)) => rty::BinOp::Mod,
+            BinOp::BitAnd if is_bool => rty::BinOp::And,
+            BinOp::BitOr if is_bool => rty::BinOp::Or,
+            _ => {
+                return Err(self.emit_err(span, format!("unsupported operator `{op:?}`")));
+            }
+        };
+        Ok(Expr::binary_op(op, e1, e2))
+    }
+
+    /// Translates casts between integers that don't change the value of the integer, i.e., the
+    /// ones that can be verified without knowing the bit representation.
+    fn cast(&self, e: Expr, from: &rty::BaseTy, to: &ty::Ty, span: Span) -> QueryResult<Expr> {
+        let bit_width = |bty: &rty::BaseTy| {
+            match bty {
+                rty::BaseTy::Int(int_ty) => int_ty.bit_width(),
+                rty::BaseTy::Uint(uint_ty) => uint_ty.bit_width(),
+                _ => None,
+            }
+            .unwrap_or(config::pointer_width().bits())
+        };
+        let to = reflected_bty(to);
+        match (from, &to) {
+            (rty::BaseTy::Bool, Some(rty::BaseTy::Int(_) | rty::BaseTy::Uint(_))) => {
+                Ok(Expr::ite(e, 1, 0))
+            }
+            (rty::BaseTy::Int(_), Some(to @ rty::BaseTy::Int(_)))
+            | (rty::BaseTy::Uint(_), Some(to @ rty::BaseTy::Uint(_)))
+                if bit_width(from) <= bit_width(to) =>
+            {
+                Ok(e)
+            }
+            (rty::BaseTy::Uint(_), Some(to @ rty::BaseTy::Int(_)))
+                if bit_width(from) < bit_width(to) =>
+            {
+                Ok(e)
+            }
+            _ => Err(self.emit_err(span, "only casts that preserve the value are supported")),
+        }
+    }
+
+    fn call(&self, kind: &CallKind, args: &[Operand], env: &Env, span: Span) -> QueryResult<Expr> {
+        let CallKind::FnDef { resolved_id, .. } = kind else {
+            return Err(self.emit_err(span, "calls through function pointers are not supported"));
+        };
+        let Some(callee) = self
+            .genv
+            .reflected_fns()
+            .find(|def_id| def_id.to_def_id() == *resolved_id)
+        else {
+            return Err(self.emit_err(span, "only calls to reflected functions are supported"));
+        };
+        if callee == self.def_id {
+            return Err(self.emit_err(span, "recursive calls are not supported"));
+        }
+        let name = self.genv.tcx().item_name(callee.to_def_id());
+        let args = args
+            .iter()
+            .map(|arg| Ok(self.operand(arg, env, span)?.0))
+            .collect::<QueryResult<_>>()?;
+        Ok(Expr::app(Expr::global_func(name, SpecFuncKind::Def), args))
+    }
+
+    fn operand(&self, op: &Operand, env: &Env, span: Span) -> QueryResult<(Expr, rty::BaseTy)> {
+        match op {
+            Operand::Copy(place) | Operand::Move(place) => {
+                let local = self.destination(place, span)?;
+                let ty = &self.body.local_decls[local].ty;
+                let Some(bty) = reflected_bty(ty) else {
+                    return Err(
+                        self.emit_err(span, format!("values of type `{ty:?}` are not supported"))
+                    );
+                };
+                Ok((self.local(local, env).clone(), bty))
+            }
+            Operand::Constant(Constant::Int(n, int_ty)) => {
+                Ok((Expr::constant(rty::Constant::from(*n)), rty::BaseTy::Int(*int_ty)))
+            }
+            Operand::Constant(Constant::Uint(n, uint_ty)) => {
+                Ok((Expr::constant(rty::Constant::from(*n)), rty::BaseTy::Uint(*uint_ty)))
+            }
+            Operand::Constant(Constant::Bool(b)) => {
+                Ok((Expr::constant(rty::Constant::from(*b)), rty::BaseTy::Bool))
+            }
+            Operand::Constant(Constant::Char(c)) => {
+                Ok((Expr::constant(rty::Constant::from(*c)), rty::BaseTy::Char))
+            }
+            Operand::Constant(_) => Err(self.emit_err(span, "unsupported constant")),
+        }
+    }
+
+    /// Returns the local a place refers to. Places with projections are not supported.
+    fn destination(&self, place: &Place, span: Span) -> QueryResult<Local> {
+        if place.projection.is_empty() {
+            Ok(place.local)
+        } else {
+            Err(self.emit_err(span, "projections are not supported"))
+        }
+    }
+
+    fn local<'e>(&self, local: Local, env: &'e Env) -> &'e Expr {
+        env.get(&local)
+            .unwrap_or_else(|| bug!("use of unassigned local `{local:?}`"))
+    }
+
+    fn emit_err(&self, span: Span, reason: impl Into<String>) -> QueryErr {
+        let err = errors::CannotReflect::new(span, self.name, reason.into());
+        QueryErr::Emitted(self.genv.sess().emit_err(err))
+    }
+}
+
+mod errors {
+    use flux_errors::E0999;
+    use flux_macros::Diagnostic;
+    use rustc_span::{Span, Symbol};
+
+    #[derive(Diagnostic)]
+    #[diag(fhir_analysis_cannot_reflect, code = E0999)]
+    pub(super) struct CannotReflect {
+        #[primary_span]
+        #[label]
+        span: Span,
+        name: Symbol,
+        reason: String,
+    }
+
+    impl CannotReflect {
+        pub(super) fn new(span: Span, name: Symbol, reason: String) -> Self {
+            Self { span, name, reason }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use flux_errors::E0999;
 use flux_macros::Diagnostic;
 use flux_middle::{fhir, rty};
+use itertools::Itertools;
 use rustc_span::{symbol::Ident, Span, Symbol};
 
 #[derive(Diagnostic)]
@@ -274,3 +275,71 @@ impl CannotInferSort {
         Self { span }
     }
 }
+
+#[derive(Diagnostic)]
+#[diag(fhir_analysis_invalid_bv_sizes, code = E0999)]
+#[note]
+pub(super) struct InvalidBvSizes {
+    #[primary_span]
+    #[label]
+    span: Span,
+    name: Symbol,
+    sizes: String,
+}
+
+impl InvalidBvSizes {
+    pub(super) fn new(span: Span, name: Symbol, sizes: &[usize]) -> Self {
+        Self { span, name, sizes: sizes.iter().join(", ") }
+    }
+}
+
+#[derive(Diagnostic)]
+#[diag(fhir_analysis_non_exhaustive_match, code = E0999)]
+pub(super) struct NonExhaustiveMatch {
+    #[primary_span]
+    #[label]
+    span: Span,
+    missing: String,
+}
+
+impl NonExhaustiveMatch {
+    pub(super) fn new(span: Span, missing: Vec<Symbol>) -> Self {
+        let missing = missing
+            .into_iter()
+            .map(|x| format!("`{x}`"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        Self { span, missing }
+    }
+}
+
+#[derive(Diagnostic)]
+#[diag(fhir_analysis_cannot_prove_termination, code = E0999)]
+#[note]
+pub(super) struct CannotProveTermination {
+    #[primary_span]
+    #[label]
+    span: Span,
+    name: Symbol,
+}
+
+impl CannotProveTermination {
+    pub(super) fn new(span: Span, name: Symbol) -> Self {
+        Self { span, name }
+    }
+}
+
+#[derive(Diagnostic)]
+#[diag(fhir_analysis_polymorphic_recursive_defn, code = E0999)]
+pub(super) struct PolymorphicRecursiveDefn {
+    #[primary_span]
+    #[label]
+    span: Span,
+    name: Symbol,
+}
+
+impl PolymorphicRecursiveDefn {
+    pub(super) fn new(span: Span, name: Symbol) -> Self {
+        Self { span, name }
+    }
+}
@@ -5,6 +5,7 @@
 mod errors;
 mod param_usage;
 mod sortck;
+mod termination;
 
 use flux_common::result::{ErrorCollector, ResultExt as _};
 use flux_errors::{Errors, FluxSession};
@@ -35,11 +36,13 @@ pub(crate) fn check_qualifier(genv: GlobalEnv, qual: &fhir::Qualifier) -> Result
     let mut infcx = InferCtxt::new(genv, owner);
     let mut wf = Wf::new(&mut infcx);
     wf.insert_params(qual.args)?;
+    wf.insert_params_for_expr(&qual.expr)?;
     infcx.check_expr(&qual.expr, &rty::Sort::Bool)?;
     for param in qual.args {
         infcx.resolve_param_sort(param)?;
     }
-    Ok(infcx.into_results())
+    resolve_params_for_expr(&mut infcx, &qual.expr)?;
+    infcx.into_results()
 }
 
 pub(crate) fn check_fn_spec(genv: GlobalEnv, func: &fhir::SpecFunc) -> Result<WfckResults> {
@@ -48,13 +51,16 @@ pub(crate) fn check_fn_spec(genv: GlobalEnv, func: &fhir::SpecFunc) -> Result<Wf
     let mut wf = Wf::new(&mut infcx);
     if let Some(body) = &func.body {
         wf.insert_params(func.args)?;
+        wf.insert_params_for_expr(body)?;
         let output = wf.as_conv_ctxt().conv_sort(&func.sort).emit(&genv)?;
         infcx.check_expr(body, &output)?;
         for param in func.args {
             infcx.resolve_param_sort(param)?;
         }
+        resolve_params_for_expr(&mut infcx, body)?;
+        termination::check(genv, func)?;
     }
-    Ok(infcx.into_results())
+    infcx.into_results()
 }
 
 pub(crate) fn check_constant_expr(
@@ -64,10 +70,12 @@ pub(crate) fn check_constant_expr(
     sort: &rty::Sort,
 ) -> Result<WfckResults> {
     let mut infcx = InferCtxt::new(genv, FluxOwnerId::Rust(owner));
+    Wf::new(&mut infcx).insert_params_for_expr(expr)?;
     let mut err = None;
     infcx.check_expr(expr, sort).collect_err(&mut err);
     err.into_result()?;
-    Ok(infcx.into_results())
+    resolve_params_for_expr(&mut infcx, expr)?;
+    infcx.into_results()
 }
 
 pub(crate) fn check_invariants(
@@ -80,6 +88,9 @@ pub(crate) fn check_invariants(
     let mut infcx = InferCtxt::new(genv, owner);
     let mut wf = Wf::new(&mut infcx);
     wf.insert_params(params)?;
+    for invariant in invariants {
+        wf.insert_params_for_expr(invariant)?;
+    }
     let mut err = None;
     for invariant in invariants {
         infcx
@@ -90,7 +101,10 @@ pub(crate) fn check_invariants(
         infcx.resolve_param_sort(param)?;
     }
     err.into_result()?;
-    Ok(infcx.into_results())
+    for invariant in invariants {
+        resolve_params_for_expr(&mut infcx, invariant)?;
+    }
+    infcx.into_results()
 }
 
 pub(crate) fn check_node<'genv>(
@@ -112,7 +126,7 @@ pub(crate) fn check_node<'genv>(
 
     param_usage::check(&infcx, node)?;
 
-    Ok(infcx.into_results())
+    infcx.into_results()
 }
 
 /// Check that all param sorts are fully resolved and save them in [`WfckResults`]
@@ -120,6 +134,12 @@ fn resolve_params(infcx: &mut InferCtxt, node: &fhir::OwnerNode) -> Result {
     visit_refine_params(node, |param| infcx.resolve_param_sort(param))
 }
 
+/// Same as [`resolve_params`] but for the parameters bound inside an expression, i.e., by
+/// quantifiers and lambdas.
+fn resolve_params_for_expr(infcx: &mut InferCtxt, expr: &fhir::Expr) -> Result {
+    visit_refine_params_in_expr(expr, |param| infcx.resolve_param_sort(param))
+}
+
 pub(crate) fn check_fn_quals(
     sess: &FluxSession,
     qualifiers: &UnordSet<Symbol>,
@@ -182,6 +202,17 @@ impl<'a, 'genv, 'tcx> Wf<'a, 'genv, 'tcx> {
         })
     }
 
+    /// Initializes the inference context with the refinement parameters bound inside `expr`. This
+    /// is only needed for items that are not an [`fhir::OwnerNode`], e.g., qualifiers.
+    fn insert_params_for_expr(&mut self, expr: &fhir::Expr) -> Result {
+        let genv = self.infcx.genv;
+        visit_refine_params_in_expr(expr, |param| {
+            let sort = self.as_conv_ctxt().conv_sort(&param.sort).emit(&genv)?;
+            self.infcx.insert_param(param.id, sort, param.kind);
+            Ok(())
+        })
+    }
+
     /// To check for well-formedness we need to know the sort of base types. For example, to check if
     /// the type `i32[e]` is well formed, we need to know that the sort of `i32` is `int` so we can
     /// check the expression `e` against it. Computing the sort from a base type is subtle and hard
@@ -400,24 +431,34 @@ fn visit_refine_params(
     node: &fhir::OwnerNode,
     f: impl FnMut(&fhir::RefineParam) -> Result,
 ) -> Result {
-    struct RefineParamVisitor<F> {
-        f: F,
-        err: Option<ErrorGuaranteed>,
-    }
-
-    impl<F> fhir::visit::Visitor<'_> for RefineParamVisitor<F>
-    where
-        F: FnMut(&fhir::RefineParam) -> Result,
-    {
-        fn visit_refine_param(&mut self, param: &fhir::RefineParam) {
-            (self.f)(param).collect_err(&mut self.err);
-        }
-    }
     let mut visitor = RefineParamVisitor { f, err: None };
     visitor.visit_node(node);
     visitor.err.into_result()
 }
 
+fn visit_refine_params_in_expr(
+    expr: &fhir::Expr,
+    f: impl FnMut(&fhir::RefineParam) -> Result,
+) -> Result {
+    let mut visitor = RefineParamVisitor { f, err: None };
+    visitor.visit_expr(expr);
+    visitor.err.into_result()
+}
+
+struct RefineParamVisitor<F> {
+    f: F,
+    err: Option<ErrorGuaranteed>,
+}
+
+impl<F> fhir::visit::Visitor<'_> for RefineParamVisitor<F>
+where
+    F: FnMut(&fhir::RefineParam) -> Result,
+{
+    fn visit_refine_param(&mut self, param: &fhir::RefineParam) {
+        (self.f)(param).collect_err(&mut self.err);
+    }
+}
+
 impl<'genv, 'tcx> ConvPhase<'genv, 'tcx> for Wf<'_, 'genv, 'tcx> {
     /// We don't expand type aliases before sort checking because we need every base type in `fhir`
     /// to match a type in `rty`.
@@ -500,11 +541,15 @@ impl WfckResultsProvider for InferCtxt<'_, '_> {
         rty::Sort::Err
     }
 
-    fn record_ctor(&self, _: FhirId) -> DefId {
-        DefId { index: DefIndex::from_u32(0), krate: CrateNum::from_u32(0) }
+    fn record_ctor(&self, _: FhirId) -> Option<DefId> {
+        Some(DefId { index: DefIndex::from_u32(0), krate: CrateNum::from_u32(0) })
     }
 
     fn param_sort(&self, param: &fhir::RefineParam) -> rty::Sort {
         self.param_sort(param.id)
     }
+
+    fn theory_func_instance(&self, _: FhirId) -> Option<Symbol> {
+        None
+    }
 }
@@ -113,6 +113,19 @@ impl<'a, 'genv, 'tcx> ParamUsesChecker<'a, 'genv, 'tcx> {
             fhir::ExprKind::Abs(_, body) => {
                 self.check_func_params_uses(body, true, is_top_level_var);
             }
+            fhir::ExprKind::Quant(_, _, body) => {
+                self.check_func_params_uses(body, false, false);
+            }
+            fhir::ExprKind::Let(_, init, body) => {
+                self.check_func_params_uses(init, false, false);
+                self.check_func_params_uses(body, is_top_level_conj, false);
+            }
+            fhir::ExprKind::Match(scrutinee, arms) => {
+                self.check_func_params_uses(scrutinee, false, false);
+                for arm in arms {
+                    self.check_func_params_uses(&arm.body, false, false);
+                }
+            }
             fhir::ExprKind::Record(fields) => {
                 for field in fields {
                     self.check_func_params_uses(field, is_top_level_conj, is_top_level_var);
@@ -197,6 +210,12 @@ impl<'genv> fhir::visit::Visitor<'genv> for ParamUsesChecker<'_, 'genv, '_> {
                 fhir::visit::walk_bty(self, bty);
                 self.check_func_params_uses(expr, false, true);
             }
+            fhir::TyKind::BareFn(bare_fn) => {
+                let snapshot = self.xi.snapshot();
+                fhir::visit::walk_ty(self, ty);
+                self.check_params_are_value_determined(bare_fn.refinement_params);
+                self.xi.rollback_to(snapshot);
+            }
             _ => {
                 fhir::visit::walk_ty(self, ty);
             }
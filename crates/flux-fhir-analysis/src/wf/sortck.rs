@@ -16,8 +16,8 @@ use flux_middle::{
 use itertools::{izip, Itertools};
 use rustc_data_structures::unord::UnordMap;
 use rustc_errors::Diagnostic;
-use rustc_hash::FxHashMap;
-use rustc_span::{def_id::DefId, symbol::Ident, Span};
+use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_span::{def_id::DefId, symbol::Ident, Span, Symbol};
 
 use super::errors;
 
@@ -33,6 +33,19 @@ pub(super) struct InferCtxt<'genv, 'tcx> {
     sort_of_bty: FxHashMap<FhirId, rty::Sort>,
     path_args: UnordMap<FhirId, rty::GenericArgs>,
     sort_of_alias_reft: UnordMap<FhirId, rty::FuncSort>,
+    /// Applications of [sized theory functions] with the sizes they were instantiated with. The
+    /// sizes are resolved when all the constraints have been collected.
+    ///
+    /// [sized theory functions]: flux_middle::is_sized_theory_func
+    sized_theory_apps: Vec<SizedTheoryApp>,
+}
+
+struct SizedTheoryApp {
+    func: FhirId,
+    span: Span,
+    name: Symbol,
+    fixpoint_name: Symbol,
+    sizes: Vec<rty::BvSize>,
 }
 
 impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
@@ -50,6 +63,7 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
             sort_of_bty: Default::default(),
             path_args: Default::default(),
             sort_of_alias_reft: Default::default(),
+            sized_theory_apps: vec![],
         }
     }
 
@@ -165,6 +179,20 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
             izip!(flds, &sorts)
                 .map(|(arg, expected)| self.check_expr(arg, expected))
                 .try_collect_exhaust()
+        } else if let rty::Sort::Tuple(sorts) = expected
+            && sorts.len() > 1
+        {
+            if flds.len() != sorts.len() {
+                return Err(self.emit_err(errors::ArgCountMismatch::new(
+                    Some(arg.span),
+                    String::from("type"),
+                    sorts.len(),
+                    flds.len(),
+                )));
+            }
+            izip!(flds, sorts)
+                .map(|(arg, expected)| self.check_expr(arg, expected))
+                .try_collect_exhaust()
         } else {
             Err(self.emit_err(errors::ArgCountMismatch::new(
                 Some(arg.span),
@@ -185,12 +213,21 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
             fhir::ExprKind::Abs(refine_params, body) => {
                 self.check_abs(expr, refine_params, body, expected)?;
             }
+            fhir::ExprKind::Let(param, init, body) => {
+                let sort = self.param_sort(param.id);
+                self.check_expr(init, &sort)?;
+                self.check_expr(body, expected)?;
+            }
+            fhir::ExprKind::Match(scrutinee, arms) => {
+                self.check_match(expr, scrutinee, arms, expected)?;
+            }
             fhir::ExprKind::Record(fields) => self.check_record(expr, fields, expected)?,
             fhir::ExprKind::Constructor(None, exprs, spread) => {
                 self.check_constructor(expr, exprs, spread, expected)?;
             }
             fhir::ExprKind::UnaryOp(..)
             | fhir::ExprKind::BinaryOp(..)
+            | fhir::ExprKind::Quant(..)
             | fhir::ExprKind::Dot(..)
             | fhir::ExprKind::App(..)
             | fhir::ExprKind::Alias(..)
@@ -223,6 +260,20 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
             fhir::ExprKind::Literal(lit) => Ok(synth_lit(*lit)),
             fhir::ExprKind::BinaryOp(op, e1, e2) => self.synth_binary_op(expr, *op, e1, e2),
             fhir::ExprKind::UnaryOp(op, e) => self.synth_unary_op(*op, e),
+            fhir::ExprKind::Quant(_, _, body) => {
+                self.check_expr(body, &rty::Sort::Bool)?;
+                Ok(rty::Sort::Bool)
+            }
+            fhir::ExprKind::Let(param, init, body) => {
+                let sort = self.param_sort(param.id);
+                self.check_expr(init, &sort)?;
+                self.synth_expr(body)
+            }
+            fhir::ExprKind::Match(scrutinee, arms) => {
+                let sort = self.next_sort_var();
+                self.check_match(expr, scrutinee, arms, &sort)?;
+                Ok(sort)
+            }
             fhir::ExprKind::App(f, es) => self.synth_app(f, es, expr.span),
             fhir::ExprKind::Alias(_alias_reft, func_args) => {
                 // To check the application we only need the sort of `_alias_reft` which we collected
@@ -308,9 +359,111 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
             ExprRes::Ctor(_) => {
                 span_bug!(path.span, "unexpected constructor in var position")
             }
+            ExprRes::DataCtor { sort, ctor } => {
+                let fsort = self.data_ctor_sort(sort, ctor)?;
+                if !fsort.inputs().is_empty() {
+                    return Err(self.emit_err(errors::ArgCountMismatch::new(
+                        Some(path.span),
+                        String::from("variant"),
+                        fsort.inputs().len(),
+                        0,
+                    )));
+                }
+                Ok(fsort.output().clone())
+            }
         }
     }
 
+    /// The sort of a data sort constructor seen as a function from its fields to the data sort.
+    fn data_ctor_sort(&mut self, sort: Symbol, ctor: Symbol) -> Result<rty::FuncSort> {
+        let sort_def = self.genv.data_sort_def(sort).emit(&self.genv)?;
+        let Some(variant) = sort_def.variant(ctor) else {
+            bug!("unknown variant `{ctor}` of data sort `{sort}`")
+        };
+        Ok(rty::FuncSort::new(variant.fields.to_vec(), data_sort(sort)))
+    }
+
+    fn check_match(
+        &mut self,
+        expr: &fhir::Expr,
+        scrutinee: &fhir::Expr,
+        arms: &[fhir::MatchArm],
+        expected: &rty::Sort,
+    ) -> Result {
+        // The sort of the scrutinee is determined by the first constructor pattern. If all arms are
+        // wildcards, any sort is fine.
+        let first_ctor = arms.iter().find_map(|arm| {
+            if let fhir::MatchPat::Ctor(path, _) = arm.pat
+                && let ExprRes::DataCtor { sort, .. } = path.res
+            {
+                Some(sort)
+            } else {
+                None
+            }
+        });
+        let Some(sort_name) = first_ctor else {
+            self.synth_expr(scrutinee)?;
+            return arms
+                .iter()
+                .try_for_each_exhaust(|arm| self.check_expr(&arm.body, expected));
+        };
+        self.check_expr(scrutinee, &data_sort(sort_name))?;
+
+        let sort_def = self.genv.data_sort_def(sort_name).emit(&self.genv)?;
+        let mut covered = FxHashSet::default();
+        let mut has_wildcard = false;
+        for arm in arms {
+            match arm.pat {
+                fhir::MatchPat::Ctor(path, params) => {
+                    let ExprRes::DataCtor { sort, ctor } = path.res else {
+                        span_bug!(path.span, "unexpected path in match pattern")
+                    };
+                    if sort != sort_name {
+                        return Err(self.emit_sort_mismatch(
+                            path.span,
+                            &data_sort(sort_name),
+                            &data_sort(sort),
+                        ));
+                    }
+                    let fsort = self.data_ctor_sort(sort, ctor)?;
+                    if params.len() != fsort.inputs().len() {
+                        return Err(self.emit_err(errors::ArgCountMismatch::new(
+                            Some(path.span),
+                            String::from("variant"),
+                            fsort.inputs().len(),
+                            params.len(),
+                        )));
+                    }
+                    iter::zip(params, fsort.inputs()).try_for_each_exhaust(
+                        |(param, expected)| {
+                            let found = self.param_sort(param.id);
+                            if self.try_equate(&found, expected).is_none() {
+                                return Err(self.emit_sort_mismatch(param.span, expected, &found));
+                            }
+                            Ok(())
+                        },
+                    )?;
+                    covered.insert(ctor);
+                }
+                fhir::MatchPat::Wildcard(_) => has_wildcard = true,
+            }
+            self.check_expr(&arm.body, expected)?;
+        }
+
+        if !has_wildcard {
+            let missing = sort_def
+                .variants
+                .iter()
+                .map(|variant| variant.name)
+                .filter(|name| !covered.contains(name))
+                .collect_vec();
+            if !missing.is_empty() {
+                return Err(self.emit_err(errors::NonExhaustiveMatch::new(expr.span, missing)));
+            }
+        }
+        Ok(())
+    }
+
     fn synth_binary_op(
         &mut self,
         expr: &fhir::Expr,
@@ -366,8 +519,9 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                 Ok(rty::Sort::Bool)
             }
             fhir::UnOp::Neg => {
-                self.check_expr(e, &rty::Sort::Int)?;
-                Ok(rty::Sort::Int)
+                let sort = self.next_num_var();
+                self.check_expr(e, &sort)?;
+                Ok(sort)
             }
         }
     }
@@ -424,14 +578,45 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                 };
                 fsort
             }
+            ExprRes::GlobalFunc(fhir::SpecFuncKind::Thy(itf), sym)
+                if flux_middle::is_sized_theory_func(itf) =>
+            {
+                let poly_fsort = self.genv.func_decl(sym).emit(&self.genv)?.sort;
+                let args = self.fresh_sort_args(&poly_fsort);
+                let sizes = args
+                    .iter()
+                    .map(|arg| {
+                        match arg {
+                            rty::SortArg::BvSize(size) => *size,
+                            rty::SortArg::Sort(_) => {
+                                span_bug!(func.span, "unexpected sort param in `{sym}`")
+                            }
+                        }
+                    })
+                    .collect();
+                self.sized_theory_apps.push(SizedTheoryApp {
+                    func: func.fhir_id,
+                    span: func.span,
+                    name: sym,
+                    fixpoint_name: itf,
+                    sizes,
+                });
+                return Ok(poly_fsort.instantiate(&args));
+            }
             ExprRes::GlobalFunc(.., sym) => self.genv.func_decl(sym).emit(&self.genv)?.sort.clone(),
+            ExprRes::DataCtor { sort, ctor } => return self.data_ctor_sort(sort, ctor),
             _ => span_bug!(func.span, "unexpected path in function position"),
         };
         Ok(self.instantiate_func_sort(poly_fsort))
     }
 
     fn instantiate_func_sort(&mut self, fsort: rty::PolyFuncSort) -> rty::FuncSort {
-        let args = fsort
+        let args = self.fresh_sort_args(&fsort);
+        fsort.instantiate(&args)
+    }
+
+    fn fresh_sort_args(&mut self, fsort: &rty::PolyFuncSort) -> Vec<rty::SortArg> {
+        fsort
             .params()
             .map(|kind| {
                 match kind {
@@ -439,8 +624,7 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                     rty::SortParamKind::BvSize => rty::SortArg::BvSize(self.next_bv_size_var()),
                 }
             })
-            .collect_vec();
-        fsort.instantiate(&args)
+            .collect_vec()
     }
 
     pub(crate) fn insert_sort_for_bty(&mut self, fhir_id: FhirId, sort: rty::Sort) {
@@ -693,8 +877,39 @@ impl InferCtxt<'_, '_> {
         }
     }
 
-    pub(crate) fn into_results(self) -> WfckResults {
-        self.wfckresults
+    /// Resolves the sizes of the applications of [sized theory functions] and returns the results
+    /// of sort checking.
+    ///
+    /// [sized theory functions]: flux_middle::is_sized_theory_func
+    pub(crate) fn into_results(mut self) -> Result<WfckResults> {
+        let errors = Errors::new(self.genv.sess());
+        for app in std::mem::take(&mut self.sized_theory_apps) {
+            let sizes = app
+                .sizes
+                .iter()
+                .map(|size| {
+                    match self.shallow_resolve(&rty::Sort::BitVec(*size)) {
+                        rty::Sort::BitVec(rty::BvSize::Fixed(size)) => Some(size),
+                        _ => None,
+                    }
+                })
+                .collect::<Option<Vec<_>>>();
+            let Some(sizes) = sizes else {
+                errors.emit(errors::CannotInferSort::new(app.span));
+                continue;
+            };
+            if let Some(instance) =
+                flux_middle::sized_theory_func_instance(app.fixpoint_name, &sizes)
+            {
+                self.wfckresults
+                    .theory_func_instances_mut()
+                    .insert(app.func, instance);
+            } else {
+                errors.emit(errors::InvalidBvSizes::new(app.span, app.name, &sizes));
+            }
+        }
+        errors.into_result()?;
+        Ok(self.wfckresults)
     }
 
     pub(crate) fn infer_mode(&self, id: fhir::ParamId) -> fhir::InferMode {
@@ -747,27 +962,32 @@ impl<'a, 'genv, 'tcx> ImplicitParamInferer<'a, 'genv, 'tcx> {
                 self.infcx.equate(&found, expected);
             }
             fhir::ExprKind::Record(flds) => {
-                if let rty::Sort::App(rty::SortCtor::Adt(sort_def), sort_args) = expected {
-                    let sorts = sort_def.field_sorts(sort_args);
-                    if flds.len() != sorts.len() {
+                let sorts = match expected {
+                    rty::Sort::App(rty::SortCtor::Adt(sort_def), sort_args) => {
+                        sort_def.field_sorts(sort_args)
+                    }
+                    rty::Sort::Tuple(sorts) if sorts.len() > 1 => sorts.clone(),
+                    _ => {
                         self.errors.emit(errors::ArgCountMismatch::new(
                             Some(idx.span),
                             String::from("type"),
-                            sorts.len(),
+                            1,
                             flds.len(),
                         ));
                         return;
                     }
-                    for (f, sort) in iter::zip(flds, &sorts) {
-                        self.infer_implicit_params(f, sort);
-                    }
-                } else {
+                };
+                if flds.len() != sorts.len() {
                     self.errors.emit(errors::ArgCountMismatch::new(
                         Some(idx.span),
                         String::from("type"),
-                        1,
+                        sorts.len(),
                         flds.len(),
                     ));
+                    return;
+                }
+                for (f, sort) in iter::zip(flds, &sorts) {
+                    self.infer_implicit_params(f, sort);
                 }
             }
             _ => {}
@@ -818,6 +1038,10 @@ fn synth_lit(lit: fhir::Lit) -> rty::Sort {
     }
 }
 
+fn data_sort(name: Symbol) -> rty::Sort {
+    rty::Sort::App(rty::SortCtor::Data(name), rty::List::empty())
+}
+
 struct ShallowResolver<'a, 'genv, 'tcx> {
     infcx: &'a mut InferCtxt<'genv, 'tcx>,
 }
@@ -0,0 +1,213 @@
+//! Termination checking for recursive refinement functions.
+//!
+//! A refinement function can call itself as long as there's a parameter that decreases in every
+//! recursive call. A parameter decreases if either
+//! * it has a data sort and the argument is a variable bound by a `match` on the parameter (or on
+//!   a variable that is itself smaller than the parameter), i.e., the recursion is structural, or
+//! * it has sort `int` and the argument is `x - k` for a positive literal `k`, where the call is
+//!   guarded by a condition implying `x >= k`, e.g., `if x > 0 { f(x - 1) } else { .. }`.
+//!
+//! The check is syntactic and deliberately simple. Mutually recursive functions are not supported
+//! and are reported as a definition cycle.
+
+use flux_middle::{
+    fhir::{self, visit::Visitor, BinOp, ExprRes, UnOp},
+    global_env::GlobalEnv,
+    walk_list,
+};
+use rustc_data_structures::unord::UnordMap;
+use rustc_errors::ErrorGuaranteed;
+use rustc_hash::FxHashSet;
+use rustc_span::Span;
+
+use super::errors;
+
+type Result<T = ()> = std::result::Result<T, ErrorGuaranteed>;
+
+/// Checks that all the recursive calls in the body of `func` decrease some parameter. This
+/// assumes `func` is well-sorted.
+pub(crate) fn check(genv: GlobalEnv, func: &fhir::SpecFunc) -> Result {
+    let Some(body) = &func.body else { return Ok(()) };
+
+    let mut checker = TerminationChecker::new(func);
+    checker.visit_expr(body);
+
+    let Some(first) = checker.calls.first() else { return Ok(()) };
+    if func.params > 0 {
+        return Err(genv
+            .sess()
+            .emit_err(errors::PolymorphicRecursiveDefn::new(first.span, func.name)));
+    }
+
+    let mut decreasing: FxHashSet<usize> = (0..func.args.len()).collect();
+    for call in &checker.calls {
+        decreasing.retain(|i| call.decreasing.contains(i));
+        if decreasing.is_empty() {
+            return Err(genv
+                .sess()
+                .emit_err(errors::CannotProveTermination::new(call.span, func.name)));
+        }
+    }
+    Ok(())
+}
+
+struct RecursiveCall {
+    span: Span,
+    /// Positions of the arguments that are smaller than the corresponding parameter
+    decreasing: FxHashSet<usize>,
+}
+
+struct TerminationChecker<'a, 'fhir> {
+    func: &'a fhir::SpecFunc<'fhir>,
+    /// Maps each parameter of the function to its position
+    params: UnordMap<fhir::ParamId, usize>,
+    /// Variables bound by a `match` mapped to the position of the parameter they are structurally
+    /// smaller than
+    smaller: UnordMap<fhir::ParamId, usize>,
+    /// Lower bounds on integer parameters implied by the conditions of the enclosing `if`s. Each
+    /// entry is the position of a parameter and a bound.
+    bounds: Vec<(usize, i128)>,
+    calls: Vec<RecursiveCall>,
+}
+
+impl<'a, 'fhir> TerminationChecker<'a, 'fhir> {
+    fn new(func: &'a fhir::SpecFunc<'fhir>) -> Self {
+        let params = func
+            .args
+            .iter()
+            .enumerate()
+            .map(|(i, param)| (param.id, i))
+            .collect();
+        Self { func, params, smaller: Default::default(), bounds: vec![], calls: vec![] }
+    }
+
+    /// Visits `expr` assuming `cond` holds if `polarity` is true or that it doesn't hold otherwise
+    fn visit_guarded(&mut self, cond: &fhir::Expr, polarity: bool, expr: &fhir::Expr<'fhir>) {
+        let len = self.bounds.len();
+        self.collect_bounds(cond, polarity);
+        self.visit_expr(expr);
+        self.bounds.truncate(len);
+    }
+
+    fn collect_bounds(&mut self, cond: &fhir::Expr, polarity: bool) {
+        match &cond.kind {
+            fhir::ExprKind::UnaryOp(UnOp::Not, e) => self.collect_bounds(e, !polarity),
+            fhir::ExprKind::BinaryOp(BinOp::And, e1, e2) if polarity => {
+                self.collect_bounds(e1, polarity);
+                self.collect_bounds(e2, polarity);
+            }
+            fhir::ExprKind::BinaryOp(BinOp::Or, e1, e2) if !polarity => {
+                self.collect_bounds(e1, polarity);
+                self.collect_bounds(e2, polarity);
+            }
+            fhir::ExprKind::BinaryOp(op, e1, e2) => {
+                let op = if polarity { *op } else { negate_rel(*op) };
+                let bound = match (op, self.position_of(e1, false), int_lit(e2)) {
+                    (BinOp::Gt, Some(pos), Some(c)) => Some((pos, c + 1)),
+                    (BinOp::Ge, Some(pos), Some(c)) => Some((pos, c)),
+                    _ => {
+                        match (op, int_lit(e1), self.position_of(e2, false)) {
+                            (BinOp::Lt, Some(c), Some(pos)) => Some((pos, c + 1)),
+                            (BinOp::Le, Some(c), Some(pos)) => Some((pos, c)),
+                            _ => None,
+                        }
+                    }
+                };
+                self.bounds.extend(bound);
+            }
+            _ => {}
+        }
+    }
+
+    /// If `expr` is a parameter of the function returns its position. If `allow_smaller` is true,
+    /// this also returns the position for variables structurally smaller than a parameter.
+    fn position_of(&self, expr: &fhir::Expr, allow_smaller: bool) -> Option<usize> {
+        let fhir::ExprKind::Var(path, _) = &expr.kind else { return None };
+        let ExprRes::Param(_, id) = path.res else { return None };
+        if let Some(pos) = self.params.get(&id) {
+            return Some(*pos);
+        }
+        if allow_smaller {
+            self.smaller.get(&id).copied()
+        } else {
+            None
+        }
+    }
+
+    fn is_smaller(&self, arg: &fhir::Expr, pos: usize) -> bool {
+        // Structural recursion
+        if let fhir::ExprKind::Var(path, _) = &arg.kind
+            && let ExprRes::Param(_, id) = path.res
+            && self.smaller.get(&id) == Some(&pos)
+        {
+            return true;
+        }
+        // Decreasing integer
+        if let fhir::ExprKind::BinaryOp(BinOp::Sub, e1, e2) = &arg.kind
+            && self.position_of(e1, false) == Some(pos)
+            && let Some(k) = int_lit(e2)
+            && k > 0
+        {
+            return self.bounds.iter().any(|&(p, lo)| p == pos && lo >= k);
+        }
+        false
+    }
+}
+
+impl<'fhir> Visitor<'fhir> for TerminationChecker<'_, 'fhir> {
+    fn visit_expr(&mut self, expr: &fhir::Expr<'fhir>) {
+        match &expr.kind {
+            fhir::ExprKind::IfThenElse(p, e1, e2) => {
+                self.visit_expr(p);
+                self.visit_guarded(p, true, e1);
+                self.visit_guarded(p, false, e2);
+            }
+            fhir::ExprKind::Match(scrutinee, arms) => {
+                self.visit_expr(scrutinee);
+                let pos = self.position_of(scrutinee, true);
+                for arm in *arms {
+                    if let (Some(pos), fhir::MatchPat::Ctor(_, params)) = (pos, &arm.pat) {
+                        for param in *params {
+                            self.smaller.insert(param.id, pos);
+                        }
+                    }
+                    self.visit_expr(&arm.body);
+                }
+            }
+            fhir::ExprKind::App(func, args) => {
+                if let ExprRes::GlobalFunc(fhir::SpecFuncKind::Def, name) = func.res
+                    && name == self.func.name
+                {
+                    let decreasing = args
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, arg)| self.is_smaller(arg, *i))
+                        .map(|(i, _)| i)
+                        .collect();
+                    self.calls
+                        .push(RecursiveCall { span: expr.span, decreasing });
+                }
+                walk_list!(self, visit_expr, *args);
+            }
+            _ => fhir::visit::walk_expr(self, expr),
+        }
+    }
+}
+
+fn negate_rel(op: BinOp) -> BinOp {
+    match op {
+        BinOp::Gt => BinOp::Le,
+        BinOp::Ge => BinOp::Lt,
+        BinOp::Lt => BinOp::Ge,
+        BinOp::Le => BinOp::Gt,
+        _ => op,
+    }
+}
+
+fn int_lit(expr: &fhir::Expr) -> Option<i128> {
+    match &expr.kind {
+        fhir::ExprKind::Literal(fhir::Lit::Int(n)) => Some(*n),
+        fhir::ExprKind::UnaryOp(UnOp::Neg, e) => int_lit(e).map(|n| -n),
+        _ => None,
+    }
+}
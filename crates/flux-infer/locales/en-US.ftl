@@ -0,0 +1,4 @@
+infer_seq_unsupported =
+    sequences are not supported by the `fixpoint` backend
+    .note = the refinements of this {$def_descr} use the `Seq` sort
+    .help = use the native backend with `#[flux::opts(fixpoint_backend = "native")]`
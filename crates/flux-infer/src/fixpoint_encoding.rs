@@ -1,6 +1,12 @@
 //! Encoding of the refinement tree into a fixpoint constraint.
 
-use std::{hash::Hash, iter};
+use std::{
+    hash::Hash,
+    io, iter,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
 use flux_common::{
     bug,
@@ -22,9 +28,12 @@ use flux_middle::{
     MaybeExternId,
 };
 use itertools::Itertools;
-use liquid_fixpoint::{FixpointResult, SmtSolver};
+use liquid_fixpoint::{
+    FixpointResult, FixpointStatus, FromSolution, Identifier, KVarBind, RenderedTask,
+    ResourceLimits, SmtSolver, Stats,
+};
 use rustc_data_structures::{
-    fx::FxIndexMap,
+    fx::{FxIndexMap, FxIndexSet},
     unord::{UnordMap, UnordSet},
 };
 use rustc_hir::def_id::{DefId, LocalDefId};
@@ -38,7 +47,7 @@ pub mod fixpoint {
 
     use flux_middle::{
         big_int::BigInt,
-        rty::{EarlyReftParam, Real},
+        rty::{self, EarlyReftParam, Real},
     };
     use liquid_fixpoint::{FixpointFmt, Identifier};
     use rustc_index::newtype_index;
@@ -81,6 +90,17 @@ pub mod fixpoint {
         Itf(Symbol),
         Param(EarlyReftParam),
         ConstGeneric(ParamConst),
+        /// The `i`-th parameter of a kvar in the solution reported by fixpoint
+        KVarParam(usize),
+        /// Constructor of a user declared data sort
+        DataCtor(rty::DataCtor),
+        /// Tester for a constructor of a user declared data sort. The name must be the name of the
+        /// constructor prefixed with `is$` as expected by fixpoint.
+        DataTester(rty::DataCtor),
+        DataProj {
+            ctor: rty::DataCtor,
+            field: u32,
+        },
     }
 
     impl From<GlobalVar> for Var {
@@ -117,6 +137,12 @@ pub mod fixpoint {
                 Var::Param(param) => {
                     write!(f, "reftgen${}${}", param.name, param.index)
                 }
+                Var::KVarParam(idx) => write!(f, "karg${idx}"),
+                Var::DataCtor(ctor) => write!(f, "mk${}${}", ctor.sort, ctor.name),
+                Var::DataTester(ctor) => write!(f, "is$mk${}${}", ctor.sort, ctor.name),
+                Var::DataProj { ctor, field } => {
+                    write!(f, "sel${}${}${field}", ctor.sort, ctor.name)
+                }
             }
         }
     }
@@ -124,6 +150,7 @@ pub mod fixpoint {
     #[derive(Clone, Hash)]
     pub enum DataSort {
         Tuple(usize),
+        User(Symbol),
     }
 
     impl Identifier for DataSort {
@@ -132,6 +159,7 @@ pub mod fixpoint {
                 DataSort::Tuple(arity) => {
                     write!(f, "Tuple{arity}")
                 }
+                DataSort::User(name) => write!(f, "Data${name}"),
             }
         }
     }
@@ -139,9 +167,21 @@ pub mod fixpoint {
     #[derive(Hash)]
     pub struct SymStr(pub Symbol);
 
+    /// Strings are printed as SMT-LIB string literals: a double quote is escaped by doubling it and
+    /// any character that is not printable ASCII (including the backslash, which starts an escape
+    /// sequence) is written as a `\u{..}` escape.
     impl FixpointFmt for SymStr {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "\"{}\"", self.0)
+            write!(f, "\"")?;
+            for c in self.0.as_str().chars() {
+                match c {
+                    '"' => write!(f, "\"\"")?,
+                    '\\' => write!(f, "\\u{{5c}}")?,
+                    ' '..='~' => write!(f, "{c}")?,
+                    _ => write!(f, "\\u{{{:x}}}", c as u32)?,
+                }
+            }
+            write!(f, "\"")
         }
     }
 
@@ -178,14 +218,55 @@ impl<'de> Deserialize<'de> for TagIdx {
 }
 
 /// Keep track of all the data sorts that we need to define in fixpoint to encode the constraint.
-/// Currently, we encode all aggregate sorts as tuples.
+/// Currently, we encode all aggregate sorts as tuples. User declared data sorts are encoded as
+/// fixpoint datatypes.
 #[derive(Default)]
 struct SortEncodingCtxt {
     /// Set of all the tuple arities that need to be defined
     tuples: UnordSet<usize>,
+    /// Set of all the user declared data sorts that need to be defined
+    data_sorts: FxIndexSet<Symbol>,
+    backend: config::FixpointBackend,
+    /// Whether a sequence was encoded with a backend that doesn't support them
+    unsupported_seq: bool,
 }
 
 impl SortEncodingCtxt {
+    fn new(backend: config::FixpointBackend) -> Self {
+        Self { backend, ..Default::default() }
+    }
+
+    /// The `fixpoint` binary doesn't support the theory of sequences. Sequences are still encoded,
+    /// as integers and uninterpreted functions, so we can keep going, but the query is reported as
+    /// an error instead of silently weakening it.
+    fn supports_seq(&mut self) -> bool {
+        let supported = matches!(self.backend, config::FixpointBackend::Native);
+        self.unsupported_seq |= !supported;
+        supported
+    }
+
+    /// Whether the backend interprets the theory function with the given fixpoint name. Functions
+    /// that are not interpreted are encoded as uninterpreted functions.
+    fn supports_theory_func(&mut self, itf: Symbol) -> bool {
+        match itf.as_str() {
+            // The `fixpoint` binary only interprets `strLen`
+            "strAt" | "strConcat" | "strPrefixOf" | "strContains" | "subString" => {
+                matches!(self.backend, config::FixpointBackend::Native)
+            }
+            name if name.starts_with("Seq_") => self.supports_seq(),
+            _ => {
+                match int_bv_conversion(itf) {
+                    // The `fixpoint` binary only knows about conversions for 32 and 64 bits
+                    Some((size, _)) => {
+                        matches!(self.backend, config::FixpointBackend::Native)
+                            || matches!(size, 32 | 64)
+                    }
+                    None => true,
+                }
+            }
+        }
+    }
+
     fn sort_to_fixpoint(&mut self, sort: &rty::Sort) -> fixpoint::Sort {
         match sort {
             rty::Sort::Int => fixpoint::Sort::Int,
@@ -207,10 +288,25 @@ impl SortEncodingCtxt {
                 let args = args.iter().map(|s| self.sort_to_fixpoint(s)).collect_vec();
                 fixpoint::Sort::App(fixpoint::SortCtor::Set, args)
             }
+            rty::Sort::App(rty::SortCtor::Data(name), _) => {
+                self.data_sorts.insert(*name);
+                fixpoint::Sort::App(
+                    fixpoint::SortCtor::Data(fixpoint::DataSort::User(*name)),
+                    vec![],
+                )
+            }
             rty::Sort::App(rty::SortCtor::Map, args) => {
                 let args = args.iter().map(|s| self.sort_to_fixpoint(s)).collect_vec();
                 fixpoint::Sort::App(fixpoint::SortCtor::Map, args)
             }
+            rty::Sort::App(rty::SortCtor::Seq, args) => {
+                if self.supports_seq() {
+                    let args = args.iter().map(|s| self.sort_to_fixpoint(s)).collect_vec();
+                    fixpoint::Sort::App(fixpoint::SortCtor::Seq, args)
+                } else {
+                    fixpoint::Sort::Int
+                }
+            }
             rty::Sort::App(rty::SortCtor::Adt(sort_def), args) => {
                 let sorts = sort_def.field_sorts(args);
                 // do not generate 1-tuples
@@ -260,7 +356,43 @@ impl SortEncodingCtxt {
         self.tuples.insert(arity);
     }
 
-    fn into_data_decls(self) -> Vec<fixpoint::DataDecl> {
+    fn into_data_decls(mut self, genv: GlobalEnv) -> QueryResult<Vec<fixpoint::DataDecl>> {
+        // Encoding the fields of a data sort may require more sorts to be declared, so we keep
+        // going until we reach a fixpoint.
+        let mut decls = vec![];
+        let mut i = 0;
+        while let Some(name) = self.data_sorts.get_index(i).copied() {
+            let sort_def = genv.data_sort_def(name)?;
+            decls.push(self.data_sort_to_fixpoint(&sort_def));
+            i += 1;
+        }
+        decls.extend(self.tuple_decls());
+        Ok(decls)
+    }
+
+    fn data_sort_to_fixpoint(&mut self, sort_def: &rty::DataSortDef) -> fixpoint::DataDecl {
+        let ctors = iter::zip(sort_def.ctors(), &sort_def.variants)
+            .map(|(ctor, variant)| {
+                fixpoint::DataCtor {
+                    name: fixpoint::Var::DataCtor(ctor),
+                    fields: variant
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .map(|(field, sort)| {
+                            fixpoint::DataField {
+                                name: fixpoint::Var::DataProj { ctor, field: field as u32 },
+                                sort: self.sort_to_fixpoint(sort),
+                            }
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        fixpoint::DataDecl { name: fixpoint::DataSort::User(sort_def.name), vars: 0, ctors }
+    }
+
+    fn tuple_decls(self) -> Vec<fixpoint::DataDecl> {
         self.tuples
             .into_items()
             .into_sorted_stable_ord()
@@ -286,6 +418,18 @@ impl SortEncodingCtxt {
     }
 }
 
+/// If `itf` is the name of a conversion between integers and bit-vectors of a fixed size, e.g.,
+/// `int_to_bv8` or `bv8_to_int`, returns the size and whether it converts into a bit-vector. See
+/// [`flux_middle::sized_theory_func_instance`].
+fn int_bv_conversion(itf: Symbol) -> Option<(usize, bool)> {
+    let name = itf.as_str();
+    if let Some(size) = name.strip_prefix("int_to_bv") {
+        return Some((size.parse().ok()?, true));
+    }
+    let size = name.strip_prefix("bv")?.strip_suffix("_to_int")?;
+    Some((size.parse().ok()?, false))
+}
+
 fn bv_size_to_fixpoint(size: rty::BvSize) -> fixpoint::Sort {
     match size {
         rty::BvSize::Fixed(size) => fixpoint::Sort::BvSize(size),
@@ -300,11 +444,60 @@ fn bv_size_to_fixpoint(size: rty::BvSize) -> fixpoint::Sort {
     }
 }
 
+/// An assignment of values to the variables in scope of a failing constraint, restricted to
+/// the variables that have a name in the source code.
+#[derive(Debug, Default)]
+pub struct Counterexample(Vec<(Symbol, String)>);
+
+impl Counterexample {
+    fn from_model(source_names: &UnordMap<String, Symbol>, model: liquid_fixpoint::Model) -> Self {
+        let assignments = model
+            .0
+            .into_iter()
+            .filter_map(|(var, value)| Some((*source_names.get(&var)?, pretty_smt_value(&value))))
+            .unique_by(|(name, _)| *name)
+            .collect();
+        Counterexample(assignments)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl std::fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .format_with(", ", |(name, value), f| f(&format_args!("`{name} = {value}`")))
+        )
+    }
+}
+
+/// Prints negative numerals as Rust literals, i.e., `(- 3)` as `-3`. Other values are left as
+/// printed by the solver.
+fn pretty_smt_value(value: &str) -> String {
+    if let Some(n) = value
+        .strip_prefix("(- ")
+        .and_then(|s| s.strip_suffix(")"))
+        .filter(|n| n.chars().all(|c| c.is_ascii_digit() || c == '.'))
+    {
+        format!("-{n}")
+    } else {
+        value.to_string()
+    }
+}
+
 type ConstMap<'tcx> = FxIndexMap<Key<'tcx>, ConstInfo>;
 
 #[derive(Eq, Hash, PartialEq)]
 enum Key<'tcx> {
     Uif(Symbol),
+    /// A recursive spec function which is encoded with a definition
+    Defn(Symbol),
     Const(DefId),
     Alias(rustc_middle::ty::TraitRef<'tcx>),
     Lambda(Lambda),
@@ -322,44 +515,533 @@ pub struct FixpointCtxt<'genv, 'tcx, T: Eq + Hash> {
     /// [`DefId`] of the item being checked. This can be a function/method or an adt when checking
     /// invariants.
     def_id: MaybeExternId,
+    /// Maps the name of variables in the fixpoint constraint to the name they have in the source
+    /// code (if any). Used to report counterexamples.
+    source_names: UnordMap<String, Symbol>,
 }
 
 pub type FixQueryCache = QueryCache<FixpointResult<TagIdx>>;
 
+/// A fixpoint constraint that has been encoded but whose result may not be known yet. Queries
+/// can be solved one at a time with [`FixpointQuery::solve`] or concurrently with a
+/// [`SolverPool`].
+pub struct FixpointQuery<Tag> {
+    hash: u64,
+    def_span: Span,
+    tags: IndexVec<TagIdx, Tag>,
+    source_names: UnordMap<String, Symbol>,
+    limits: ResourceLimits,
+    /// Only present when dumping the checker trace or if the solution was requested
+    decoder: Option<SolutionDecoder>,
+    stats: QueryStats,
+    state: QueryState,
+}
+
+/// The result of solving a [`FixpointQuery`]: the tags of the failing constraints, or the reason
+/// the solver couldn't find an answer.
+pub type FixpointOutcome<Tag> = Result<Vec<(Tag, Counterexample)>, SolverError>;
+
+/// Everything we learn from solving a [`FixpointQuery`]
+pub struct SolvedQuery<Tag> {
+    pub outcome: FixpointOutcome<Tag>,
+    /// The solution found for the kvars. Only available if the query was encoded with
+    /// `decode_solution` set, empty otherwise.
+    pub solution: KVarSolutions,
+    /// This is `None` if the constraint was trivial and there was nothing to solve
+    pub stats: Option<QueryStats>,
+}
+
+/// Statistics about solving a [`FixpointQuery`]
+#[derive(Clone, Debug, Default)]
+pub struct QueryStats {
+    pub kvars: usize,
+    pub qualifiers: usize,
+    /// Whether the result was found in the cache
+    pub cached: bool,
+    /// Time spent running the solver. This is zero if the result was cached.
+    pub solve_time: Duration,
+    /// The statistics reported by fixpoint. These are only available if the solver found an
+    /// answer, including when the answer came from the cache.
+    pub fixpoint: Option<Stats>,
+}
+
+/// A resource limit exceeded while solving a query
+#[derive(Clone, Copy, Debug)]
+pub enum LimitExceeded {
+    Timeout(Duration),
+    /// The memory limit in bytes
+    Memory(u64),
+}
+
+/// Why the solver couldn't find an answer for a query
+#[derive(Clone, Debug)]
+pub enum SolverError {
+    LimitExceeded(LimitExceeded),
+    /// The solver couldn't be run or it crashed, e.g., because it's not installed. The string
+    /// describes the failure.
+    Crash(String),
+}
+
+enum QueryState {
+    /// The constraint was trivial and there's nothing to solve
+    Trivial,
+    /// The result was found in the cache
+    Cached(FixpointResult<TagIdx>),
+    /// The task was solved but the result is not yet in the cache
+    Solved(FixpointResult<TagIdx>),
+    /// The task still needs to be sent to `fixpoint`
+    Pending(RenderedTask),
+    /// The task was sent to a [`SolverPool`] and we are waiting for the result
+    Dispatched,
+    /// The solver was stopped or crashed before it found a result. This is not cached because
+    /// the query may succeed with different limits or in a different environment.
+    Failed(SolverError),
+}
+
+impl<Tag: Copy> FixpointQuery<Tag> {
+    fn trivial(def_span: Span) -> Self {
+        FixpointQuery {
+            hash: 0,
+            def_span,
+            tags: IndexVec::new(),
+            source_names: Default::default(),
+            limits: ResourceLimits::default(),
+            decoder: None,
+            stats: QueryStats::default(),
+            state: QueryState::Trivial,
+        }
+    }
+
+    /// Solves the query in the current thread and returns the tags of the failing constraints
+    pub fn solve(self, cache: &mut FixQueryCache) -> FixpointOutcome<Tag> {
+        self.solve_full(cache).outcome
+    }
+
+    /// Like [`FixpointQuery::solve`] but it also returns the solution found for the kvars and
+    /// statistics about the query.
+    pub fn solve_full(mut self, cache: &mut FixQueryCache) -> SolvedQuery<Tag> {
+        if let QueryState::Pending(task) = &self.state {
+            let (result, time) = run_timed(|| task.run());
+            self.set_result(result, time);
+        }
+        self.finish(cache)
+    }
+
+    fn set_result(&mut self, result: io::Result<FixpointResult<TagIdx>>, time: Duration) {
+        self.stats.solve_time = time;
+        self.state = match result {
+            Ok(result) => QueryState::Solved(result),
+            Err(err) if err.kind() == io::ErrorKind::TimedOut => {
+                let timeout = self.limits.timeout.unwrap_or_default();
+                QueryState::Failed(SolverError::LimitExceeded(LimitExceeded::Timeout(timeout)))
+            }
+            Err(err) if err.kind() == io::ErrorKind::OutOfMemory => {
+                let memory = self.limits.memory.unwrap_or_default();
+                QueryState::Failed(SolverError::LimitExceeded(LimitExceeded::Memory(memory)))
+            }
+            Err(err) => {
+                QueryState::Failed(SolverError::Crash(format!("failed to run fixpoint: {err}")))
+            }
+        };
+    }
+
+    fn finish(mut self, cache: &mut FixQueryCache) -> SolvedQuery<Tag> {
+        let result = match self.state {
+            QueryState::Trivial => {
+                return SolvedQuery {
+                    outcome: Ok(vec![]),
+                    solution: KVarSolutions::default(),
+                    stats: None,
+                };
+            }
+            QueryState::Failed(err) => {
+                return SolvedQuery {
+                    outcome: Err(err),
+                    solution: KVarSolutions::default(),
+                    stats: Some(self.stats),
+                };
+            }
+            QueryState::Cached(result) => {
+                self.stats.cached = true;
+                result
+            }
+            QueryState::Solved(result) => {
+                // A crash may be caused by the environment, e.g., a missing solver, so we don't
+                // want to remember it.
+                if !matches!(result.status, FixpointStatus::Crash(_)) {
+                    cache.insert(self.hash, &result);
+                }
+                result
+            }
+            QueryState::Pending(_) | QueryState::Dispatched => {
+                span_bug!(self.def_span, "fixpoint query was not solved")
+            }
+        };
+        let solution = match &self.decoder {
+            Some(decoder) => decoder.decode(&result.solution),
+            None => KVarSolutions::default(),
+        };
+        let outcome = match result.status {
+            FixpointStatus::Safe(stats) => {
+                self.stats.fixpoint = Some(stats);
+                Ok(vec![])
+            }
+            FixpointStatus::Unsafe(stats, errors) => {
+                self.stats.fixpoint = Some(stats);
+                let errors = errors
+                    .into_iter()
+                    .unique_by(|err| err.tag)
+                    .map(|err| {
+                        let cex = Counterexample::from_model(&self.source_names, err.model);
+                        (self.tags[err.tag], cex)
+                    })
+                    .collect_vec();
+                Ok(errors)
+            }
+            FixpointStatus::Crash(err) => Err(SolverError::Crash(format!("{err:?}"))),
+        };
+        SolvedQuery { outcome, solution, stats: Some(self.stats) }
+    }
+}
+
+fn run_timed<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// A pool of threads solving fixpoint queries in the background. Each worker waits on its own
+/// `fixpoint` process, so at most `jobs` processes run at the same time. Results are collected by
+/// [`SolverPool::finish`] in the order queries were submitted, independently of the order in which
+/// they finish.
+pub struct SolverPool<Tag> {
+    tasks: Option<mpsc::Sender<(usize, RenderedTask)>>,
+    results: mpsc::Receiver<(usize, io::Result<FixpointResult<TagIdx>>, Duration)>,
+    workers: Vec<thread::JoinHandle<()>>,
+    queries: Vec<FixpointQuery<Tag>>,
+}
+
+impl<Tag: Copy> SolverPool<Tag> {
+    pub fn new(jobs: usize) -> Self {
+        let (task_sender, task_receiver) = mpsc::channel::<(usize, RenderedTask)>();
+        let (result_sender, results) = mpsc::channel();
+        let task_receiver = Arc::new(Mutex::new(task_receiver));
+        let workers = (0..jobs)
+            .map(|_| {
+                let tasks = Arc::clone(&task_receiver);
+                let results = result_sender.clone();
+                thread::spawn(move || {
+                    loop {
+                        // The lock is released before running the task
+                        let Ok((idx, task)) = tasks.lock().unwrap().recv() else { break };
+                        let (result, time) = run_timed(|| task.run());
+                        if results.send((idx, result, time)).is_err() {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+        SolverPool { tasks: Some(task_sender), results, workers, queries: vec![] }
+    }
+
+    /// Submits a query to be solved in the background. Queries that don't need to be sent to
+    /// `fixpoint` are kept as they are.
+    pub fn submit(&mut self, mut query: FixpointQuery<Tag>) {
+        let idx = self.queries.len();
+        query.state = match query.state {
+            QueryState::Pending(task) => {
+                self.tasks.as_ref().unwrap().send((idx, task)).unwrap();
+                QueryState::Dispatched
+            }
+            state => state,
+        };
+        self.queries.push(query);
+    }
+
+    /// Waits for all submitted queries and returns their results in submission order.
+    pub fn finish(mut self, cache: &mut FixQueryCache) -> Vec<SolvedQuery<Tag>> {
+        // Dropping the sender signals workers to stop once the queue is drained
+        self.tasks = None;
+        for (idx, result, time) in &self.results {
+            self.queries[idx].set_result(result, time);
+        }
+        for worker in self.workers {
+            worker.join().unwrap();
+        }
+        self.queries
+            .into_iter()
+            .map(|query| query.finish(cache))
+            .collect()
+    }
+}
+
+/// The solution inferred for each [`rty::KVar`] as a predicate over its arguments. Solutions are
+/// approximated: conjuncts that cannot be mapped back from fixpoint are dropped.
+#[derive(Default)]
+pub struct KVarSolutions(FxIndexMap<rty::KVid, Lambda>);
+
+impl KVarSolutions {
+    pub fn get(&self, kvid: rty::KVid) -> Option<&Lambda> {
+        self.0.get(&kvid)
+    }
+}
+
+/// Maps the solution reported by fixpoint back to [`rty`], e.g., to include the inferred kvars in
+/// the checker trace.
+struct SolutionDecoder {
+    span: tracing::Span,
+    /// For each fixpoint kvar, the kvar it encodes and its number of arguments. A fixpoint kvar
+    /// may take fewer arguments than the [`rty::KVar`], in which case it takes the last ones (see
+    /// [`KVarEncoding::Conj`]).
+    kvars: IndexVec<fixpoint::KVid, (rty::KVid, usize)>,
+    /// The sorts of the arguments of each [`rty::KVar`]
+    sorts: IndexVec<rty::KVid, Vec<rty::Sort>>,
+    /// The value of the global constants that can be mapped back
+    globals: UnordMap<fixpoint::GlobalVar, rty::Expr>,
+}
+
+impl SolutionDecoder {
+    fn new(
+        genv: GlobalEnv,
+        def_id: MaybeExternId,
+        kvar_gen: &KVarGen,
+        kcx: &KVarEncodingCtxt,
+        const_map: &ConstMap,
+    ) -> Self {
+        let kvars = kcx
+            .kvars
+            .iter()
+            .map(|kvar| (kvar.orig, kvar.sorts.len()))
+            .collect();
+        let sorts = kvar_gen
+            .kvars
+            .iter()
+            .map(|decl| decl.sorts.clone())
+            .collect();
+        let globals = const_map
+            .iter()
+            .filter_map(|(key, info)| {
+                let val = match key {
+                    Key::Uif(name) => {
+                        // Theory functions not supported by the backend are also encoded as uifs
+                        let kind = genv
+                            .func_decl(*name)
+                            .map_or(SpecFuncKind::Uif, |decl| decl.kind);
+                        rty::Expr::global_func(*name, kind)
+                    }
+                    Key::Defn(name) => rty::Expr::global_func(*name, SpecFuncKind::Def),
+                    Key::Const(def_id) => {
+                        info.val.clone().unwrap_or_else(|| {
+                            rty::Expr::const_def_id(*def_id, rty::ConstantInfo::Uninterpreted)
+                        })
+                    }
+                    Key::Alias(_) | Key::Lambda(_) => return None,
+                };
+                Some((info.name, val))
+            })
+            .collect();
+        let span = dbg::solution_span!(genv.tcx(), def_id.resolved_id());
+        SolutionDecoder { span, kvars, sorts, globals }
+    }
+
+    /// Maps the solution back to [`rty`]. If the checker trace is enabled, this also emits an event
+    /// with the solution of each kvar.
+    fn decode(&self, solution: &[KVarBind]) -> KVarSolutions {
+        let mut conjuncts: FxIndexMap<rty::KVid, Vec<rty::Expr>> = FxIndexMap::default();
+        for bind in solution {
+            // The solution is only informative, so we ignore what we cannot parse
+            let Ok(sol) = bind.parse(&mut SolutionParser(self)) else { continue };
+            let (kvid, arity) = self.kvars[sol.kvar];
+            conjuncts.entry(kvid).or_default().extend(
+                sol.conjuncts
+                    .iter()
+                    .filter_map(|e| self.expr_to_rty(e, kvid, arity)),
+            );
+        }
+        let solutions: FxIndexMap<_, _> = conjuncts
+            .into_iter()
+            .map(|(kvid, conjuncts)| {
+                let inputs = self.sorts[kvid].clone();
+                let fsort = rty::FuncSort::new(inputs, rty::Sort::Bool);
+                (kvid, Lambda::bind_with_fsort(rty::Expr::and_from_iter(conjuncts), fsort))
+            })
+            .collect();
+        if config::dump_checker_trace() {
+            self.span.in_scope(|| {
+                for (kvid, solution) in &solutions {
+                    dbg::kvar_solution!(kvid, solution);
+                }
+            });
+        }
+        KVarSolutions(solutions)
+    }
+
+    fn expr_to_rty(
+        &self,
+        expr: &fixpoint::Expr,
+        kvid: rty::KVid,
+        arity: usize,
+    ) -> Option<rty::Expr> {
+        let go = |e| self.expr_to_rty(e, kvid, arity);
+        let expr = match expr {
+            fixpoint::Expr::Constant(c) => {
+                let c = match c {
+                    fixpoint::Constant::Numeral(n) => rty::Constant::Int(*n),
+                    fixpoint::Constant::Decimal(r) => rty::Constant::Real(*r),
+                    fixpoint::Constant::Boolean(b) => rty::Constant::Bool(*b),
+                    fixpoint::Constant::String(s) => rty::Constant::Str(s.0),
+                };
+                rty::Expr::constant(c)
+            }
+            fixpoint::Expr::Var(fixpoint::Var::KVarParam(idx)) => {
+                let idx = (self.sorts[kvid].len() + idx).checked_sub(arity)?;
+                rty::Expr::bvar(
+                    rty::INNERMOST,
+                    BoundVar::from_usize(idx),
+                    rty::BoundReftKind::Annon,
+                )
+            }
+            fixpoint::Expr::Var(fixpoint::Var::Global(var)) => self.globals.get(var)?.clone(),
+            fixpoint::Expr::Var(_) => return None,
+            fixpoint::Expr::App(func, args) => {
+                let args = args.iter().map(go).collect::<Option<List<_>>>()?;
+                rty::Expr::app(go(func)?, args)
+            }
+            fixpoint::Expr::Neg(e) => rty::Expr::unary_op(rty::UnOp::Neg, go(e)?),
+            fixpoint::Expr::Not(e) => rty::Expr::unary_op(rty::UnOp::Not, go(e)?),
+            fixpoint::Expr::BinaryOp(op, es) => {
+                let op = match op {
+                    fixpoint::BinOp::Add => rty::BinOp::Add,
+                    fixpoint::BinOp::Sub => rty::BinOp::Sub,
+                    fixpoint::BinOp::Mul => rty::BinOp::Mul,
+                    fixpoint::BinOp::Div => rty::BinOp::Div,
+                    fixpoint::BinOp::Mod => rty::BinOp::Mod,
+                };
+                let [e1, e2] = &**es;
+                rty::Expr::binary_op(op, go(e1)?, go(e2)?)
+            }
+            fixpoint::Expr::Atom(rel, es) => {
+                // The sort of the operands is not known, but it's only needed for ordering
+                // relations over bitvectors, which are never inferred.
+                let op = match rel {
+                    fixpoint::BinRel::Eq => rty::BinOp::Eq,
+                    fixpoint::BinRel::Ne => rty::BinOp::Ne,
+                    fixpoint::BinRel::Gt => rty::BinOp::Gt(rty::Sort::Int),
+                    fixpoint::BinRel::Ge => rty::BinOp::Ge(rty::Sort::Int),
+                    fixpoint::BinRel::Lt => rty::BinOp::Lt(rty::Sort::Int),
+                    fixpoint::BinRel::Le => rty::BinOp::Le(rty::Sort::Int),
+                };
+                let [e1, e2] = &**es;
+                rty::Expr::binary_op(op, go(e1)?, go(e2)?)
+            }
+            fixpoint::Expr::Imp(es) => {
+                let [e1, e2] = &**es;
+                rty::Expr::binary_op(rty::BinOp::Imp, go(e1)?, go(e2)?)
+            }
+            fixpoint::Expr::Iff(es) => {
+                let [e1, e2] = &**es;
+                rty::Expr::binary_op(rty::BinOp::Iff, go(e1)?, go(e2)?)
+            }
+            fixpoint::Expr::IfThenElse(es) => {
+                let [p, e1, e2] = &**es;
+                rty::Expr::ite(go(p)?, go(e1)?, go(e2)?)
+            }
+            fixpoint::Expr::And(es) => {
+                rty::Expr::and_from_iter(es.iter().map(go).collect::<Option<Vec<_>>>()?)
+            }
+            fixpoint::Expr::Or(es) => {
+                rty::Expr::or_from_iter(es.iter().map(go).collect::<Option<Vec<_>>>()?)
+            }
+            fixpoint::Expr::Quant(..) => return None,
+        };
+        Some(expr)
+    }
+}
+
+/// Maps the names in a [`KVarBind`] to the fixpoint encoding
+struct SolutionParser<'a>(&'a SolutionDecoder);
+
+impl FromSolution<fixpoint::FixpointTypes> for SolutionParser<'_> {
+    fn kvar(&mut self, name: &str) -> Option<fixpoint::KVid> {
+        let idx = name
+            .trim_start_matches('$')
+            .strip_prefix('k')?
+            .parse()
+            .ok()?;
+        let kvid = fixpoint::KVid::from_u32(idx);
+        self.0.kvars.get(kvid).is_some().then_some(kvid)
+    }
+
+    fn kvar_param(&mut self, idx: usize) -> Option<fixpoint::Var> {
+        Some(fixpoint::Var::KVarParam(idx))
+    }
+
+    fn var(&mut self, name: &str) -> Option<fixpoint::Var> {
+        let var = fixpoint::GlobalVar::from_u32(name.strip_prefix('c')?.parse().ok()?);
+        self.0
+            .globals
+            .contains_key(&var)
+            .then_some(fixpoint::Var::Global(var))
+    }
+
+    fn numeral(&mut self, digits: &str) -> Option<BigInt> {
+        Some(BigInt::from(digits.parse::<u128>().ok()?))
+    }
+
+    fn decimal(&mut self, digits: &str) -> Option<rty::Real> {
+        rty::Real::from_decimal_str(digits)
+    }
+
+    fn string(&mut self, s: &str) -> Option<fixpoint::SymStr> {
+        Some(fixpoint::SymStr(Symbol::intern(s)))
+    }
+}
+
 impl<'genv, 'tcx, Tag> FixpointCtxt<'genv, 'tcx, Tag>
 where
     Tag: std::hash::Hash + Eq + Copy,
 {
-    pub fn new(genv: GlobalEnv<'genv, 'tcx>, def_id: MaybeExternId, kvars: KVarGen) -> Self {
+    pub fn new(
+        genv: GlobalEnv<'genv, 'tcx>,
+        def_id: MaybeExternId,
+        kvars: KVarGen,
+        backend: config::FixpointBackend,
+    ) -> Self {
         let def_span = genv.tcx().def_span(def_id);
         Self {
             comments: vec![],
             kvars,
-            scx: SortEncodingCtxt::default(),
+            scx: SortEncodingCtxt::new(backend),
             genv,
             ecx: ExprEncodingCtxt::new(genv, def_span),
             kcx: Default::default(),
             tags: IndexVec::new(),
             tags_inv: Default::default(),
             def_id,
+            source_names: Default::default(),
         }
     }
 
-    pub fn check(
+    /// Encodes the constraint as a [`FixpointQuery`] without waiting for its result. The query is
+    /// solved right away if it is trivial, its result is in the `cache`, or the backend cannot run
+    /// in a separate thread.
+    pub fn encode_query(
         mut self,
         cache: &mut FixQueryCache,
         constraint: fixpoint::Constraint,
         scrape_quals: bool,
         solver: SmtSolver,
-    ) -> QueryResult<Vec<Tag>> {
-        // skip checking trivial constraints
-        if !constraint.is_concrete() {
-            self.ecx.errors.into_result()?;
-            return Ok(vec![]);
-        }
+        limits: ResourceLimits,
+        decode_solution: bool,
+    ) -> QueryResult<FixpointQuery<Tag>> {
         let def_span = self.def_span();
 
-        let kvars = self.kcx.into_fixpoint();
+        // skip checking trivial constraints, unless we need the solution of the kvars
+        if !constraint.is_concrete() && !decode_solution {
+            self.ecx.errors.into_result()?;
+            return Ok(FixpointQuery::trivial(def_span));
+        }
 
         let constraint = self.ecx.assume_const_values(constraint, &mut self.scx)?;
 
@@ -367,6 +1049,19 @@ where
             .ecx
             .qualifiers_for(self.def_id.local_id(), &mut self.scx)?;
 
+        let define_funs = self.ecx.define_funs(&mut self.scx)?;
+
+        let decoder = (decode_solution || config::dump_checker_trace()).then(|| {
+            SolutionDecoder::new(
+                self.genv,
+                self.def_id,
+                &self.kvars,
+                &self.kcx,
+                &self.ecx.const_map,
+            )
+        });
+        let kvars = self.kcx.into_fixpoint();
+
         let mut constants = self
             .ecx
             .const_map
@@ -388,57 +1083,68 @@ where
             });
         }
 
+        if self.scx.unsupported_seq {
+            let tcx = self.genv.tcx();
+            let def_id = self.def_id.resolved_id();
+            self.ecx.errors.emit(errors::SeqUnsupported {
+                span: tcx.def_span(def_id),
+                def_descr: tcx.def_descr(def_id),
+            });
+        }
+
         // We are done encoding. Check if there are any errors.
         self.ecx.errors.into_result()?;
 
+        let backend = self.scx.backend;
         let task = fixpoint::Task {
             comments: self.comments,
             constants,
+            define_funs,
             kvars,
             constraint,
             qualifiers,
             scrape_quals,
             solver,
-            data_decls: self.scx.into_data_decls(),
+            limits,
+            data_decls: self.scx.into_data_decls(self.genv)?,
         };
         if config::dump_constraint() {
             dbg::dump_item_info(self.genv.tcx(), self.def_id.resolved_id(), "smt2", &task).unwrap();
         }
 
-        let task_key = self.genv.tcx().def_path_str(self.def_id);
-
-        match Self::run_task_with_cache(task, task_key, cache) {
-            FixpointResult::Safe(_) => Ok(vec![]),
-            FixpointResult::Unsafe(_, errors) => {
-                Ok(errors
-                    .into_iter()
-                    .map(|err| self.tags[err.tag])
-                    .unique()
-                    .collect_vec())
-            }
-            FixpointResult::Crash(err) => span_bug!(def_span, "fixpoint crash: {err:?}"),
-        }
-    }
-
-    fn run_task_with_cache(
-        task: fixpoint::Task,
-        key: String,
-        cache: &mut FixQueryCache,
-    ) -> FixpointResult<TagIdx> {
-        let hash = task.hash_with_default();
-        if config::is_cache_enabled()
-            && let Some(result) = cache.lookup(&key, hash)
-        {
-            return result.clone();
+        let mut rendered = task.render();
+        if decoder.is_some() {
+            rendered = rendered.with_solution();
         }
-        let result = task
-            .run()
-            .unwrap_or_else(|err| tracked_span_bug!("failed to run fixpoint: {err:?}"));
-
-        if config::is_cache_enabled() {
-            cache.insert(key, hash, result.clone());
+        let hash = rendered.stable_hash(&backend.to_string());
+        let mut query = FixpointQuery {
+            hash,
+            def_span,
+            tags: self.tags,
+            source_names: self.source_names,
+            limits,
+            decoder,
+            stats: QueryStats {
+                kvars: task.kvars.len(),
+                qualifiers: task.qualifiers.len(),
+                ..Default::default()
+            },
+            state: QueryState::Trivial,
+        };
+        if let Some(result) = cache.lookup(hash) {
+            query.state = QueryState::Cached(result);
+        } else {
+            match backend {
+                config::FixpointBackend::Fixpoint => query.state = QueryState::Pending(rendered),
+                // The native backend prints identifiers while solving, which requires the
+                // session globals, so we cannot move it to a different thread.
+                config::FixpointBackend::Native => {
+                    let (result, time) = run_timed(|| task.run_native());
+                    query.set_result(result, time);
+                }
+            }
         }
-        result
+        Ok(query)
     }
 
     fn tag_idx(&mut self, tag: Tag) -> TagIdx
@@ -455,9 +1161,14 @@ where
     pub(crate) fn with_name_map<R>(
         &mut self,
         name: rty::Name,
+        kind: rty::BoundReftKind,
         f: impl FnOnce(&mut Self, fixpoint::LocalVar) -> R,
     ) -> R {
         let fresh = self.ecx.local_var_env.insert_fvar_map(name);
+        if let rty::BoundReftKind::Named(sym) = kind {
+            self.source_names
+                .insert(fixpoint::Var::Local(fresh).display().to_string(), sym);
+        }
         let r = f(self, fresh);
         self.ecx.local_var_env.remove_fvar_map(name);
         r
@@ -467,12 +1178,26 @@ where
         self.scx.sort_to_fixpoint(sort)
     }
 
-    pub(crate) fn var_to_fixpoint(&self, var: &rty::Var) -> fixpoint::Var {
-        self.ecx.var_to_fixpoint(var)
+    /// Encodes a variable bound at the root of the constraint, i.e., a const generic or an early
+    /// bound refinement parameter. These always have a name in the source which we record for
+    /// counterexamples.
+    pub(crate) fn root_var_to_fixpoint(&mut self, var: &rty::Var) -> fixpoint::Var {
+        let fvar = self.ecx.var_to_fixpoint(var);
+        let name = match var {
+            rty::Var::EarlyParam(param) => Some(param.name),
+            rty::Var::ConstGeneric(param) => Some(param.name),
+            _ => None,
+        };
+        if let Some(name) = name {
+            self.source_names.insert(fvar.display().to_string(), name);
+        }
+        fvar
     }
 
     /// Encodes an expression in head position as a [`fixpoint::Constraint`] "peeling out"
-    /// implications and foralls.
+    /// implications and foralls. Conjunctions are split such that each conjunct gets its own tag
+    /// with the span of the sub-expression. This way we can report exactly which part of a
+    /// condition cannot be proved.
     ///
     /// [`fixpoint::Constraint`]: liquid_fixpoint::Constraint
     pub(crate) fn head_to_fixpoint(
@@ -483,19 +1208,35 @@ where
     where
         Tag: std::fmt::Debug,
     {
+        self.head_to_fixpoint_aux(expr, None, mk_tag)
+    }
+
+    /// Auxiliary function for [`FixpointCtxt::head_to_fixpoint`]. The span `enclosing` is the span
+    /// of the closest enclosing expression that has one, which is used for conjuncts that don't
+    /// have a span of their own, e.g., because they were produced by a substitution.
+    fn head_to_fixpoint_aux(
+        &mut self,
+        expr: &rty::Expr,
+        enclosing: Option<ESpan>,
+        mk_tag: impl Fn(Option<ESpan>) -> Tag + Copy,
+    ) -> QueryResult<fixpoint::Constraint>
+    where
+        Tag: std::fmt::Debug,
+    {
+        let espan = expr.span().or(enclosing);
         match expr.kind() {
             rty::ExprKind::BinaryOp(rty::BinOp::And, ..) => {
                 // avoid creating nested conjunctions
                 let cstrs = expr
                     .flatten_conjs()
                     .into_iter()
-                    .map(|e| self.head_to_fixpoint(e, mk_tag))
+                    .map(|e| self.head_to_fixpoint_aux(e, espan, mk_tag))
                     .try_collect()?;
                 Ok(fixpoint::Constraint::Conj(cstrs))
             }
             rty::ExprKind::BinaryOp(rty::BinOp::Imp, e1, e2) => {
                 let (bindings, assumption) = self.assumption_to_fixpoint(e1)?;
-                let cstr = self.head_to_fixpoint(e2, mk_tag)?;
+                let cstr = self.head_to_fixpoint_aux(e2, espan, mk_tag)?;
                 Ok(fixpoint::Constraint::foralls(bindings, mk_implies(assumption, cstr)))
             }
             rty::ExprKind::KVar(kvar) => {
@@ -507,7 +1248,7 @@ where
                 self.ecx
                     .local_var_env
                     .push_layer_with_fresh_names(pred.vars().len());
-                let cstr = self.head_to_fixpoint(pred.as_ref().skip_binder(), mk_tag)?;
+                let cstr = self.head_to_fixpoint_aux(pred.as_ref().skip_binder(), espan, mk_tag)?;
                 let vars = self.ecx.local_var_env.pop_layer();
 
                 let bindings = iter::zip(vars, pred.vars())
@@ -523,7 +1264,7 @@ where
                 Ok(fixpoint::Constraint::foralls(bindings, cstr))
             }
             _ => {
-                let tag_idx = self.tag_idx(mk_tag(expr.span()));
+                let tag_idx = self.tag_idx(mk_tag(espan));
                 let pred = fixpoint::Pred::Expr(self.ecx.expr_to_fixpoint(expr, &mut self.scx)?);
                 Ok(fixpoint::Constraint::Pred(pred, Some(tag_idx)))
             }
@@ -559,13 +1300,21 @@ where
             rty::ExprKind::KVar(kvar) => {
                 preds.push(self.kvar_to_fixpoint(kvar, bindings)?);
             }
-            rty::ExprKind::ForAll(_) => {
-                // If a forall appears in assumptive position replace it with true. This is sound
-                // because we are weakening the context, i.e., anything true without the assumption
-                // should remain true after adding it. Note that this relies on the predicate
-                // appearing negatively. This is guaranteed by the surface syntax because foralls
-                // can only appear at the top-level in a requires clause.
-                preds.push(fixpoint::Pred::TRUE);
+            rty::ExprKind::Exists(pred) => {
+                // An existential in assumptive position can be skolemized by binding the variables
+                // in the enclosing constraint.
+                self.ecx
+                    .local_var_env
+                    .push_layer_with_fresh_names(pred.vars().len());
+                self.assumption_to_fixpoint_aux(pred.as_ref().skip_binder(), bindings, preds)?;
+                let vars = self.ecx.local_var_env.pop_layer();
+                for (var, kind) in iter::zip(vars, pred.vars()) {
+                    bindings.push(fixpoint::Bind {
+                        name: var.into(),
+                        sort: self.scx.sort_to_fixpoint(kind.expect_sort()),
+                        pred: fixpoint::Pred::TRUE,
+                    });
+                }
             }
             _ => {
                 preds.push(fixpoint::Pred::Expr(self.ecx.expr_to_fixpoint(expr, &mut self.scx)?));
@@ -1008,26 +1757,79 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                 let var = self.register_const_for_lambda(lam, scx);
                 fixpoint::Expr::Var(var.into())
             }
-            rty::ExprKind::GlobalFunc(_, SpecFuncKind::Thy(sym)) => {
-                fixpoint::Expr::Var(fixpoint::Var::Itf(*sym))
+            rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Thy(itf))
+                if !scx.supports_theory_func(*itf) =>
+            {
+                let var = if let Some((size, to_bv)) = int_bv_conversion(*itf) {
+                    self.register_int_bv_conversion(*itf, size, to_bv)
+                } else {
+                    self.register_uif(*sym, scx)
+                };
+                fixpoint::Expr::Var(var.into())
+            }
+            rty::ExprKind::GlobalFunc(_, SpecFuncKind::Thy(itf)) => {
+                fixpoint::Expr::Var(fixpoint::Var::Itf(*itf))
             }
             rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Uif) => {
                 fixpoint::Expr::Var(self.register_uif(*sym, scx).into())
             }
+            rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Def)
+                if self.genv.spec_func_defns()?.recursive_defn(*sym).is_some() =>
+            {
+                fixpoint::Expr::Var(self.register_defn(*sym, scx).into())
+            }
             rty::ExprKind::GlobalFunc(sym, SpecFuncKind::Def) => {
                 span_bug!(self.def_span, "unexpected global function `{sym}`. Function must be normalized away at this point")
             }
+            rty::ExprKind::Ctor(ctor, args) => {
+                let func = fixpoint::Expr::Var(fixpoint::Var::DataCtor(*ctor));
+                if args.is_empty() {
+                    func
+                } else {
+                    fixpoint::Expr::App(Box::new(func), self.exprs_to_fixpoint(args, scx)?)
+                }
+            }
+            rty::ExprKind::IsCtor(ctor, e) => {
+                let func = fixpoint::Expr::Var(fixpoint::Var::DataTester(*ctor));
+                fixpoint::Expr::App(Box::new(func), vec![self.expr_to_fixpoint(e, scx)?])
+            }
+            rty::ExprKind::CtorField(ctor, field, e) => {
+                let func =
+                    fixpoint::Expr::Var(fixpoint::Var::DataProj { ctor: *ctor, field: *field });
+                fixpoint::Expr::App(Box::new(func), vec![self.expr_to_fixpoint(e, scx)?])
+            }
+            rty::ExprKind::ForAll(pred) => {
+                self.quant_to_fixpoint(fixpoint::Quantifier::Forall, pred, scx)?
+            }
+            rty::ExprKind::Exists(pred) => {
+                self.quant_to_fixpoint(fixpoint::Quantifier::Exists, pred, scx)?
+            }
             rty::ExprKind::Hole(..)
             | rty::ExprKind::KVar(_)
             | rty::ExprKind::Local(_)
-            | rty::ExprKind::PathProj(..)
-            | rty::ExprKind::ForAll(_) => {
+            | rty::ExprKind::PathProj(..) => {
                 span_bug!(self.def_span, "unexpected expr: `{expr:?}`")
             }
         };
         Ok(e)
     }
 
+    fn quant_to_fixpoint(
+        &mut self,
+        quant: fixpoint::Quantifier,
+        pred: &rty::Binder<rty::Expr>,
+        scx: &mut SortEncodingCtxt,
+    ) -> QueryResult<fixpoint::Expr> {
+        self.local_var_env
+            .push_layer_with_fresh_names(pred.vars().len());
+        let body = self.expr_to_fixpoint(pred.as_ref().skip_binder(), scx);
+        let vars = self.local_var_env.pop_layer();
+        let vars = iter::zip(vars, pred.vars())
+            .map(|(var, kind)| (var.into(), scx.sort_to_fixpoint(kind.expect_sort())))
+            .collect();
+        Ok(fixpoint::Expr::Quant(quant, vars, Box::new(body?)))
+    }
+
     fn exprs_to_fixpoint<'b>(
         &mut self,
         exprs: impl IntoIterator<Item = &'b rty::Expr>,
@@ -1250,7 +2052,10 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                     .genv
                     .func_decl(name)
                     .map(|decl| {
-                        debug_assert_eq!(decl.kind, fhir::SpecFuncKind::Uif);
+                        debug_assert!(matches!(
+                            decl.kind,
+                            fhir::SpecFuncKind::Uif | fhir::SpecFuncKind::Thy(_)
+                        ));
                         scx.func_sort_to_fixpoint(&decl.sort)
                     })
                     .unwrap_or_else(|err| {
@@ -1267,6 +2072,103 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
             .name
     }
 
+    /// Registers an uninterpreted function for a conversion between integers and bit-vectors of a
+    /// size not supported by the backend. We can't use [`Self::register_uif`] because the
+    /// declared sort of the conversion is polymorphic on the size.
+    fn register_int_bv_conversion(
+        &mut self,
+        itf: Symbol,
+        size: usize,
+        to_bv: bool,
+    ) -> fixpoint::GlobalVar {
+        self.const_map
+            .entry(Key::Uif(itf))
+            .or_insert_with(|| {
+                let bv = fixpoint::Sort::BitVec(Box::new(fixpoint::Sort::BvSize(size)));
+                let sort = if to_bv {
+                    fixpoint::Sort::mk_func(0, [fixpoint::Sort::Int], bv)
+                } else {
+                    fixpoint::Sort::mk_func(0, [bv], fixpoint::Sort::Int)
+                };
+                ConstInfo {
+                    name: self.global_var_gen.fresh(),
+                    sort,
+                    val: None,
+                    comment: format!("uif: {itf}"),
+                }
+            })
+            .name
+    }
+
+    /// Registers a constant for a recursive spec function. Its definition is encoded separately by
+    /// [`ExprEncodingCtxt::define_funs`].
+    fn register_defn(&mut self, name: Symbol, scx: &mut SortEncodingCtxt) -> fixpoint::GlobalVar {
+        let key = Key::Defn(name);
+        self.const_map
+            .entry(key)
+            .or_insert_with(|| {
+                let sort = self
+                    .genv
+                    .func_decl(name)
+                    .map(|decl| scx.func_sort_to_fixpoint(&decl.sort))
+                    .unwrap_or_else(|err| {
+                        self.errors.emit(err.at(self.def_span));
+                        fixpoint::Sort::Int
+                    });
+                ConstInfo {
+                    name: self.global_var_gen.fresh(),
+                    sort,
+                    val: None,
+                    comment: format!("defn: {name}"),
+                }
+            })
+            .name
+    }
+
+    /// Encodes the definitions of all the recursive spec functions registered so far
+    fn define_funs(&mut self, scx: &mut SortEncodingCtxt) -> QueryResult<Vec<fixpoint::FunDef>> {
+        let mut fun_defs = vec![];
+        // Encoding a definition can register new ones, so we cannot iterate over the map directly
+        let mut i = 0;
+        while let Some((key, info)) = self.const_map.get_index(i) {
+            if let Key::Defn(name) = key {
+                let (name, var) = (*name, info.name);
+                fun_defs.push(self.fun_def_to_fixpoint(name, var, scx)?);
+            }
+            i += 1;
+        }
+        Ok(fun_defs)
+    }
+
+    fn fun_def_to_fixpoint(
+        &mut self,
+        name: Symbol,
+        var: fixpoint::GlobalVar,
+        scx: &mut SortEncodingCtxt,
+    ) -> QueryResult<fixpoint::FunDef> {
+        let defn = self
+            .genv
+            .spec_func_defns()?
+            .recursive_defn(name)
+            .unwrap_or_else(|| span_bug!(self.def_span, "no definition found for `{name}`"))
+            .expr
+            .clone();
+        let fsort = self.genv.func_decl(name)?.sort.expect_mono();
+
+        self.local_var_env
+            .push_layer_with_fresh_names(defn.vars().len());
+        let body = self.expr_to_fixpoint(defn.as_ref().skip_binder(), scx);
+        let args = iter::zip(self.local_var_env.pop_layer(), fsort.inputs())
+            .map(|(var, sort)| (var.into(), scx.sort_to_fixpoint(sort)))
+            .collect();
+        Ok(fixpoint::FunDef {
+            name: fixpoint::Var::Global(var),
+            args,
+            out: scx.sort_to_fixpoint(fsort.output()),
+            body: body?,
+        })
+    }
+
     fn register_rust_const(
         &mut self,
         def_id: DefId,
@@ -1402,3 +2304,19 @@ fn mk_implies(assumption: fixpoint::Pred, cstr: fixpoint::Constraint) -> fixpoin
         Box::new(cstr),
     )
 }
+
+mod errors {
+    use flux_errors::E0999;
+    use flux_macros::Diagnostic;
+    use rustc_span::Span;
+
+    #[derive(Diagnostic)]
+    #[diag(infer_seq_unsupported, code = E0999)]
+    #[note]
+    #[help]
+    pub(super) struct SeqUnsupported {
+        #[primary_span]
+        pub span: Span,
+        pub def_descr: &'static str,
+    }
+}
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, fmt, iter};
+use std::{cell::RefCell, fmt, iter, time::Duration};
 
 use flux_common::{bug, dbg, tracked_span_assert_eq, tracked_span_dbg_assert_eq};
 use flux_config::{self as config, InferOpts};
@@ -9,9 +9,9 @@ use flux_middle::{
     query_bug,
     rty::{
         self, canonicalize::Hoister, fold::TypeFoldable, AliasKind, AliasTy, BaseTy, Binder,
-        BoundVariableKinds, CoroutineObligPredicate, ESpan, EVid, EarlyBinder, Expr, ExprKind,
-        GenericArg, GenericArgs, HoleKind, InferMode, Lambda, List, Loc, Mutability, Name, Path,
-        PolyVariant, PtrKind, RefineArgs, RefineArgsExt, Region, Sort, Ty, TyKind, Var,
+        BoundReftKind, BoundVariableKinds, CoroutineObligPredicate, ESpan, EVid, EarlyBinder, Expr,
+        ExprKind, GenericArg, GenericArgs, HoleKind, InferMode, Lambda, List, Loc, Mutability,
+        Name, Path, PolyVariant, PtrKind, RefineArgs, RefineArgsExt, Region, Sort, Ty, TyKind, Var,
     },
     MaybeExternId,
 };
@@ -26,7 +26,9 @@ use rustc_span::Span;
 
 use crate::{
     evars::{EVarState, EVarStore},
-    fixpoint_encoding::{FixQueryCache, FixpointCtxt, KVarEncoding, KVarGen},
+    fixpoint_encoding::{
+        FixQueryCache, FixpointCtxt, FixpointOutcome, FixpointQuery, KVarEncoding, KVarGen,
+    },
     projections::NormalizeExt as _,
     refine_tree::{AssumeInvariants, Cursor, Marker, RefineTree, Scope, Unpacker},
 };
@@ -70,6 +72,8 @@ pub enum ConstrReason {
     Rem,
     Goto(BasicBlock),
     Overflow,
+    RawPtrDeref,
+    RawPtrOffset,
     Subtype(SubtypeReason),
     Other,
 }
@@ -154,6 +158,9 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
             cursor: self.refine_tree.cursor_at_root(),
             inner: &self.inner,
             check_overflow: self.opts.check_overflow,
+            precise_bit_ops: self.opts.precise_bit_ops,
+            check_raw_pointers: self.opts.check_raw_pointers,
+            assume_panic_abort: self.opts.assume_panic_abort,
         }
     }
 
@@ -172,7 +179,18 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
         cache: &mut FixQueryCache,
         def_id: MaybeExternId,
         ext: &'static str,
-    ) -> QueryResult<Vec<Tag>> {
+    ) -> QueryResult<FixpointOutcome<Tag>> {
+        Ok(self.encode_fixpoint_query(cache, def_id, ext)?.solve(cache))
+    }
+
+    /// Like [`InferCtxtRoot::execute_fixpoint_query`] but it doesn't wait for the result of the
+    /// query, which can be later solved together with other queries.
+    pub fn encode_fixpoint_query(
+        self,
+        cache: &mut FixQueryCache,
+        def_id: MaybeExternId,
+        ext: &'static str,
+    ) -> QueryResult<FixpointQuery<Tag>> {
         let inner = self.inner.into_inner();
         let kvars = inner.kvars;
         let evars = inner.evars;
@@ -191,7 +209,7 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
                 .unwrap();
         }
 
-        let mut fcx = FixpointCtxt::new(self.genv, def_id, kvars);
+        let mut fcx = FixpointCtxt::new(self.genv, def_id, kvars, self.opts.fixpoint_backend);
         let cstr = refine_tree.into_fixpoint(&mut fcx)?;
 
         let backend = match self.opts.solver {
@@ -199,7 +217,19 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
             flux_config::SmtSolver::CVC5 => liquid_fixpoint::SmtSolver::CVC5,
         };
 
-        fcx.check(cache, cstr, self.opts.scrape_quals, backend)
+        let limits = liquid_fixpoint::ResourceLimits {
+            timeout: (self.opts.timeout > 0).then(|| Duration::from_secs(self.opts.timeout)),
+            memory: (self.opts.memory_limit > 0).then(|| self.opts.memory_limit * 1024 * 1024),
+        };
+
+        fcx.encode_query(
+            cache,
+            cstr,
+            self.opts.scrape_quals,
+            backend,
+            limits,
+            self.opts.suggest_sigs,
+        )
     }
 
     pub fn split(self) -> (RefineTree, KVarGen) {
@@ -212,6 +242,9 @@ pub struct InferCtxt<'infcx, 'genv, 'tcx> {
     pub region_infcx: &'infcx rustc_infer::infer::InferCtxt<'tcx>,
     pub def_id: DefId,
     pub check_overflow: bool,
+    pub precise_bit_ops: bool,
+    pub check_raw_pointers: bool,
+    pub assume_panic_abort: bool,
     cursor: Cursor<'infcx>,
     inner: &'infcx RefCell<InferCtxtInner>,
 }
@@ -389,6 +422,10 @@ impl<'infcx, 'genv, 'tcx> InferCtxt<'infcx, 'genv, 'tcx> {
         self.cursor.define_var(sort)
     }
 
+    pub fn define_bound_reft_var(&mut self, sort: &Sort, kind: BoundReftKind) -> Name {
+        self.cursor.define_bound_reft_var(sort, kind)
+    }
+
     pub fn check_pred(&mut self, pred: impl Into<Expr>, tag: Tag) {
         self.cursor.check_pred(pred, tag);
     }
@@ -835,14 +872,67 @@ impl<'a, E: LocEnv> Sub<'a, E> {
                 }
                 Ok(())
             }
-            (BaseTy::FnPtr(sig_a), BaseTy::FnPtr(sig_b)) => {
-                tracked_span_assert_eq!(sig_a, sig_b);
-                Ok(())
-            }
+            (BaseTy::FnPtr(sig_a), BaseTy::FnPtr(sig_b)) => self.fn_ptrs(infcx, sig_a, sig_b),
             _ => Err(query_bug!("incompatible base types: `{a:?}` - `{b:?}`"))?,
         }
     }
 
+    /// Function subtyping for function pointers. The function `sig_a` is a subtype of `sig_b`
+    /// if it accepts every input accepted by `sig_b` (contravariance) and its output is a subtype
+    /// of the output of `sig_b` (covariance).
+    fn fn_ptrs(
+        &mut self,
+        infcx: &mut InferCtxt,
+        sig_a: &rty::PolyFnSig,
+        sig_b: &rty::PolyFnSig,
+    ) -> InferResult {
+        let sig_b = sig_b.replace_bound_vars(
+            |_| rty::ReErased,
+            |sort, _, kind| Expr::fvar(infcx.define_bound_reft_var(sort, kind)),
+        );
+        tracked_span_assert_eq!(sig_a.skip_binder_ref().inputs().len(), sig_b.inputs().len());
+
+        let output_a = infcx.ensure_resolved_evars(|infcx| {
+            let sig_a = sig_a.replace_bound_vars(
+                |_| rty::ReErased,
+                |sort, mode, _| infcx.fresh_infer_var(sort, mode),
+            );
+            for requires in sig_b.requires() {
+                infcx.assume_pred(requires);
+            }
+            for (ty_a, ty_b) in iter::zip(sig_a.inputs(), sig_b.inputs()) {
+                self.tys(infcx, ty_b, ty_a)?;
+            }
+            for requires in sig_a.requires() {
+                infcx.check_pred(requires, self.tag());
+            }
+            Ok(sig_a.output())
+        })?;
+
+        let output_a = infcx
+            .fully_resolve_evars(&output_a)
+            .replace_bound_refts_with(|sort, _, kind| {
+                Expr::fvar(infcx.define_bound_reft_var(sort, kind))
+            });
+        for ensures in &output_a.ensures {
+            if let rty::Ensures::Pred(pred) = ensures {
+                infcx.assume_pred(pred);
+            }
+        }
+        infcx.ensure_resolved_evars(|infcx| {
+            let output_b = sig_b
+                .output()
+                .replace_bound_refts_with(|sort, mode, _| infcx.fresh_infer_var(sort, mode));
+            self.tys(infcx, &output_a.ret, &output_b.ret)?;
+            for ensures in &output_b.ensures {
+                if let rty::Ensures::Pred(pred) = ensures {
+                    infcx.check_pred(pred, self.tag());
+                }
+            }
+            Ok(())
+        })
+    }
+
     fn generic_args(
         &mut self,
         infcx: &mut InferCtxt,
@@ -1,6 +1,7 @@
 #![feature(extract_if, let_chains, never_type, rustc_private)]
 
 extern crate rustc_data_structures;
+extern crate rustc_errors;
 extern crate rustc_hir;
 extern crate rustc_index;
 extern crate rustc_infer;
@@ -15,3 +16,7 @@ pub mod fixpoint_encoding;
 pub mod infer;
 pub mod projections;
 pub mod refine_tree;
+
+use flux_macros::fluent_messages;
+
+fluent_messages! { "../locales/en-US.ftl" }
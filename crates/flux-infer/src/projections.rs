@@ -25,11 +25,11 @@ use rustc_trait_selection::traits::SelectionContext;
 use crate::infer::InferCtxt;
 
 pub trait NormalizeExt: TypeFoldable {
-    fn normalize_projections<'tcx>(&self, infcx: &mut InferCtxt) -> QueryResult<Self>;
+    fn normalize_projections(&self, infcx: &mut InferCtxt) -> QueryResult<Self>;
 }
 
 impl<T: TypeFoldable> NormalizeExt for T {
-    fn normalize_projections<'tcx>(&self, infcx: &mut InferCtxt) -> QueryResult<Self> {
+    fn normalize_projections(&self, infcx: &mut InferCtxt) -> QueryResult<Self> {
         let mut normalizer = Normalizer::new(infcx.branch())?;
         self.erase_regions().try_fold_with(&mut normalizer)
     }
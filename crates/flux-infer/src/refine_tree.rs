@@ -13,7 +13,7 @@ use flux_middle::{
     rty::{
         canonicalize::{Hoister, HoisterDelegate},
         fold::{TypeFoldable, TypeSuperVisitable, TypeVisitable, TypeVisitor},
-        BaseTy, EVid, Expr, Name, Sort, SpecFuncDefns, Ty, TyCtor, TyKind, Var,
+        BaseTy, BoundReftKind, EVid, Expr, Name, Sort, SpecFuncDefns, Ty, TyCtor, TyKind, Var,
     },
 };
 use itertools::Itertools;
@@ -125,8 +125,17 @@ impl<'a> Cursor<'a> {
     /// Defines a fresh refinement variable with the given `sort` and advance the cursor to the new
     /// node. It returns the freshly generated name for the variable.
     pub(crate) fn define_var(&mut self, sort: &Sort) -> Name {
+        self.define_bound_reft_var(sort, BoundReftKind::Annon)
+    }
+
+    /// Same as [`Cursor::define_var`] but it additionally records the [`BoundReftKind`] of the
+    /// binder the variable is instantiating. If the binder has a name in the source, we use it
+    /// to refer to the variable in counterexamples.
+    pub(crate) fn define_bound_reft_var(&mut self, sort: &Sort, kind: BoundReftKind) -> Name {
         let fresh = Name::from_usize(self.ptr.next_name_idx());
-        self.ptr = self.ptr.push_node(NodeKind::ForAll(fresh, sort.clone()));
+        self.ptr = self
+            .ptr
+            .push_node(NodeKind::ForAll(fresh, sort.clone(), kind));
         fresh
     }
 
@@ -216,6 +225,7 @@ impl<'a> Cursor<'a> {
 ///
 /// [cleared]: Cursor::move_to
 /// [refinement tree]: RefineTree
+#[derive(Clone)]
 pub struct Marker {
     ptr: WeakNodePtr,
 }
@@ -326,7 +336,7 @@ impl NodePtr {
                         params = Some(p.clone());
                         None
                     }
-                    NodeKind::ForAll(_, sort) => Some(sort.clone()),
+                    NodeKind::ForAll(_, sort, _) => Some(sort.clone()),
                     _ => None,
                 }
             })
@@ -338,6 +348,7 @@ impl NodePtr {
     }
 }
 
+#[derive(Clone)]
 struct WeakNodePtr(Weak<RefCell<Node>>);
 
 impl WeakNodePtr {
@@ -349,7 +360,7 @@ impl WeakNodePtr {
 enum NodeKind {
     /// List of const and refinement generics
     Root(Vec<(Var, Sort)>),
-    ForAll(Name, Sort),
+    ForAll(Name, Sort, BoundReftKind),
     Assumption(Expr),
     Head(Expr, Tag),
     True,
@@ -463,7 +474,7 @@ impl Node {
 
     fn to_fixpoint(&self, cx: &mut FixpointCtxt<Tag>) -> QueryResult<Option<fixpoint::Constraint>> {
         let cstr = match &self.kind {
-            NodeKind::Trace(_) | NodeKind::ForAll(_, Sort::Loc) => {
+            NodeKind::Trace(_) | NodeKind::ForAll(_, Sort::Loc, _) => {
                 children_to_fixpoint(cx, &self.children)?
             }
 
@@ -478,7 +489,7 @@ impl Node {
                     }
                     constr = fixpoint::Constraint::ForAll(
                         fixpoint::Bind {
-                            name: cx.var_to_fixpoint(var),
+                            name: cx.root_var_to_fixpoint(var),
                             sort: cx.sort_to_fixpoint(sort),
                             pred: fixpoint::Pred::TRUE,
                         },
@@ -487,8 +498,8 @@ impl Node {
                 }
                 Some(constr)
             }
-            NodeKind::ForAll(name, sort) => {
-                cx.with_name_map(*name, |cx, fresh| -> QueryResult<_> {
+            NodeKind::ForAll(name, sort, kind) => {
+                cx.with_name_map(*name, *kind, |cx, fresh| -> QueryResult<_> {
                     let Some(children) = children_to_fixpoint(cx, &self.children)? else {
                         return Ok(None);
                     };
@@ -589,7 +600,7 @@ mod pretty {
     fn bindings_chain(ptr: &NodePtr) -> (Vec<(Name, Sort)>, Vec<NodePtr>) {
         fn go(ptr: &NodePtr, mut bindings: Vec<(Name, Sort)>) -> (Vec<(Name, Sort)>, Vec<NodePtr>) {
             let node = ptr.borrow();
-            if let NodeKind::ForAll(name, sort) = &node.kind {
+            if let NodeKind::ForAll(name, sort, _) = &node.kind {
                 bindings.push((*name, sort.clone()));
                 if let [child] = &node.children[..] {
                     go(child, bindings)
@@ -645,7 +656,7 @@ mod pretty {
                     )?;
                     fmt_children(&node.children, cx, f)
                 }
-                NodeKind::ForAll(name, sort) => {
+                NodeKind::ForAll(name, sort, _) => {
                     let (bindings, children) = if cx.bindings_chain {
                         bindings_chain(self)
                     } else {
@@ -717,7 +728,7 @@ mod pretty {
                             elements.push(format_cx!(cx, "{:?} {:?}", ^name, sort));
                         }
                     }
-                    NodeKind::ForAll(name, sort) => {
+                    NodeKind::ForAll(name, sort, _) => {
                         elements.push(format_cx!(cx, "{:?}: {:?}", ^name, sort));
                     }
                     NodeKind::Assumption(pred) => {
@@ -783,7 +794,7 @@ impl RefineCtxtTrace {
         parents.into_iter().rev().for_each(|ptr| {
             let node = ptr.borrow();
             match &node.kind {
-                NodeKind::ForAll(name, sort) => {
+                NodeKind::ForAll(name, sort, _) => {
                     let bind = RcxBind {
                         name: format_cx!(cx, "{:?}", ^name),
                         sort: format_cx!(cx, "{:?}", sort),
@@ -21,7 +21,7 @@ use std::{borrow::Cow, fmt};
 use flux_common::{bug, span_bug};
 use flux_rustc_bridge::def_id_to_string;
 use flux_syntax::surface::ParamMode;
-pub use flux_syntax::surface::{BinOp, UnOp};
+pub use flux_syntax::surface::{BinOp, QuantKind, UnOp};
 use itertools::Itertools;
 use rustc_ast::TraitObjectSyntax;
 use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
@@ -40,7 +40,7 @@ use rustc_span::{symbol::Ident, Span, Symbol};
 pub use rustc_target::abi::VariantIdx;
 use rustc_target::spec::abi;
 
-use crate::MaybeExternId;
+use crate::{rty::Real, MaybeExternId};
 
 /// A boolean-like enum used to mark whether a piece of code is ignored.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -278,6 +278,7 @@ pub enum ImplItemKind<'fhir> {
 pub enum FluxItem<'fhir> {
     Qualifier(Qualifier<'fhir>),
     Func(SpecFunc<'fhir>),
+    DataSort(DataSortDecl<'fhir>),
 }
 
 impl FluxItem<'_> {
@@ -285,14 +286,32 @@ impl FluxItem<'_> {
         match self {
             FluxItem::Qualifier(qual) => qual.name,
             FluxItem::Func(func) => func.name,
+            FluxItem::DataSort(data_sort) => data_sort.name,
         }
     }
 }
 
+/// A user declared data sort, e.g., `sort Status { Idle, Running(int) }`
+#[derive(Debug)]
+pub struct DataSortDecl<'fhir> {
+    pub name: Symbol,
+    pub variants: &'fhir [DataSortVariant<'fhir>],
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub struct DataSortVariant<'fhir> {
+    pub name: Ident,
+    pub fields: &'fhir [Sort<'fhir>],
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SortDecl {
     pub name: Symbol,
     pub span: Span,
+    /// Whether this is a data sort (as opposed to an opaque sort)
+    pub is_data: bool,
 }
 
 pub type SortDecls = FxHashMap<Symbol, SortDecl>;
@@ -524,7 +543,6 @@ pub enum TyKind<'fhir> {
     BareFn(&'fhir BareFnTy<'fhir>),
     Tuple(&'fhir [Ty<'fhir>]),
     Array(&'fhir Ty<'fhir>, ConstArg),
-    RawPtr(&'fhir Ty<'fhir>, Mutability),
     OpaqueDef(&'fhir OpaqueTy<'fhir>),
     TraitObject(&'fhir [PolyTraitRef<'fhir>], Lifetime, TraitObjectSyntax),
     Never,
@@ -535,6 +553,12 @@ pub struct BareFnTy<'fhir> {
     pub safety: Safety,
     pub abi: abi::Abi,
     pub generic_params: &'fhir [GenericParam<'fhir>],
+    /// Refinement parameters bound by the function pointer, i.e., the explicit parameters in
+    /// `fn[n: int](i32[n])` and the implicit ones declared with `@n` in the inputs. These are
+    /// always empty if the type was [lifted].
+    ///
+    /// [lifted]: lift::LiftCtxt::lift_bare_fn
+    pub refinement_params: &'fhir [RefineParam<'fhir>],
     pub decl: &'fhir FnDecl<'fhir>,
     pub param_names: &'fhir [Ident],
 }
@@ -624,6 +648,7 @@ impl<'fhir> BaseTy<'fhir> {
 pub enum BaseTyKind<'fhir> {
     Path(QPath<'fhir>),
     Slice(&'fhir Ty<'fhir>),
+    RawPtr(&'fhir Ty<'fhir>, Mutability),
 }
 
 #[derive(Clone, Copy)]
@@ -824,6 +849,7 @@ pub enum PrimSort {
     Real,
     Set,
     Map,
+    Seq,
 }
 
 impl PrimSort {
@@ -834,6 +860,7 @@ impl PrimSort {
             PrimSort::Real => "real",
             PrimSort::Set => "Set",
             PrimSort::Map => "Map",
+            PrimSort::Seq => "Seq",
         }
     }
 
@@ -845,6 +872,7 @@ impl PrimSort {
             PrimSort::Real => 0,
             PrimSort::Set => 1,
             PrimSort::Map => 2,
+            PrimSort::Seq => 1,
         }
     }
 }
@@ -853,8 +881,10 @@ impl PrimSort {
 pub enum SortRes {
     /// A primitive sort.
     PrimSort(PrimSort),
-    /// A user declared sort.
+    /// A user declared opaque sort.
     User { name: Symbol },
+    /// A user declared data sort.
+    Data { name: Symbol },
     /// A sort parameter inside a polymorphic function or data sort.
     SortParam(usize),
     /// The sort associated to a (generic) type parameter
@@ -963,10 +993,28 @@ pub enum ExprKind<'fhir> {
     Alias(AliasReft<'fhir>, &'fhir [Expr<'fhir>]),
     IfThenElse(&'fhir Expr<'fhir>, &'fhir Expr<'fhir>, &'fhir Expr<'fhir>),
     Abs(&'fhir [RefineParam<'fhir>], &'fhir Expr<'fhir>),
+    Quant(QuantKind, &'fhir [RefineParam<'fhir>], &'fhir Expr<'fhir>),
+    /// `let x = e1; e2`
+    Let(&'fhir RefineParam<'fhir>, &'fhir Expr<'fhir>, &'fhir Expr<'fhir>),
+    Match(&'fhir Expr<'fhir>, &'fhir [MatchArm<'fhir>]),
     Record(&'fhir [Expr<'fhir>]),
     Constructor(Option<PathExpr<'fhir>>, &'fhir [FieldExpr<'fhir>], Option<&'fhir Spread<'fhir>>),
 }
 
+#[derive(Clone, Copy)]
+pub struct MatchArm<'fhir> {
+    pub pat: MatchPat<'fhir>,
+    pub body: Expr<'fhir>,
+    pub span: Span,
+}
+
+#[derive(Clone, Copy)]
+pub enum MatchPat<'fhir> {
+    /// A variant of a data sort. The path always resolves to a [`ExprRes::DataCtor`].
+    Ctor(PathExpr<'fhir>, &'fhir [RefineParam<'fhir>]),
+    Wildcard(Span),
+}
+
 impl Expr<'_> {
     pub fn is_colon_param(&self) -> Option<ParamId> {
         if let ExprKind::Var(path, Some(ParamKind::Colon)) = &self.kind
@@ -983,7 +1031,7 @@ impl Expr<'_> {
 #[derive(Clone, Copy)]
 pub enum Lit {
     Int(i128),
-    Real(i128),
+    Real(Real),
     Bool(bool),
     Str(Symbol),
     Char(char),
@@ -1000,6 +1048,13 @@ pub enum ExprRes<Id = ParamId> {
     ConstGeneric(DefId),
     NumConst(i128),
     GlobalFunc(SpecFuncKind, Symbol),
+    /// A variant of a [data sort]
+    ///
+    /// [data sort]: SortRes::Data
+    DataCtor {
+        sort: Symbol,
+        ctor: Symbol,
+    },
 }
 
 impl<Id> ExprRes<Id> {
@@ -1011,6 +1066,7 @@ impl<Id> ExprRes<Id> {
             ExprRes::GlobalFunc(kind, name) => ExprRes::GlobalFunc(kind, name),
             ExprRes::ConstGeneric(def_id) => ExprRes::ConstGeneric(def_id),
             ExprRes::Ctor(def_id) => ExprRes::Ctor(def_id),
+            ExprRes::DataCtor { sort, ctor } => ExprRes::DataCtor { sort, ctor },
         }
     }
 
@@ -1314,8 +1370,6 @@ impl fmt::Debug for Ty<'_> {
             TyKind::Array(ty, len) => write!(f, "[{ty:?}; {len:?}]"),
             TyKind::Never => write!(f, "!"),
             TyKind::Constr(pred, ty) => write!(f, "{{{ty:?} | {pred:?}}}"),
-            TyKind::RawPtr(ty, Mutability::Not) => write!(f, "*const {ty:?}"),
-            TyKind::RawPtr(ty, Mutability::Mut) => write!(f, "*mut {ty:?}"),
             TyKind::Infer => write!(f, "_"),
             TyKind::OpaqueDef(opaque_ty) => {
                 write!(f, "impl trait <def_id = {:?}>", opaque_ty.def_id.resolved_id(),)
@@ -1339,6 +1393,15 @@ impl fmt::Debug for BareFnTy<'_> {
                     .format(",")
             )?;
         }
+        if !self.refinement_params.is_empty() {
+            write!(
+                f,
+                "forall<{}> ",
+                self.refinement_params.iter().format_with(", ", |param, f| {
+                    f(&format_args!("{}: {:?}", param.name, param.sort))
+                })
+            )?;
+        }
         write!(f, "{:?}", self.decl)
     }
 }
@@ -1373,6 +1436,8 @@ impl fmt::Debug for BaseTy<'_> {
         match &self.kind {
             BaseTyKind::Path(qpath) => write!(f, "{qpath:?}"),
             BaseTyKind::Slice(ty) => write!(f, "[{ty:?}]"),
+            BaseTyKind::RawPtr(ty, Mutability::Not) => write!(f, "*const {ty:?}"),
+            BaseTyKind::RawPtr(ty, Mutability::Mut) => write!(f, "*mut {ty:?}"),
         }
     }
 }
@@ -1462,6 +1527,21 @@ impl fmt::Debug for Expr<'_> {
                     })
                 )
             }
+            ExprKind::Quant(kind, params, body) => {
+                write!(
+                    f,
+                    "{kind:?}{}. {body:?}",
+                    params.iter().format_with(", ", |param, f| {
+                        f(&format_args!("{}: {:?}", param.name, param.sort))
+                    })
+                )
+            }
+            ExprKind::Let(param, init, body) => {
+                write!(f, "let {}: {:?} = {init:?}; {body:?}", param.name, param.sort)
+            }
+            ExprKind::Match(scrutinee, arms) => {
+                write!(f, "match {scrutinee:?} {{ {:?} }}", arms.iter().format(", "))
+            }
             ExprKind::Record(flds) => {
                 write!(f, "{{ {:?} }}", flds.iter().format(", "))
             }
@@ -1482,6 +1562,19 @@ impl fmt::Debug for Expr<'_> {
     }
 }
 
+impl fmt::Debug for MatchArm<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.pat {
+            MatchPat::Ctor(path, []) => write!(f, "{path:?}")?,
+            MatchPat::Ctor(path, params) => {
+                write!(f, "{path:?}({})", params.iter().map(|param| param.name).format(", "))?;
+            }
+            MatchPat::Wildcard(_) => write!(f, "_")?,
+        }
+        write!(f, " => {:?}", self.body)
+    }
+}
+
 impl fmt::Debug for PathExpr<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.segments.iter().format("::"))
@@ -1530,6 +1623,7 @@ impl fmt::Debug for SortRes {
             SortRes::PrimSort(PrimSort::Real) => write!(f, "real"),
             SortRes::PrimSort(PrimSort::Set) => write!(f, "Set"),
             SortRes::PrimSort(PrimSort::Map) => write!(f, "Map"),
+            SortRes::PrimSort(PrimSort::Seq) => write!(f, "Seq"),
             SortRes::SortParam(n) => write!(f, "@{}", n),
             SortRes::TyParam(def_id) => write!(f, "{}::sort", def_id_to_string(*def_id)),
             SortRes::SelfParam { trait_id } => {
@@ -1541,7 +1635,7 @@ impl fmt::Debug for SortRes {
             SortRes::SelfParamAssoc { ident: assoc, .. } => {
                 write!(f, "Self::{assoc}")
             }
-            SortRes::User { name } => write!(f, "{name}"),
+            SortRes::User { name } | SortRes::Data { name } => write!(f, "{name}"),
             SortRes::Adt(def_id) => write!(f, "{}::sort", def_id_to_string(*def_id)),
         }
     }
@@ -299,7 +299,9 @@ impl<'a, 'genv, 'tcx> LiftCtxt<'a, 'genv, 'tcx> {
             }
             hir::TyKind::Ptr(mut_ty) => {
                 let ty = self.lift_ty(mut_ty.ty)?;
-                fhir::TyKind::RawPtr(self.genv.alloc(ty), mut_ty.mutbl)
+                let kind = fhir::BaseTyKind::RawPtr(self.genv.alloc(ty), mut_ty.mutbl);
+                let bty = fhir::BaseTy { kind, fhir_id: self.next_fhir_id(), span: ty.span };
+                return Ok(fhir::Ty { kind: fhir::TyKind::BaseTy(bty), span: ty.span });
             }
             hir::TyKind::OpaqueDef(opaque_ty) => {
                 let opaque_ty = self.lift_opaque_ty(opaque_ty)?;
@@ -343,6 +345,7 @@ impl<'a, 'genv, 'tcx> LiftCtxt<'a, 'genv, 'tcx> {
             safety: bare_fn.safety,
             abi: bare_fn.abi,
             generic_params,
+            refinement_params: &[],
             decl: self.genv.alloc(decl),
             param_names: self.genv.alloc_slice(bare_fn.param_names),
         })
@@ -2,9 +2,9 @@ use super::{
     AliasReft, AssocItemConstraint, AssocItemConstraintKind, BaseTy, BaseTyKind, Ensures, EnumDef,
     Expr, ExprKind, FieldDef, FieldExpr, FnDecl, FnOutput, FnSig, FuncSort, GenericArg,
     GenericBound, Generics, Impl, ImplAssocReft, ImplItem, ImplItemKind, Item, ItemKind, Lifetime,
-    Lit, OpaqueTy, OwnerNode, Path, PathExpr, PathSegment, PolyFuncSort, PolyTraitRef, QPath,
-    RefineParam, Requires, Sort, SortPath, StructDef, TraitAssocReft, TraitItem, TraitItemKind, Ty,
-    TyAlias, TyKind, VariantDef, VariantRet, WhereBoundPredicate,
+    Lit, MatchArm, MatchPat, OpaqueTy, OwnerNode, Path, PathExpr, PathSegment, PolyFuncSort,
+    PolyTraitRef, QPath, RefineParam, Requires, Sort, SortPath, StructDef, TraitAssocReft,
+    TraitItem, TraitItemKind, Ty, TyAlias, TyKind, VariantDef, VariantRet, WhereBoundPredicate,
 };
 use crate::fhir::StructKind;
 
@@ -167,6 +167,10 @@ pub trait Visitor<'v>: Sized {
         walk_expr(self, expr);
     }
 
+    fn visit_match_arm(&mut self, arm: &MatchArm<'v>) {
+        walk_match_arm(self, arm);
+    }
+
     fn visit_field_expr(&mut self, expr: &FieldExpr<'v>) {
         walk_field_expr(self, expr);
     }
@@ -374,16 +378,16 @@ pub fn walk_ty<'v, V: Visitor<'v>>(vis: &mut V, ty: &Ty<'v>) {
             vis.visit_lifetime(&lft);
             vis.visit_ty(mty.ty);
         }
-        TyKind::BareFn(bare_fn) => vis.visit_fn_decl(bare_fn.decl),
+        TyKind::BareFn(bare_fn) => {
+            walk_list!(vis, visit_refine_param, bare_fn.refinement_params);
+            vis.visit_fn_decl(bare_fn.decl);
+        }
         TyKind::Tuple(tys) => {
             walk_list!(vis, visit_ty, tys);
         }
         TyKind::Array(ty, _len) => {
             vis.visit_ty(ty);
         }
-        TyKind::RawPtr(ty, _mtblt) => {
-            vis.visit_ty(ty);
-        }
         TyKind::OpaqueDef(opaque_ty) => {
             vis.visit_opaque_ty(opaque_ty);
         }
@@ -398,7 +402,7 @@ pub fn walk_ty<'v, V: Visitor<'v>>(vis: &mut V, ty: &Ty<'v>) {
 pub fn walk_bty<'v, V: Visitor<'v>>(vis: &mut V, bty: &BaseTy<'v>) {
     match &bty.kind {
         BaseTyKind::Path(path) => vis.visit_qpath(path),
-        BaseTyKind::Slice(ty) => vis.visit_ty(ty),
+        BaseTyKind::Slice(ty) | BaseTyKind::RawPtr(ty, _) => vis.visit_ty(ty),
     }
 }
 
@@ -468,6 +472,14 @@ pub fn walk_field_expr<'v, V: Visitor<'v>>(vis: &mut V, expr: &FieldExpr<'v>) {
     vis.visit_expr(&expr.expr);
 }
 
+pub fn walk_match_arm<'v, V: Visitor<'v>>(vis: &mut V, arm: &MatchArm<'v>) {
+    if let MatchPat::Ctor(path, params) = arm.pat {
+        vis.visit_path_expr(&path);
+        walk_list!(vis, visit_refine_param, params);
+    }
+    vis.visit_expr(&arm.body);
+}
+
 pub fn walk_expr<'v, V: Visitor<'v>>(vis: &mut V, expr: &Expr<'v>) {
     match expr.kind {
         ExprKind::Var(path, _) => vis.visit_path_expr(&path),
@@ -492,10 +504,19 @@ pub fn walk_expr<'v, V: Visitor<'v>>(vis: &mut V, expr: &Expr<'v>) {
             vis.visit_expr(e2);
             vis.visit_expr(e3);
         }
-        ExprKind::Abs(refine_params, body) => {
+        ExprKind::Abs(refine_params, body) | ExprKind::Quant(_, refine_params, body) => {
             walk_list!(vis, visit_refine_param, refine_params);
             vis.visit_expr(body);
         }
+        ExprKind::Let(param, init, body) => {
+            vis.visit_expr(init);
+            vis.visit_refine_param(param);
+            vis.visit_expr(body);
+        }
+        ExprKind::Match(scrutinee, arms) => {
+            vis.visit_expr(scrutinee);
+            walk_list!(vis, visit_match_arm, arms);
+        }
         ExprKind::Record(fields) => {
             walk_list!(vis, visit_expr, fields);
         }
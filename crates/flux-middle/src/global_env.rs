@@ -163,6 +163,10 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         self.inner.queries.func_decl(self, name)
     }
 
+    pub fn data_sort_def(self, name: Symbol) -> QueryResult<rty::DataSortDef> {
+        self.inner.queries.data_sort_def(self, name)
+    }
+
     pub fn variances_of(self, did: DefId) -> &'tcx [Variance] {
         self.tcx().variances_of(did)
     }
@@ -470,6 +474,23 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         self.collect_specs().should_fail.contains(&def_id)
     }
 
+    /// Whether the function is marked with `#[flux::reflect]`
+    pub fn is_reflected(self, def_id: LocalDefId) -> bool {
+        self.collect_specs().reflected.contains(&def_id)
+    }
+
+    /// Iterator over all functions marked with `#[flux::reflect]`
+    pub fn reflected_fns(self) -> impl Iterator<Item = LocalDefId> + 'genv {
+        self.collect_specs().reflected.iter().copied()
+    }
+
+    /// The reflected function whose name is `name`, if any. Reflected functions live in the
+    /// same namespace as refinement functions, so they are looked up by their unqualified name.
+    pub fn reflected_fn(self, name: Symbol) -> Option<LocalDefId> {
+        self.reflected_fns()
+            .find(|def_id| self.tcx().item_name(def_id.to_def_id()) == name)
+    }
+
     /// Traverse the parent chain of `def_id` until the first node for which `f` returns [`Some`].
     fn traverse_parents<T>(
         self,
@@ -546,6 +567,26 @@ impl<'genv, 'tcx> Map<'genv, 'tcx> {
         })
     }
 
+    pub fn data_sort(self, name: Symbol) -> Option<&'genv fhir::DataSortDecl<'genv>> {
+        self.fhir.items.get(&name).and_then(|item| {
+            if let fhir::FluxItem::DataSort(data_sort) = item {
+                Some(data_sort)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn data_sorts(self) -> impl Iterator<Item = &'genv fhir::DataSortDecl<'genv>> {
+        self.fhir.items.values().filter_map(|item| {
+            if let fhir::FluxItem::DataSort(data_sort) = item {
+                Some(data_sort)
+            } else {
+                None
+            }
+        })
+    }
+
     pub fn qualifiers(self) -> impl Iterator<Item = &'genv fhir::Qualifier<'genv>> {
         self.fhir.items.values().filter_map(|item| {
             if let fhir::FluxItem::Qualifier(qual) = item {
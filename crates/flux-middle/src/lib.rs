@@ -53,7 +53,7 @@ use global_env::GlobalEnv;
 use queries::QueryResult;
 use rty::VariantIdx;
 use rustc_data_structures::{
-    fx::FxIndexMap,
+    fx::{FxIndexMap, FxIndexSet},
     unord::{UnordMap, UnordSet},
 };
 use rustc_hir::OwnerId;
@@ -83,6 +83,8 @@ pub static THEORY_FUNCS: LazyLock<UnordMap<Symbol, TheoryFunc>> = LazyLock::new(
     let param0 = ParamSort::from_u32(0);
     let param1 = ParamSort::from_u32(1);
     let bv_param0 = BvSize::Param(ParamSort::from_u32(0));
+    let bv_param1 = BvSize::Param(ParamSort::from_u32(1));
+    let bv_param2 = BvSize::Param(ParamSort::from_u32(2));
     [
         // String operations
         TheoryFunc {
@@ -93,6 +95,46 @@ pub static THEORY_FUNCS: LazyLock<UnordMap<Symbol, TheoryFunc>> = LazyLock::new(
                 rty::FuncSort::new(vec![rty::Sort::Str], Int),
             ),
         },
+        TheoryFunc {
+            name: Symbol::intern("str_concat"),
+            fixpoint_name: Symbol::intern("strConcat"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, rty::Sort::Str], rty::Sort::Str),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_prefix_of"),
+            fixpoint_name: Symbol::intern("strPrefixOf"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, rty::Sort::Str], Bool),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_contains"),
+            fixpoint_name: Symbol::intern("strContains"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, rty::Sort::Str], Bool),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_at"),
+            fixpoint_name: Symbol::intern("strAt"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, Int], rty::Sort::Str),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("str_substr"),
+            fixpoint_name: Symbol::intern("subString"),
+            sort: rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Str, Int, Int], rty::Sort::Str),
+            ),
+        },
         // BitVector <-> int
         TheoryFunc {
             name: Symbol::intern("bv_zero_extend_32_to_64"),
@@ -142,6 +184,59 @@ pub static THEORY_FUNCS: LazyLock<UnordMap<Symbol, TheoryFunc>> = LazyLock::new(
                 rty::FuncSort::new(vec![BitVec(BvSize::Fixed(64))], Int),
             ),
         },
+        // BitVector conversions for arbitrary sizes (see [`sized_theory_func_instance`])
+        TheoryFunc {
+            name: Symbol::intern("bv_zero_extend"),
+            fixpoint_name: Symbol::intern("zero_extend"),
+            sort: rty::PolyFuncSort::new(
+                List::from_arr([SortParamKind::BvSize, SortParamKind::BvSize]),
+                rty::FuncSort::new(vec![BitVec(bv_param0)], BitVec(bv_param1)),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("bv_sign_extend"),
+            fixpoint_name: Symbol::intern("sign_extend"),
+            sort: rty::PolyFuncSort::new(
+                List::from_arr([SortParamKind::BvSize, SortParamKind::BvSize]),
+                rty::FuncSort::new(vec![BitVec(bv_param0)], BitVec(bv_param1)),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("bv_extract"),
+            fixpoint_name: Symbol::intern("extract"),
+            sort: rty::PolyFuncSort::new(
+                List::from_arr([SortParamKind::BvSize, SortParamKind::BvSize]),
+                rty::FuncSort::new(vec![BitVec(bv_param0)], BitVec(bv_param1)),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("bv_concat"),
+            fixpoint_name: Symbol::intern("concat"),
+            sort: rty::PolyFuncSort::new(
+                List::from_arr([
+                    SortParamKind::BvSize,
+                    SortParamKind::BvSize,
+                    SortParamKind::BvSize,
+                ]),
+                rty::FuncSort::new(vec![BitVec(bv_param0), BitVec(bv_param1)], BitVec(bv_param2)),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("bv_int_to_bv"),
+            fixpoint_name: Symbol::intern("int_to_bv"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::BvSize),
+                rty::FuncSort::new(vec![Int], BitVec(bv_param0)),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("bv_bv_to_int"),
+            fixpoint_name: Symbol::intern("bv_to_int"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::BvSize),
+                rty::FuncSort::new(vec![BitVec(bv_param0)], Int),
+            ),
+        },
         // BitVector arith
         TheoryFunc {
             name: Symbol::intern("bv_add"),
@@ -384,12 +479,123 @@ pub static THEORY_FUNCS: LazyLock<UnordMap<Symbol, TheoryFunc>> = LazyLock::new(
                 ),
             ),
         },
+        // Sequence operations
+        TheoryFunc {
+            name: Symbol::intern("seq_empty"),
+            fixpoint_name: Symbol::intern("Seq_empty"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(vec![Int], Sort::app(Seq, List::singleton(Var(param0)))),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("seq_unit"),
+            fixpoint_name: Symbol::intern("Seq_unit"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(vec![Var(param0)], Sort::app(Seq, List::singleton(Var(param0)))),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("seq_len"),
+            fixpoint_name: Symbol::intern("Seq_len"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(vec![Sort::app(Seq, List::singleton(Var(param0)))], Int),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("seq_nth"),
+            fixpoint_name: Symbol::intern("Seq_nth"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![Sort::app(Seq, List::singleton(Var(param0))), Int],
+                    Var(param0),
+                ),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("seq_concat"),
+            fixpoint_name: Symbol::intern("Seq_concat"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![
+                        Sort::app(Seq, List::singleton(Var(param0))),
+                        Sort::app(Seq, List::singleton(Var(param0))),
+                    ],
+                    Sort::app(Seq, List::singleton(Var(param0))),
+                ),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("seq_update"),
+            fixpoint_name: Symbol::intern("Seq_update"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![Sort::app(Seq, List::singleton(Var(param0))), Int, Var(param0)],
+                    Sort::app(Seq, List::singleton(Var(param0))),
+                ),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("seq_extract"),
+            fixpoint_name: Symbol::intern("Seq_extract"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![Sort::app(Seq, List::singleton(Var(param0))), Int, Int],
+                    Sort::app(Seq, List::singleton(Var(param0))),
+                ),
+            ),
+        },
+        TheoryFunc {
+            name: Symbol::intern("seq_contains"),
+            fixpoint_name: Symbol::intern("Seq_contains"),
+            sort: rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![Sort::app(Seq, List::singleton(Var(param0))), Var(param0)],
+                    Bool,
+                ),
+            ),
+        },
     ]
     .into_iter()
     .map(|itf| (itf.name, itf))
     .collect()
 });
 
+/// Whether the encoding of a theory function depends on the bit-vector sizes it is instantiated
+/// with. For example, the SMT operation to zero extend a bit-vector is indexed by the number of bits
+/// to add, so `bv_zero_extend` from size `n` to size `m` is encoded as `(_ zero_extend m-n)`.
+pub fn is_sized_theory_func(fixpoint_name: Symbol) -> bool {
+    matches!(
+        fixpoint_name.as_str(),
+        "zero_extend" | "sign_extend" | "extract" | "concat" | "int_to_bv" | "bv_to_int"
+    )
+}
+
+/// Returns the name of the operation implementing a [sized theory function] for the given sizes, or
+/// `None` if the sizes are not valid for the operation, e.g., when extending to a smaller size.
+///
+/// [sized theory function]: is_sized_theory_func
+pub fn sized_theory_func_instance(fixpoint_name: Symbol, sizes: &[usize]) -> Option<Symbol> {
+    let name = match (fixpoint_name.as_str(), sizes) {
+        ("zero_extend" | "sign_extend", &[n, m]) if n <= m => {
+            format!("app (_ {fixpoint_name} {})", m - n)
+        }
+        ("extract", &[n, m]) if 0 < m && m <= n => format!("app (_ extract {} 0)", m - 1),
+        ("concat", &[n, m, k]) if n + m == k => "concat".to_string(),
+        ("int_to_bv", &[n]) => format!("int_to_bv{n}"),
+        ("bv_to_int", &[n]) => format!("bv{n}_to_int"),
+        _ => return None,
+    };
+    Some(Symbol::intern(&name))
+}
+
 #[derive(Default)]
 pub struct Specs {
     pub fn_sigs: UnordMap<OwnerId, surface::FnSpec>,
@@ -405,6 +611,8 @@ pub struct Specs {
     pub trusted_impl: UnordMap<LocalDefId, fhir::Trusted>,
     pub infer_opts: UnordMap<LocalDefId, config::PartialInferOpts>,
     pub should_fail: UnordSet<LocalDefId>,
+    /// Functions marked with `#[flux::reflect]`
+    pub reflected: FxIndexSet<LocalDefId>,
     /// Set of dummy items generated by the extern spec macro we must completely ignore. This is
     /// not the same as [ignored items] because, for ignored items, we still need to return errors
     /// for queries and handle them gracefully in order to report them at the use it.
@@ -131,6 +131,7 @@ pub struct Providers {
     pub qualifiers: fn(GlobalEnv) -> QueryResult<Vec<rty::Qualifier>>,
     pub spec_func_defns: fn(GlobalEnv) -> QueryResult<rty::SpecFuncDefns>,
     pub spec_func_decl: fn(GlobalEnv, Symbol) -> QueryResult<rty::SpecFuncDecl>,
+    pub data_sort_def: fn(GlobalEnv, Symbol) -> QueryResult<rty::DataSortDef>,
     pub adt_sort_def_of: fn(GlobalEnv, LocalDefId) -> QueryResult<rty::AdtSortDef>,
     pub check_wf: for<'genv> fn(GlobalEnv, LocalDefId) -> QueryResult<Rc<rty::WfckResults>>,
     pub adt_def: fn(GlobalEnv, LocalDefId) -> QueryResult<rty::AdtDef>,
@@ -171,6 +172,7 @@ impl Default for Providers {
             fhir_crate: |_| empty_query!(),
             spec_func_defns: |_| empty_query!(),
             spec_func_decl: |_, _| empty_query!(),
+            data_sort_def: |_, _| empty_query!(),
             qualifiers: |_| empty_query!(),
             adt_sort_def_of: |_, _| empty_query!(),
             check_wf: |_, _| empty_query!(),
@@ -204,6 +206,7 @@ pub struct Queries<'genv, 'tcx> {
     lower_fn_sig: Cache<DefId, QueryResult<ty::EarlyBinder<ty::PolyFnSig>>>,
     defns: OnceCell<QueryResult<rty::SpecFuncDefns>>,
     func_decls: Cache<Symbol, QueryResult<rty::SpecFuncDecl>>,
+    data_sort_defs: Cache<Symbol, QueryResult<rty::DataSortDef>>,
     qualifiers: OnceCell<QueryResult<Vec<rty::Qualifier>>>,
     adt_sort_def_of: Cache<DefId, QueryResult<rty::AdtSortDef>>,
     check_wf: Cache<LocalDefId, QueryResult<Rc<rty::WfckResults>>>,
@@ -240,6 +243,7 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
             lower_fn_sig: Default::default(),
             defns: Default::default(),
             func_decls: Default::default(),
+            data_sort_defs: Default::default(),
             qualifiers: Default::default(),
             adt_sort_def_of: Default::default(),
             check_wf: Default::default(),
@@ -396,6 +400,14 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
         run_with_cache(&self.func_decls, name, || (self.providers.spec_func_decl)(genv, name))
     }
 
+    pub(crate) fn data_sort_def(
+        &self,
+        genv: GlobalEnv,
+        name: Symbol,
+    ) -> QueryResult<rty::DataSortDef> {
+        run_with_cache(&self.data_sort_defs, name, || (self.providers.data_sort_def)(genv, name))
+    }
+
     pub(crate) fn qualifiers(&self, genv: GlobalEnv) -> QueryResult<&[rty::Qualifier]> {
         self.qualifiers
             .get_or_init(|| (self.providers.qualifiers)(genv))
@@ -139,7 +139,7 @@ where
     pub fn replace_bound_vars(
         &self,
         mut replace_region: impl FnMut(BoundRegion) -> Region,
-        mut replace_expr: impl FnMut(&Sort, InferMode) -> Expr,
+        mut replace_expr: impl FnMut(&Sort, InferMode, BoundReftKind) -> Expr,
     ) -> T {
         let mut exprs = UnordMap::default();
         let mut regions = UnordMap::default();
@@ -148,8 +148,8 @@ where
                 exprs
                     .entry(breft.var)
                     .or_insert_with(|| {
-                        let (sort, mode, _) = self.vars[breft.var.as_usize()].expect_refine();
-                        replace_expr(sort, mode)
+                        let (sort, mode, kind) = self.vars[breft.var.as_usize()].expect_refine();
+                        replace_expr(sort, mode, kind)
                     })
                     .clone()
             },
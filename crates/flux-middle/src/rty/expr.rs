@@ -21,8 +21,8 @@ use rustc_target::abi::FieldIdx;
 use rustc_type_ir::{BoundVar, DebruijnIndex, INNERMOST};
 
 use super::{
-    BaseTy, Binder, BoundReftKind, BoundVariableKinds, ConstantInfo, FuncSort, GenericArgs,
-    GenericArgsExt as _, IntTy, Sort, UintTy,
+    BaseTy, Binder, BoundReftKind, BoundVariableKinds, ConstantInfo, FloatTy, FuncSort,
+    GenericArgs, GenericArgsExt as _, IntTy, Sort, UintTy,
 };
 use crate::{
     big_int::BigInt,
@@ -258,6 +258,17 @@ impl Expr {
         Expr::aggregate(AggregateKind::Tuple(flds.len()), flds)
     }
 
+    /// The index of a raw pointer with the given `base`, `off` and `len` (see [`Sort::raw_ptr`]).
+    pub fn raw_ptr(base: impl Into<Expr>, off: impl Into<Expr>, len: impl Into<Expr>) -> Expr {
+        Expr::tuple(List::from_arr([base.into(), off.into(), len.into()]))
+    }
+
+    /// Splits the index of a raw pointer into its `base`, `off` and `len`.
+    pub fn raw_ptr_fields(&self) -> (Expr, Expr, Expr) {
+        let proj = |field| self.proj_and_reduce(FieldProj::Tuple { arity: 3, field });
+        (proj(0), proj(1), proj(2))
+    }
+
     pub fn adt(def_id: DefId, flds: List<Expr>) -> Expr {
         ExprKind::Aggregate(AggregateKind::Adt(def_id), flds).intern()
     }
@@ -303,6 +314,22 @@ impl Expr {
         ExprKind::ForAll(expr).intern()
     }
 
+    pub fn exists(expr: Binder<Expr>) -> Expr {
+        ExprKind::Exists(expr).intern()
+    }
+
+    pub fn ctor(ctor: DataCtor, args: List<Expr>) -> Expr {
+        ExprKind::Ctor(ctor, args).intern()
+    }
+
+    pub fn is_ctor(ctor: DataCtor, e: impl Into<Expr>) -> Expr {
+        ExprKind::IsCtor(ctor, e.into()).intern()
+    }
+
+    pub fn ctor_field(ctor: DataCtor, field: u32, e: impl Into<Expr>) -> Expr {
+        ExprKind::CtorField(ctor, field, e.into()).intern()
+    }
+
     pub fn binary_op(op: BinOp, e1: impl Into<Expr>, e2: impl Into<Expr>) -> Expr {
         ExprKind::BinaryOp(op, e1.into(), e2.into()).intern()
     }
@@ -378,7 +405,14 @@ impl Expr {
     /// An expression is an *atom* if it is "self-delimiting", i.e., it has a clear boundary
     /// when printed. This is used to avoid unnecessary parenthesis when pretty printing.
     pub fn is_atom(&self) -> bool {
-        !matches!(self.kind(), ExprKind::Abs(..) | ExprKind::BinaryOp(..) | ExprKind::ForAll(..))
+        !matches!(
+            self.kind(),
+            ExprKind::Abs(..)
+                | ExprKind::BinaryOp(..)
+                | ExprKind::ForAll(..)
+                | ExprKind::Exists(..)
+                | ExprKind::IsCtor(..)
+        )
     }
 
     /// Simple syntactic check to see if the expression is a trivially true predicate. This is used
@@ -494,6 +528,24 @@ impl Expr {
                             Expr::ite(p, e1.fold_with(self), e2.fold_with(self)).at_opt(span)
                         }
                     }
+                    ExprKind::IsCtor(ctor, e) => {
+                        let e = e.fold_with(self);
+                        match e.kind() {
+                            ExprKind::Ctor(ctor2, _) => {
+                                Expr::constant(Constant::Bool(ctor == ctor2))
+                            }
+                            _ => Expr::is_ctor(*ctor, e).at_opt(span),
+                        }
+                    }
+                    ExprKind::CtorField(ctor, field, e) => {
+                        let e = e.fold_with(self);
+                        match e.kind() {
+                            ExprKind::Ctor(ctor2, flds) if ctor == ctor2 => {
+                                flds[*field as usize].clone()
+                            }
+                            _ => Expr::ctor_field(*ctor, *field, e).at_opt(span),
+                        }
+                    }
                     _ => expr.super_fold_with(self),
                 }
             }
@@ -680,6 +732,14 @@ pub enum ExprKind {
     /// about the scope).
     Hole(HoleKind),
     ForAll(Binder<Expr>),
+    Exists(Binder<Expr>),
+    /// A variant of a user declared data sort applied to its fields
+    Ctor(DataCtor, List<Expr>),
+    /// Whether an expression of a data sort was built with the given variant
+    IsCtor(DataCtor, Expr),
+    /// The `i`-th field of an expression of a data sort. This is only meaningful if the expression
+    /// was built with the given variant.
+    CtorField(DataCtor, u32, Expr),
 }
 
 impl ExprKind {
@@ -703,6 +763,13 @@ impl AggregateKind {
     }
 }
 
+/// A variant of a user declared data sort
+#[derive(Clone, Copy, PartialEq, Eq, Hash, TyEncodable, TyDecodable, Debug)]
+pub struct DataCtor {
+    pub sort: Symbol,
+    pub name: Symbol,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, TyEncodable, TyDecodable, Debug)]
 pub enum FieldProj {
     Tuple { arity: usize, field: u32 },
@@ -943,16 +1010,152 @@ impl From<Local> for Loc {
     }
 }
 
+/// A rational number used as the value of constants of sort `real`. The fraction is always kept in
+/// its normal form, i.e., the denominator is positive and coprime with the numerator, such that two
+/// reals are equal if and only if their representations are equal.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encodable, Decodable)]
-pub struct Real(pub i128);
+pub struct Real {
+    num: i128,
+    den: i128,
+}
+
+impl Real {
+    pub const ZERO: Real = Real { num: 0, den: 1 };
+    pub const ONE: Real = Real { num: 1, den: 1 };
+
+    /// Creates the real `num/den`. Returns [`None`] if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Option<Real> {
+        if den == 0 {
+            return None;
+        }
+        let g = gcd(num.unsigned_abs(), den.unsigned_abs());
+        let (mut num, mut den) = (num / g as i128, den / g as i128);
+        if den < 0 {
+            num = num.checked_neg()?;
+            den = den.checked_neg()?;
+        }
+        Some(Real { num, den })
+    }
+
+    pub fn from_int(n: i128) -> Real {
+        Real { num: n, den: 1 }
+    }
+
+    /// Parses a literal in decimal notation, e.g., `12.5` or `1_000.0`. Exponents are not supported.
+    /// Returns [`None`] if the string is not a valid literal or the value cannot be represented.
+    pub fn from_decimal_str(s: &str) -> Option<Real> {
+        let (int_part, frac_part) = s.split_once('.').unwrap_or((s, ""));
+        let mut num: i128 = 0;
+        let mut den: i128 = 1;
+        for c in int_part
+            .chars()
+            .chain(frac_part.chars())
+            .filter(|c| *c != '_')
+        {
+            let d = c.to_digit(10)?;
+            num = num.checked_mul(10)?.checked_add(d.into())?;
+        }
+        for _ in frac_part.chars().filter(|c| *c != '_') {
+            den = den.checked_mul(10)?;
+        }
+        Real::new(num, den)
+    }
+
+    /// Returns the exact value of a finite floating-point number given its bit representation, or
+    /// [`None`] if the number is infinite, NaN, or its value cannot be represented.
+    pub fn from_float_bits(bits: u128, float_ty: FloatTy) -> Option<Real> {
+        let (exp_bits, mant_bits): (u32, u32) = match float_ty {
+            FloatTy::F16 => (5, 10),
+            FloatTy::F32 => (8, 23),
+            FloatTy::F64 => (11, 52),
+            FloatTy::F128 => (15, 112),
+        };
+        let bias = (1i32 << (exp_bits - 1)) - 1;
+        let negative = (bits >> (exp_bits + mant_bits)) & 1 == 1;
+        let biased_exp = ((bits >> mant_bits) & ((1 << exp_bits) - 1)) as i32;
+        let frac = bits & ((1 << mant_bits) - 1);
+        if biased_exp == (1 << exp_bits) - 1 {
+            return None;
+        }
+        // The value is `mant * 2^exp`
+        let (mut mant, mut exp) = if biased_exp == 0 {
+            (frac, 1 - bias - mant_bits as i32)
+        } else {
+            (frac | (1 << mant_bits), biased_exp - bias - mant_bits as i32)
+        };
+        if mant == 0 {
+            return Some(Real::ZERO);
+        }
+        while exp < 0 && mant % 2 == 0 {
+            mant /= 2;
+            exp += 1;
+        }
+        let mut num = i128::try_from(mant).ok()?;
+        if negative {
+            num = -num;
+        }
+        if exp >= 0 {
+            Real::new(num.checked_mul(2i128.checked_pow(exp as u32)?)?, 1)
+        } else {
+            Real::new(num, 2i128.checked_pow(exp.unsigned_abs())?)
+        }
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl From<Real> for Constant {
+    fn from(r: Real) -> Self {
+        Constant::Real(r)
+    }
+}
+
+impl From<Real> for Expr {
+    fn from(r: Real) -> Self {
+        Expr::constant(Constant::Real(r))
+    }
+}
 
 impl liquid_fixpoint::FixpointFmt for Real {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0 < 0 {
-            write!(f, "(- {}.0)", self.0.unsigned_abs())
+        let num = if self.num < 0 {
+            format!("(- {}.0)", self.num.unsigned_abs())
         } else {
-            write!(f, "{}.0", self.0)
+            format!("{}.0", self.num)
+        };
+        if self.den == 1 {
+            write!(f, "{num}")
+        } else {
+            write!(f, "(/ {num} {}.0)", self.den)
+        }
+    }
+}
+
+/// Prints the real in decimal notation if it has a finite decimal expansion (which is always the case
+/// for floating-point values and literals) or as a fraction otherwise.
+impl fmt::Display for Real {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            return write!(f, "{}.0", self.num);
         }
+        let mut scale: i128 = 1;
+        for digits in 1..=38 {
+            scale *= 10;
+            if scale % self.den == 0
+                && let Some(scaled) = self.num.checked_mul(scale / self.den)
+            {
+                let sign = if scaled < 0 { "-" } else { "" };
+                let scaled = scaled.unsigned_abs();
+                let scale = scale.unsigned_abs();
+                return write!(f, "{sign}{}.{:0digits$}", scaled / scale, scaled % scale);
+            }
+        }
+        write!(f, "{}.0/{}.0", self.num, self.den)
     }
 }
 
@@ -1285,6 +1488,28 @@ pub(crate) mod pretty {
                         w!(cx, f, "{:?}", expr.as_ref().skip_binder())
                     })
                 }
+                ExprKind::Exists(expr) => {
+                    let vars = expr.vars();
+                    cx.with_bound_vars(vars, || {
+                        if !vars.is_empty() {
+                            cx.fmt_bound_vars(false, "∃", vars, ". ", f)?;
+                        }
+                        w!(cx, f, "{:?}", expr.as_ref().skip_binder())
+                    })
+                }
+                ExprKind::Ctor(ctor, flds) => {
+                    if flds.is_empty() {
+                        w!(cx, f, "{}", ^ctor.name)
+                    } else {
+                        w!(cx, f, "{}({:?})", ^ctor.name, join!(", ", flds))
+                    }
+                }
+                ExprKind::IsCtor(ctor, e) => {
+                    w!(cx, f, "{:?} is {}", parens!(e, !e.is_atom()), ^ctor.name)
+                }
+                ExprKind::CtorField(ctor, field, e) => {
+                    w!(cx, f, "{:?}.{}.{}", parens!(e, !e.is_atom()), ^ctor.name, ^field)
+                }
             }
         }
     }
@@ -1293,7 +1518,7 @@ pub(crate) mod pretty {
         fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
                 Constant::Int(i) => w!(cx, f, "{i}"),
-                Constant::Real(r) => w!(cx, f, "{}.0", ^r.0),
+                Constant::Real(r) => w!(cx, f, "{}", ^r),
                 Constant::Bool(b) => w!(cx, f, "{b}"),
                 Constant::Str(sym) => w!(cx, f, "\"{sym}\""),
                 Constant::Char(c) => w!(cx, f, "\'{c}\'"),
@@ -1463,7 +1688,10 @@ pub(crate) mod pretty {
                 | ExprKind::ConstDefId(..)
                 | ExprKind::Hole(..)
                 | ExprKind::GlobalFunc(..)
-                | ExprKind::KVar(..) => debug_nested(cx, &e),
+                | ExprKind::KVar(..)
+                | ExprKind::Ctor(..)
+                | ExprKind::IsCtor(..)
+                | ExprKind::CtorField(..) => debug_nested(cx, &e),
 
                 ExprKind::IfThenElse(p, e1, e2) => {
                     let p_d = p.fmt_nested(cx)?;
@@ -1591,6 +1819,13 @@ pub(crate) mod pretty {
                         Ok(NestedString { text, children: expr_d.children, key: None })
                     })
                 }
+                ExprKind::Exists(expr) => {
+                    nested_with_bound_vars(cx, "∃", expr.vars(), |ex_str| {
+                        let expr_d = expr.as_ref().skip_binder().fmt_nested(cx)?;
+                        let text = format!("{}{}", ex_str, expr_d.text);
+                        Ok(NestedString { text, children: expr_d.children, key: None })
+                    })
+                }
             }
         }
     }
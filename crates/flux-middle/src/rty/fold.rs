@@ -867,9 +867,12 @@ impl TypeSuperVisitable for Expr {
                 e2.visit_with(visitor)
             }
             ExprKind::Aggregate(_, flds) => flds.visit_with(visitor),
-            ExprKind::FieldProj(e, _) | ExprKind::PathProj(e, _) | ExprKind::UnaryOp(_, e) => {
-                e.visit_with(visitor)
-            }
+            ExprKind::FieldProj(e, _)
+            | ExprKind::PathProj(e, _)
+            | ExprKind::UnaryOp(_, e)
+            | ExprKind::IsCtor(_, e)
+            | ExprKind::CtorField(_, _, e) => e.visit_with(visitor),
+            ExprKind::Ctor(_, flds) => flds.visit_with(visitor),
             ExprKind::App(func, arg) => {
                 func.visit_with(visitor)?;
                 arg.visit_with(visitor)
@@ -885,7 +888,7 @@ impl TypeSuperVisitable for Expr {
                 args.visit_with(visitor)
             }
             ExprKind::Abs(body) => body.visit_with(visitor),
-            ExprKind::ForAll(expr) => expr.visit_with(visitor),
+            ExprKind::ForAll(expr) | ExprKind::Exists(expr) => expr.visit_with(visitor),
             ExprKind::Constant(_)
             | ExprKind::Hole(_)
             | ExprKind::Local(_)
@@ -943,6 +946,12 @@ impl TypeSuperFoldable for Expr {
                 Expr::alias(alias, args)
             }
             ExprKind::ForAll(expr) => Expr::forall(expr.try_fold_with(folder)?),
+            ExprKind::Exists(expr) => Expr::exists(expr.try_fold_with(folder)?),
+            ExprKind::Ctor(ctor, flds) => Expr::ctor(*ctor, flds.try_fold_with(folder)?),
+            ExprKind::IsCtor(ctor, e) => Expr::is_ctor(*ctor, e.try_fold_with(folder)?),
+            ExprKind::CtorField(ctor, field, e) => {
+                Expr::ctor_field(*ctor, *field, e.try_fold_with(folder)?)
+            }
         };
         Ok(expr.at_opt(span))
     }
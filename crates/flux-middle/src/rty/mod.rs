@@ -18,8 +18,8 @@ use std::{borrow::Cow, cmp::Ordering, hash::Hash, sync::LazyLock};
 
 pub use binder::{Binder, BoundReftKind, BoundVariableKind, BoundVariableKinds, EarlyBinder};
 pub use expr::{
-    AggregateKind, AliasReft, BinOp, BoundReft, Constant, ESpan, EVid, EarlyReftParam, Expr,
-    ExprKind, FieldProj, HoleKind, KVar, KVid, Lambda, Loc, Name, Path, Real, UnOp, Var,
+    AggregateKind, AliasReft, BinOp, BoundReft, Constant, DataCtor, ESpan, EVid, EarlyReftParam,
+    Expr, ExprKind, FieldProj, HoleKind, KVar, KVid, Lambda, Loc, Name, Path, Real, UnOp, Var,
 };
 pub use flux_arc_interner::List;
 use flux_arc_interner::{impl_internable, impl_slice_internable, Interned};
@@ -560,25 +560,7 @@ impl FnTraitPredicate {
         let mut vars = vec![];
 
         let closure_ty = Ty::closure(closure_id, tys, args);
-        let env_ty = match self.kind {
-            ClosureKind::Fn => {
-                vars.push(BoundVariableKind::Region(BoundRegionKind::BrEnv));
-                let br = BoundRegion {
-                    var: BoundVar::from_usize(vars.len() - 1),
-                    kind: BoundRegionKind::BrEnv,
-                };
-                Ty::mk_ref(ReBound(INNERMOST, br), closure_ty, Mutability::Not)
-            }
-            ClosureKind::FnMut => {
-                vars.push(BoundVariableKind::Region(BoundRegionKind::BrEnv));
-                let br = BoundRegion {
-                    var: BoundVar::from_usize(vars.len() - 1),
-                    kind: BoundRegionKind::BrEnv,
-                };
-                Ty::mk_ref(ReBound(INNERMOST, br), closure_ty, Mutability::Mut)
-            }
-            ClosureKind::FnOnce => closure_ty,
-        };
+        let env_ty = self.env_ty(closure_ty, &mut vars);
         let inputs = std::iter::once(env_ty)
             .chain(self.tupled_args.expect_tuple().iter().cloned())
             .collect();
@@ -593,6 +575,38 @@ impl FnTraitPredicate {
 
         PolyFnSig::bind_with_vars(fn_sig, List::from(vars))
     }
+
+    /// The signature of the method of the trait (i.e., `call`, `call_mut` or `call_once`) when
+    /// called on `self_ty`. Contrary to [`FnTraitPredicate::to_closure_sig`] the arguments are
+    /// passed as a single tuple.
+    pub fn to_call_sig(&self) -> PolyFnSig {
+        let mut vars = vec![];
+
+        let env_ty = self.env_ty(self.self_ty.clone(), &mut vars);
+        let inputs = List::from_arr([env_ty, self.tupled_args.clone()]);
+
+        let fn_sig = FnSig::new(
+            Safety::Safe,
+            abi::Abi::RustCall,
+            List::empty(),
+            inputs,
+            Binder::bind_with_vars(FnOutput::new(self.output.clone(), vec![]), List::empty()),
+        );
+
+        PolyFnSig::bind_with_vars(fn_sig, List::from(vars))
+    }
+
+    fn env_ty(&self, ty: Ty, vars: &mut Vec<BoundVariableKind>) -> Ty {
+        let mutbl = match self.kind {
+            ClosureKind::Fn => Mutability::Not,
+            ClosureKind::FnMut => Mutability::Mut,
+            ClosureKind::FnOnce => return ty,
+        };
+        vars.push(BoundVariableKind::Region(BoundRegionKind::BrEnv));
+        let br =
+            BoundRegion { var: BoundVar::from_usize(vars.len() - 1), kind: BoundRegionKind::BrEnv };
+        Ty::mk_ref(ReBound(INNERMOST, br), ty, mutbl)
+    }
 }
 
 #[derive(
@@ -633,8 +647,13 @@ pub struct AssocRefinement {
 pub enum SortCtor {
     Set,
     Map,
+    Seq,
     Adt(AdtSortDef),
-    User { name: Symbol },
+    User {
+        name: Symbol,
+    },
+    /// A user declared data sort. The definition can be obtained with [`GlobalEnv::data_sort_def`].
+    Data(Symbol),
 }
 
 newtype_index! {
@@ -786,6 +805,13 @@ impl Sort {
         Self::tuple(vec![])
     }
 
+    /// The sort of raw pointers: a triple `(base, off, len)` with the address of the allocation
+    /// the pointer points into, the offset of the pointer from that address, and the length of the
+    /// allocation. Both the offset and the length are measured in elements of the pointee type.
+    pub fn raw_ptr() -> Self {
+        Self::tuple(vec![Sort::Int, Sort::Int, Sort::Int])
+    }
+
     #[track_caller]
     pub fn expect_func(&self) -> &PolyFuncSort {
         if let Sort::Func(sort) = self {
@@ -1071,6 +1097,31 @@ pub struct SpecFunc {
     pub expr: Binder<Expr>,
 }
 
+/// The definition of a user declared data sort: a list of variants, each with a list of fields.
+#[derive(Debug, Clone)]
+pub struct DataSortDef {
+    pub name: Symbol,
+    pub variants: Vec<DataVariant>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DataVariant {
+    pub name: Symbol,
+    pub fields: List<Sort>,
+}
+
+impl DataSortDef {
+    pub fn variant(&self, name: Symbol) -> Option<&DataVariant> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    pub fn ctors(&self) -> impl Iterator<Item = DataCtor> + '_ {
+        self.variants
+            .iter()
+            .map(|variant| DataCtor { sort: self.name, name: variant.name })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SpecFuncDecl {
     pub name: Symbol,
@@ -1547,6 +1598,10 @@ impl BaseTy {
         matches!(self, BaseTy::Float(_))
     }
 
+    pub fn is_raw_ptr(&self) -> bool {
+        matches!(self, BaseTy::RawPtr(..))
+    }
+
     pub fn is_bool(&self) -> bool {
         matches!(self, BaseTy::Bool)
     }
@@ -2582,6 +2637,10 @@ pub struct WfckResults {
     field_projs: ItemLocalMap<FieldProj>,
     node_sorts: ItemLocalMap<Sort>,
     record_ctors: ItemLocalMap<DefId>,
+    /// The instance of each application of a [sized theory function]
+    ///
+    /// [sized theory function]: crate::is_sized_theory_func
+    theory_func_instances: ItemLocalMap<Symbol>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -2612,6 +2671,7 @@ impl WfckResults {
             field_projs: ItemLocalMap::default(),
             node_sorts: ItemLocalMap::default(),
             record_ctors: ItemLocalMap::default(),
+            theory_func_instances: ItemLocalMap::default(),
         }
     }
 
@@ -2654,6 +2714,14 @@ impl WfckResults {
     pub fn record_ctors(&self) -> LocalTableInContext<DefId> {
         LocalTableInContext { owner: self.owner, data: &self.record_ctors }
     }
+
+    pub fn theory_func_instances_mut(&mut self) -> LocalTableInContextMut<Symbol> {
+        LocalTableInContextMut { owner: self.owner, data: &mut self.theory_func_instances }
+    }
+
+    pub fn theory_func_instances(&self) -> LocalTableInContext<Symbol> {
+        LocalTableInContext { owner: self.owner, data: &self.theory_func_instances }
+    }
 }
 
 impl<T> LocalTableInContextMut<'_, T> {
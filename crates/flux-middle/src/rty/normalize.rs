@@ -17,6 +17,9 @@ use crate::{
 #[derive(Default)]
 pub struct SpecFuncDefns {
     defns: FxHashMap<Symbol, SpecFunc>,
+    /// Self-recursive definitions. These are not inlined during normalization and are instead
+    /// encoded as defined functions in fixpoint.
+    recursive: FxHashMap<Symbol, SpecFunc>,
 }
 
 pub(super) struct Normalizer<'a> {
@@ -25,10 +28,16 @@ pub(super) struct Normalizer<'a> {
 
 impl SpecFuncDefns {
     pub fn new(defns: FxHashMap<Symbol, SpecFunc>) -> Result<Self, Vec<Symbol>> {
-        let raw = SpecFuncDefns { defns };
+        let raw = SpecFuncDefns { defns, recursive: FxHashMap::default() };
         raw.normalize()
     }
 
+    /// Returns the definition of `name` if it is recursive. The body is normalized, i.e., all calls
+    /// to non-recursive functions are inlined.
+    pub fn recursive_defn(&self, name: Symbol) -> Option<&SpecFunc> {
+        self.recursive.get(&name)
+    }
+
     fn defn_deps(&self, expr: &Binder<Expr>) -> FxHashSet<Symbol> {
         struct DepsVisitor(FxHashSet<Symbol>);
         impl TypeVisitor for DepsVisitor {
@@ -50,6 +59,8 @@ impl SpecFuncDefns {
     /// * either Ok(d1...dn) which are topologically sorted such that
     ///   forall i < j, di does not depend on i.e. "call" dj
     /// * or Err(d1...dn) where d1 'calls' d2 'calls' ... 'calls' dn 'calls' d1
+    ///
+    /// Self-recursive calls are ignored, i.e., only cycles of length greater than one are reported.
     fn sorted_defns(&self) -> Result<Vec<Symbol>, Vec<Symbol>> {
         // 1. Make the Symbol-Index
         let mut i2s: Vec<Symbol> = Vec::new();
@@ -66,6 +77,7 @@ impl SpecFuncDefns {
             let deps = self.defn_deps(&defn.expr);
             let ddeps = deps
                 .iter()
+                .filter(|s| *s != name)
                 .filter_map(|s| s2i.get(s).copied())
                 .collect_vec();
             adj_list.push(ddeps);
@@ -90,13 +102,19 @@ impl SpecFuncDefns {
         // 1. Topologically sort the Defns
         let ds = self.sorted_defns()?;
 
-        // 2. Expand each defn in the sorted order
-        let mut exp_defns = SpecFuncDefns { defns: FxHashMap::default() };
+        // 2. Expand each defn in the sorted order. Recursive defns are expanded but kept apart so
+        //    they are never inlined.
+        let mut exp_defns = SpecFuncDefns::default();
         for d in ds {
             if let Some(defn) = self.defns.remove(&d) {
+                let is_recursive = self.defn_deps(&defn.expr).contains(&d);
                 let expr = defn.expr.normalize(&exp_defns);
                 let exp_defn = SpecFunc { expr, ..defn };
-                exp_defns.defns.insert(d, exp_defn);
+                if is_recursive {
+                    exp_defns.recursive.insert(d, exp_defn);
+                } else {
+                    exp_defns.defns.insert(d, exp_defn);
+                }
             }
         }
         Ok(exp_defns)
@@ -65,7 +65,8 @@ impl Pretty for SortCtor {
         match self {
             SortCtor::Set => w!(cx, f, "Set"),
             SortCtor::Map => w!(cx, f, "Map"),
-            SortCtor::User { name, .. } => w!(cx, f, "{}", ^name),
+            SortCtor::Seq => w!(cx, f, "Seq"),
+            SortCtor::User { name, .. } | SortCtor::Data(name) => w!(cx, f, "{}", ^name),
             SortCtor::Adt(adt_sort_def) => {
                 w!(cx, f, "{:?}", adt_sort_def.did())
             }
@@ -263,9 +263,7 @@ impl<'genv, 'tcx> Refiner<'genv, 'tcx> {
             ty::TyKind::Str => rty::BaseTy::Str,
             ty::TyKind::Slice(ty) => rty::BaseTy::Slice(ty.refine(self)?),
             ty::TyKind::Char => rty::BaseTy::Char,
-            ty::TyKind::FnPtr(poly_fn_sig) => {
-                rty::BaseTy::FnPtr(poly_fn_sig.refine(&self.as_default())?)
-            }
+            ty::TyKind::FnPtr(poly_fn_sig) => rty::BaseTy::FnPtr(poly_fn_sig.refine(self)?),
             ty::TyKind::RawPtr(ty, mu) => rty::BaseTy::RawPtr(ty.refine(&self.as_default())?, *mu),
             ty::TyKind::Dynamic(exi_preds, r) => {
                 let exi_preds = exi_preds
@@ -67,9 +67,9 @@ impl GlobalEnv<'_, '_> {
                     .param_at(p.index as usize, self.tcx());
                 self.sort_of_generic_param(generic_param_def.def_id)?
             }
-            ty::TyKind::Float(_)
-            | ty::TyKind::RawPtr(..)
-            | ty::TyKind::Ref(..)
+            ty::TyKind::Float(_) => Some(rty::Sort::Real),
+            ty::TyKind::RawPtr(..) => Some(rty::Sort::raw_ptr()),
+            ty::TyKind::Ref(..)
             | ty::TyKind::Tuple(_)
             | ty::TyKind::Array(..)
             | ty::TyKind::Alias(..)
@@ -117,9 +117,9 @@ impl rty::BaseTy {
                     rty::AliasTy::new(alias_ty.def_id, alias_ty.args.clone(), List::empty());
                 rty::Sort::Alias(*kind, alias_ty)
             }
-            rty::BaseTy::Float(_)
-            | rty::BaseTy::RawPtr(..)
-            | rty::BaseTy::Ref(..)
+            rty::BaseTy::Float(_) => rty::Sort::Real,
+            rty::BaseTy::RawPtr(..) => rty::Sort::raw_ptr(),
+            rty::BaseTy::Ref(..)
             | rty::BaseTy::FnPtr(..)
             | rty::BaseTy::FnDef(..)
             | rty::BaseTy::Tuple(_)
@@ -11,6 +11,9 @@ refineck_condition_span_note =
 refineck_call_span_note =
     inside this call
 
+refineck_counterexample_note =
+    the constraint fails when {$counterexample}
+
 refineck_refine_error =
     refinement type error
     .label = a {$cond} cannot be proved
@@ -36,6 +39,12 @@ refineck_unknown_error =
 refineck_overflow_error =
     arithmetic operation may overflow
 
+refineck_raw_ptr_deref_error =
+    raw pointer may be dereferenced out of bounds
+
+refineck_raw_ptr_offset_error =
+    raw pointer may be moved out of bounds
+
 refineck_opaque_struct_error =
     cannot access fields of opaque struct `{$struct}`.
 
@@ -52,6 +61,32 @@ refineck_unsupported_call =
 refineck_expected_neg =
     {$def_descr} marked with `#[should_fail]` didn't produce a refinement type error
 
+refineck_timeout_error =
+    verification timed out
+    .note = the solver didn't finish within {$secs} seconds
+    .help = the limit can be changed with `#[flux::opts(timeout = ...)]`
+
+refineck_out_of_memory_error =
+    verification ran out of memory
+    .note = the solver exceeded the limit of {$megabytes} MB
+    .help = the limit can be changed with `#[flux::opts(memory_limit = ...)]`
+
+refineck_solver_crash =
+    verification failed because the solver crashed
+    .note = {$msg}
+
+refineck_suggested_sig =
+    inferred a signature for `{$name}`: `{$sig}`
+    .suggestion = consider adding the inferred signature
+
+refineck_suggested_sig_no_solution =
+    cannot infer a signature for `{$name}`
+    .note = the solver did not report a solution for the refinement of the output
+
+refineck_panic_abort_assumed =
+    cleanup code in `{$name}` is not checked
+    .note = panics are assumed to abort because `assume_panic_abort` is set
+
 # Invariant checking
 
 refineck_invalid_invariant =
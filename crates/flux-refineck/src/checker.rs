@@ -28,8 +28,8 @@ use flux_rustc_bridge::{
     self,
     mir::{
         self, AggregateKind, AssertKind, BasicBlock, Body, BorrowKind, CastKind, Constant,
-        Location, NonDivergingIntrinsic, Operand, Place, Rvalue, Statement, StatementKind,
-        Terminator, TerminatorKind, START_BLOCK,
+        Location, NonDivergingIntrinsic, Operand, Place, PlaceElem, Rvalue, Statement,
+        StatementKind, Terminator, TerminatorKind, UnwindAction, START_BLOCK,
     },
     ty::{self, GenericArgsExt as _},
 };
@@ -53,6 +53,7 @@ use crate::{
     ghost_statements::{GhostStatement, GhostStatements, Point},
     primops,
     queue::WorkQueue,
+    suggest::{self, SigHoles},
     type_env::{
         BasicBlockEnv, BasicBlockEnvShape, PtrToRefBound, SpanTrace, TypeEnv, TypeEnvTrace,
     },
@@ -72,6 +73,10 @@ pub(crate) struct Checker<'ck, 'genv, 'tcx, M> {
     /// A marker to the node in the refinement tree at the end of the basic block after applying
     /// the effects of the terminator.
     markers: IndexVec<BasicBlock, Option<Marker>>,
+    /// A marker to the node in the refinement tree before the terminator of the basic block, for
+    /// basic blocks that unwind into cleanup code. Cleanup blocks are checked from this node because
+    /// the effects of the terminator may not happen when unwinding.
+    unwind_markers: IndexVec<BasicBlock, Option<Marker>>,
     visited: BitSet<BasicBlock>,
     queue: WorkQueue<'ck>,
     default_refiner: Refiner<'genv, 'tcx>,
@@ -130,14 +135,16 @@ pub(crate) struct RefineMode {
 pub(crate) struct ShapeResult(FxHashMap<LocalDefId, FxHashMap<BasicBlock, BasicBlockEnvShape>>);
 
 /// A `Guard` describes extra "control" information that holds at the start of a successor basic block
-#[derive(Debug)]
-enum Guard {
+enum Guard<'a> {
     /// No extra information holds, e.g., for a plain goto.
     None,
     /// A predicate that can be assumed, e.g., in the branches of an if-then-else.
     Pred(Expr),
     /// The corresponding place was found to be of a particular variant.
     Match(Place, VariantIdx),
+    /// The successor is a cleanup block reached by unwinding. Unwinding starts from the node in
+    /// the refinement tree and the environment before the terminator.
+    Unwind(Marker, TypeEnv<'a>),
 }
 
 impl<'ck, 'genv, 'tcx> Checker<'ck, 'genv, 'tcx, ShapeMode> {
@@ -183,7 +190,7 @@ impl<'ck, 'genv, 'tcx> Checker<'ck, 'genv, 'tcx, RefineMode> {
         ghost_stmts: &'ck UnordMap<LocalDefId, GhostStatements>,
         bb_env_shapes: ShapeResult,
         opts: InferOpts,
-    ) -> Result<InferCtxtRoot<'genv, 'tcx>> {
+    ) -> Result<(InferCtxtRoot<'genv, 'tcx>, Option<SigHoles>)> {
         let def_id = local_id.to_def_id();
         let span = genv.tcx().def_span(def_id);
         let mut root_ctxt = genv.infcx_root(def_id, opts).build().with_span(span)?;
@@ -201,17 +208,23 @@ impl<'ck, 'genv, 'tcx> Checker<'ck, 'genv, 'tcx, RefineMode> {
                 .instantiate_identity()
                 .normalize_projections(&mut infcx)
                 .with_span(span)?;
+            let (poly_sig, holes) = if opts.suggest_sigs {
+                suggest::add_holes(genv, &infcx, local_id, poly_sig).with_span(span)?
+            } else {
+                (poly_sig, None)
+            };
             Checker::run(infcx, local_id, inherited, poly_sig)?;
 
-            Ok(root_ctxt)
+            Ok((root_ctxt, holes))
         })
     }
 }
 
 /// The function `check_fn_subtyping` does a function subtyping check between
 /// the sub-type (T_f) corresponding to the type of `def_id` @ `args` and the
-/// super-type (T_g) corresponding to the `oblig_sig`. This subtyping is handled
-/// as akin to the code
+/// super-type (T_g) corresponding to the `oblig_sig`. If `def_id` is `None`, the
+/// sub-type has no refinement parameters of its own, e.g., it comes from a bound
+/// in scope. This subtyping is handled as akin to the code
 ///
 ///   T_f := (S1,...,Sn) -> S
 ///   T_g := (T1,...,Tn) -> T
@@ -222,7 +235,7 @@ impl<'ck, 'genv, 'tcx> Checker<'ck, 'genv, 'tcx, RefineMode> {
 ///  }
 fn check_fn_subtyping(
     infcx: &mut InferCtxt,
-    def_id: &DefId,
+    def_id: Option<DefId>,
     sub_sig: EarlyBinder<rty::PolyFnSig>,
     sub_args: &[GenericArg],
     super_sig: &rty::PolyFnSig,
@@ -233,7 +246,10 @@ fn check_fn_subtyping(
     let tcx = infcx.genv.tcx();
 
     let super_sig = super_sig
-        .replace_bound_vars(|_| rty::ReErased, |sort, _| Expr::fvar(infcx.define_var(sort)))
+        .replace_bound_vars(
+            |_| rty::ReErased,
+            |sort, _, kind| Expr::fvar(infcx.define_bound_reft_var(sort, kind)),
+        )
         .normalize_projections(&mut infcx)?;
 
     // 1. Unpack `T_g` input types
@@ -249,10 +265,16 @@ fn check_fn_subtyping(
 
     let output = infcx.ensure_resolved_evars(|infcx| {
         // 2. Fresh names for `T_f` refine-params / Instantiate fn_def_sig and normalize it
-        let refine_args = infcx.instantiate_refine_args(*def_id, sub_args)?;
+        let refine_args = match def_id {
+            Some(def_id) => infcx.instantiate_refine_args(def_id, sub_args)?,
+            None => identity_refine_args(infcx.genv, infcx.def_id)?,
+        };
         let sub_sig = sub_sig.instantiate(tcx, sub_args, &refine_args);
         let sub_sig = sub_sig
-            .replace_bound_vars(|_| rty::ReErased, |sort, mode| infcx.fresh_infer_var(sort, mode))
+            .replace_bound_vars(
+                |_| rty::ReErased,
+                |sort, mode, _| infcx.fresh_infer_var(sort, mode),
+            )
             .normalize_projections(infcx)?;
 
         // 3. INPUT subtyping (g-input <: f-input)
@@ -275,7 +297,9 @@ fn check_fn_subtyping(
 
     let output = infcx
         .fully_resolve_evars(&output)
-        .replace_bound_refts_with(|sort, _, _| Expr::fvar(infcx.define_var(sort)));
+        .replace_bound_refts_with(|sort, _, kind| {
+            Expr::fvar(infcx.define_bound_reft_var(sort, kind))
+        });
 
     // 4. OUTPUT subtyping (f_out <: g_out)
     infcx.ensure_resolved_evars(|infcx| {
@@ -296,6 +320,39 @@ fn check_fn_subtyping(
     })
 }
 
+/// Whether any of the inputs of `fn_sig` is a strong reference
+fn has_strg_refs(fn_sig: &EarlyBinder<rty::PolyFnSig>) -> bool {
+    fn_sig
+        .skip_binder_ref()
+        .skip_binder_ref()
+        .inputs()
+        .iter()
+        .any(|ty| matches!(ty.kind(), TyKind::StrgRef(..)))
+}
+
+/// The signature of a function pointer obtained by reifying the function `def_id`. The early
+/// refinement parameters of the function become late-bound, i.e., they are bound by the function
+/// pointer and appended after the late-bound regions.
+fn fn_def_to_fn_ptr_sig(
+    genv: GlobalEnv,
+    def_id: DefId,
+    fn_sig: EarlyBinder<rty::PolyFnSig>,
+    args: &[GenericArg],
+) -> QueryResult<rty::PolyFnSig> {
+    let tcx = genv.tcx();
+    let fn_sig = fn_sig.skip_binder();
+    let mut vars = fn_sig.vars().to_vec();
+    let bound_regions = vars.len();
+    let refine_args = RefineArgs::for_item(genv, def_id, |param, index| {
+        let param = param.instantiate(tcx, args, &[]);
+        let kind = rty::BoundReftKind::Named(param.name);
+        vars.push(rty::BoundVariableKind::Refine(param.sort, param.mode, kind));
+        Expr::bvar(rty::INNERMOST, rty::BoundVar::from_usize(bound_regions + index), kind)
+    })?;
+    let fn_sig = EarlyBinder(fn_sig.skip_binder()).instantiate(tcx, args, &refine_args);
+    Ok(rty::PolyFnSig::bind_with_vars(fn_sig, rty::List::from_vec(vars)))
+}
+
 /// Trait subtyping check, which makes sure that the type for an impl method (def_id)
 /// is a subtype of the corresponding trait method.
 pub(crate) fn trait_impl_subtyping<'genv, 'tcx>(
@@ -335,7 +392,7 @@ pub(crate) fn trait_impl_subtyping<'genv, 'tcx>(
     let impl_sig = genv.fn_sig(impl_method_id)?;
     check_fn_subtyping(
         &mut infcx,
-        &impl_method_id,
+        Some(impl_method_id),
         impl_sig,
         &impl_args,
         &trait_fn_sig.instantiate(tcx, &trait_args, &trait_refine_args),
@@ -422,7 +479,10 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         let body = genv.mir(def_id).with_span(span)?;
 
         let fn_sig = poly_sig
-            .replace_bound_vars(|_| rty::ReErased, |sort, _| Expr::fvar(infcx.define_var(sort)))
+            .replace_bound_vars(
+                |_| rty::ReErased,
+                |sort, _, kind| Expr::fvar(infcx.define_bound_reft_var(sort, kind)),
+            )
             .normalize_projections(&mut infcx)
             .with_span(span)?;
 
@@ -445,6 +505,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             visited: BitSet::new_empty(body.basic_blocks.len()),
             output: fn_sig.output().clone(),
             markers: IndexVec::from_fn_n(|_| None, body.basic_blocks.len()),
+            unwind_markers: IndexVec::from_fn_n(|_| None, body.basic_blocks.len()),
             queue: WorkQueue::empty(body.basic_blocks.len(), &body.dominator_order_rank),
             default_refiner: Refiner::default_for_item(genv, def_id.to_def_id()).with_span(span)?,
         };
@@ -472,7 +533,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     fn check_basic_block(
         &mut self,
         mut infcx: InferCtxt<'_, 'genv, 'tcx>,
-        mut env: TypeEnv,
+        mut env: TypeEnv<'ck>,
         bb: BasicBlock,
     ) -> Result {
         dbg::basic_block_start!(bb, infcx, env);
@@ -533,10 +594,43 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         ty: Ty,
         span: Span,
     ) -> InferResult {
+        self.check_raw_ptr_derefs(infcx, env, span, place)?;
         let ty = infcx.hoister(true).hoist(&ty);
         env.assign(&mut infcx.at(span), place, ty)
     }
 
+    /// Checks that every raw pointer dereferenced in `place` points to an element of its
+    /// allocation. This is only done if `check_raw_pointers` is enabled.
+    fn check_raw_ptr_derefs(
+        &self,
+        infcx: &mut InferCtxt,
+        env: &mut TypeEnv,
+        span: Span,
+        place: &Place,
+    ) -> InferResult {
+        if !infcx.check_raw_pointers {
+            return Ok(());
+        }
+        for (i, elem) in place.projection.iter().enumerate() {
+            if !matches!(elem, PlaceElem::Deref) {
+                continue;
+            }
+            let ptr = Place::new(place.local, place.projection[..i].to_vec());
+            if !matches!(env.lookup_rust_ty(self.genv, &ptr)?.kind(), ty::TyKind::RawPtr(..)) {
+                continue;
+            }
+            let ty = env.lookup_place(&mut infcx.at(span), &ptr)?;
+            if let TyKind::Indexed(BaseTy::RawPtr(..), idx) = infcx.unpack(&ty).kind() {
+                let (_, off, len) = idx.raw_ptr_fields();
+                let in_bounds = Expr::and(Expr::ge(&off, 0), Expr::lt(off, len));
+                infcx
+                    .at(span)
+                    .check_pred(in_bounds, ConstrReason::RawPtrDeref);
+            }
+        }
+        Ok(())
+    }
+
     fn check_statement(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
@@ -595,10 +689,10 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     fn check_terminator(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
-        env: &mut TypeEnv,
+        env: &mut TypeEnv<'ck>,
         terminator: &Terminator<'tcx>,
         last_stmt_span: Option<Span>,
-    ) -> Result<Vec<(BasicBlock, Guard)>> {
+    ) -> Result<Vec<(BasicBlock, Guard<'ck>)>> {
         let source_info = terminator.source_info;
         let terminator_span = source_info.span;
         match &terminator.kind {
@@ -628,11 +722,29 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     Ok(Self::check_match(&discr_ty, targets))
                 }
             }
-            TerminatorKind::Call { kind, args, destination, target, .. } => {
+            TerminatorKind::Call { kind, args, destination, target, unwind } => {
                 let actuals = self
                     .check_operands(infcx, env, terminator_span, args)
                     .with_span(terminator_span)?;
+                let unwind = self.unwind_successor(infcx, env, unwind, &actuals);
                 let ret = match kind {
+                    mir::CallKind::FnDef { resolved_id, resolved_args, .. }
+                        if let Some(fn_sig) = self
+                            .fn_trait_call_sig(*resolved_id, &resolved_args.lowered)
+                            .with_span(terminator_span)? =>
+                    {
+                        let generic_args =
+                            self.identity_generic_args().with_span(terminator_span)?;
+                        self.check_call(
+                            infcx,
+                            env,
+                            terminator_span,
+                            None,
+                            EarlyBinder(fn_sig),
+                            &generic_args,
+                            &actuals,
+                        )?
+                    }
                     mir::CallKind::FnDef { resolved_id, resolved_args, .. } => {
                         let fn_sig = self.genv.fn_sig(*resolved_id).with_span(terminator_span)?;
 
@@ -653,38 +765,47 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                             &actuals,
                         )?
                     }
-                    mir::CallKind::FnPtr { operand, .. } => {
+                    mir::CallKind::FnPtr { fn_sig, operand } => {
                         let ty = self
                             .check_operand(infcx, env, terminator_span, operand)
                             .with_span(terminator_span)?;
-                        if let TyKind::Indexed(BaseTy::FnPtr(fn_sig), _) = infcx.unpack(&ty).kind()
+                        // If the operand doesn't have a refined function pointer type (e.g., it
+                        // comes from an opaque or generic type) we use the unrefined signature.
+                        let fn_sig = if let Some(BaseTy::FnPtr(fn_sig)) =
+                            infcx.unpack(&ty).as_bty_skipping_existentials()
                         {
-                            self.check_call(
-                                infcx,
-                                env,
-                                terminator_span,
-                                None,
-                                EarlyBinder(fn_sig.clone()),
-                                &[],
-                                &actuals,
-                            )?
+                            fn_sig.clone()
                         } else {
-                            bug!("TODO: fnptr call {ty:?}")
-                        }
+                            fn_sig
+                                .refine(&self.default_refiner)
+                                .with_span(terminator_span)?
+                        };
+                        self.check_call(
+                            infcx,
+                            env,
+                            terminator_span,
+                            None,
+                            EarlyBinder(fn_sig),
+                            &[],
+                            &actuals,
+                        )?
                     }
                 };
 
                 let ret = infcx.unpack(&ret);
                 infcx.assume_invariants(&ret);
 
+                self.check_raw_ptr_derefs(infcx, env, terminator_span, destination)
+                    .with_span(terminator_span)?;
                 env.assign(&mut infcx.at(terminator_span), destination, ret)
                     .with_span(terminator_span)?;
 
+                let mut successors = vec![];
                 if let Some(target) = target {
-                    Ok(vec![(*target, Guard::None)])
-                } else {
-                    Ok(vec![])
+                    successors.push((*target, Guard::None));
                 }
+                successors.extend(unwind);
+                Ok(successors)
             }
             TerminatorKind::Assert { cond, expected, target, msg } => {
                 Ok(vec![(
@@ -693,15 +814,45 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                         .with_span(terminator_span)?,
                 )])
             }
-            TerminatorKind::Drop { place, target, .. } => {
+            TerminatorKind::Drop { place, target, unwind } => {
                 let _ = env.move_place(&mut infcx.at(terminator_span), place);
-                Ok(vec![(*target, Guard::None)])
+                let mut successors = vec![(*target, Guard::None)];
+                successors.extend(self.unwind_successor(infcx, env, unwind, &[]));
+                Ok(successors)
             }
             TerminatorKind::FalseEdge { real_target, .. } => Ok(vec![(*real_target, Guard::None)]),
             TerminatorKind::FalseUnwind { real_target, .. } => {
                 Ok(vec![(*real_target, Guard::None)])
             }
-            TerminatorKind::UnwindResume => bug!("TODO: implement checking of cleanup code"),
+            // Mutable references passed as arguments are folded back before resuming unwinding
+            // (see `collect_folds_at_ret`), so their invariants are checked as in a return.
+            TerminatorKind::UnwindResume => Ok(vec![]),
+        }
+    }
+
+    /// The cleanup block executed when unwinding from a call or drop, unless panics are assumed
+    /// to abort. Unwinding directly into the caller isn't followed because there's no cleanup
+    /// code to check.
+    ///
+    /// This must be called before checking the call: the callee may panic before establishing its
+    /// postcondition, so cleanup code starts from the current state, except that the callee may
+    /// have written anything through the mutable pointers in `actuals`. The destination is not
+    /// assigned when unwinding, but cleanup code cannot read it.
+    fn unwind_successor(
+        &self,
+        infcx: &InferCtxt,
+        env: &TypeEnv<'ck>,
+        unwind: &UnwindAction,
+        actuals: &[Ty],
+    ) -> Option<(BasicBlock, Guard<'ck>)> {
+        if let UnwindAction::Cleanup(cleanup) = unwind
+            && !infcx.assume_panic_abort
+        {
+            let mut env = env.clone();
+            env.havoc_ptrs(actuals);
+            Some((*cleanup, Guard::Unwind(infcx.marker(), env)))
+        } else {
+            None
         }
     }
 
@@ -759,7 +910,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     .instantiate_refine_args(callee_def_id, &generic_args)
                     .with_span(span)?
             }
-            None => rty::List::empty(),
+            None => identity_refine_args(genv, self.def_id.to_def_id()).with_span(span)?,
         };
 
         let clauses = match callee_def_id {
@@ -782,7 +933,10 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         // Instantiate function signature and normalize it
         let fn_sig = fn_sig
             .instantiate(tcx, &generic_args, &refine_args)
-            .replace_bound_vars(|_| rty::ReErased, |sort, mode| infcx.fresh_infer_var(sort, mode))
+            .replace_bound_vars(
+                |_| rty::ReErased,
+                |sort, mode, _| infcx.fresh_infer_var(sort, mode),
+            )
             .normalize_projections(infcx)
             .with_span(span)?;
 
@@ -804,7 +958,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
 
         let output = infcx
             .fully_resolve_evars(&fn_sig.output)
-            .replace_bound_refts_with(|sort, _, _| Expr::fvar(infcx.define_var(sort)));
+            .replace_bound_refts_with(|sort, _, kind| {
+                Expr::fvar(infcx.define_bound_reft_var(sort, kind))
+            });
 
         env.assume_ensures(infcx, &output.ensures);
         fold_local_ptrs(infcx, env, span).with_span(span)?;
@@ -861,18 +1017,82 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 // See `tests/neg/surface/fndef00.rs`
                 let sub_sig = self.genv.fn_sig(def_id).with_span(span)?;
                 let oblig_sig = fn_trait_pred.fndef_poly_sig();
-                check_fn_subtyping(infcx, def_id, sub_sig, args, &oblig_sig, span)
+                check_fn_subtyping(infcx, Some(*def_id), sub_sig, args, &oblig_sig, span)
                     .with_span(span)?;
             }
+            Some(BaseTy::Param(param_ty)) => {
+                // The obligation can only be satisfied by a bound on the parameter in scope, so
+                // we check the signature in that bound is a subtype of the one in `fn_trait_pred`.
+                // See `tests/neg/surface/fn_trait_bound00.rs`
+                if let Some(bound) = self.fn_trait_bound_of(*param_ty).with_span(span)? {
+                    let sub_sig = EarlyBinder(bound.fndef_poly_sig());
+                    let sub_args = self.identity_generic_args().with_span(span)?;
+                    let oblig_sig = fn_trait_pred.fndef_poly_sig();
+                    check_fn_subtyping(infcx, None, sub_sig, &sub_args, &oblig_sig, span)
+                        .with_span(span)?;
+                }
+            }
             _ => {
-                // TODO: When we allow refining closure/fn at the surface level, we would need to do some function subtyping here,
-                // but for now, we can skip as all the relevant types are unrefined.
+                // TODO: When we allow refining fn pointers and trait objects at the surface level,
+                // we would need to do some function subtyping here, but for now, we can skip as
+                // all the relevant types are unrefined.
                 // See issue-767.rs
             }
         }
         Ok(())
     }
 
+    /// Returns the `Fn` bound on the type parameter `param_ty` in the where clauses of the item
+    /// being checked (or of its parents if we are checking a closure), if any.
+    fn fn_trait_bound_of(&self, param_ty: rty::ParamTy) -> QueryResult<Option<FnTraitPredicate>> {
+        let mut def_id = Some(self.def_id.to_def_id());
+        while let Some(id) = def_id {
+            let predicates = self.genv.predicates_of(id)?;
+            let clauses = predicates.predicates().instantiate_identity();
+            let (_, fn_clauses) = Clause::split_off_fn_trait_clauses(self.genv, &clauses);
+            for clause in fn_clauses {
+                // FIXME(nilehmann) we shouldn't be skipping this binder
+                let clause = clause.skip_binder();
+                if let Some(BaseTy::Param(p)) = clause.self_ty.as_bty_skipping_existentials()
+                    && *p == param_ty
+                {
+                    return Ok(Some(clause));
+                }
+            }
+            def_id = predicates.skip_binder().parent;
+        }
+        Ok(None)
+    }
+
+    /// If `callee_id` is the method of one of the `Fn` traits and it is called on a type parameter
+    /// with a `Fn` bound in scope, returns the signature of the call according to that bound.
+    fn fn_trait_call_sig(
+        &self,
+        callee_id: DefId,
+        args: &ty::GenericArgs,
+    ) -> QueryResult<Option<PolyFnSig>> {
+        let tcx = self.genv.tcx();
+        let Some(kind) = tcx
+            .trait_of_item(callee_id)
+            .and_then(|trait_id| tcx.fn_trait_kind_from_def_id(trait_id))
+        else {
+            return Ok(None);
+        };
+        let ty::TyKind::Param(param_ty) = args[0].expect_type().kind() else {
+            return Ok(None);
+        };
+        let Some(bound) = self.fn_trait_bound_of(*param_ty)? else {
+            return Ok(None);
+        };
+        Ok(Some(FnTraitPredicate { kind, ..bound }.to_call_sig()))
+    }
+
+    /// Generic arguments mapping all the generic parameters in scope to themselves.
+    fn identity_generic_args(&self) -> QueryResult<Vec<GenericArg>> {
+        let root_id = self.genv.tcx().typeck_root_def_id(self.def_id.to_def_id());
+        Ok(GenericArg::identity_for_item(self.genv, root_id)?.to_vec())
+    }
+
     fn check_closure_clauses(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
@@ -896,7 +1116,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         cond: &Operand,
         expected: bool,
         msg: &AssertKind,
-    ) -> InferResult<Guard> {
+    ) -> InferResult<Guard<'ck>> {
         let ty = self.check_operand(infcx, env, terminator_span, cond)?;
         let TyKind::Indexed(BaseTy::Bool, idx) = ty.kind() else {
             tracked_span_bug!("unexpected ty `{ty:?}`");
@@ -919,7 +1139,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
 
     /// Checks conditional branching as in a `match` statement. [`SwitchTargets`](https://doc.rust-lang.org/nightly/nightly-rustc/stable_mir/mir/struct.SwitchTargets.html) contains a list of branches - the exact bit value which is being compared and the block to jump to. Using the conditionals, each branch can be checked using the new control flow information.
     /// See <https://github.com/flux-rs/flux/pull/840#discussion_r1786543174>
-    fn check_if(discr_ty: &Ty, targets: &SwitchTargets) -> Vec<(BasicBlock, Guard)> {
+    fn check_if(discr_ty: &Ty, targets: &SwitchTargets) -> Vec<(BasicBlock, Guard<'ck>)> {
         let mk = |bits| {
             match discr_ty.kind() {
                 TyKind::Indexed(BaseTy::Bool, idx) => {
@@ -947,7 +1167,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         successors
     }
 
-    fn check_match(discr_ty: &Ty, targets: &SwitchTargets) -> Vec<(BasicBlock, Guard)> {
+    fn check_match(discr_ty: &Ty, targets: &SwitchTargets) -> Vec<(BasicBlock, Guard<'ck>)> {
         let (adt_def, place) = discr_ty.expect_discr();
 
         let mut successors = vec![];
@@ -978,14 +1198,24 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     fn check_successors(
         &mut self,
         mut infcx: InferCtxt<'_, 'genv, 'tcx>,
-        env: TypeEnv,
+        env: TypeEnv<'ck>,
         from: BasicBlock,
         terminator_span: Span,
-        successors: Vec<(BasicBlock, Guard)>,
+        successors: Vec<(BasicBlock, Guard<'ck>)>,
     ) -> Result {
+        self.unwind_markers[from] = successors.iter().find_map(|(_, guard)| {
+            if let Guard::Unwind(marker, _) = guard {
+                Some(marker.clone())
+            } else {
+                None
+            }
+        });
         for (target, guard) in successors {
-            let mut infcx = infcx.branch();
-            let mut env = env.clone();
+            let (mut infcx, mut env) = if let Guard::Unwind(marker, env) = &guard {
+                (infcx.move_to(marker, false), env.clone())
+            } else {
+                (infcx.branch(), env.clone())
+            };
             match guard {
                 Guard::None => {}
                 Guard::Pred(expr) => {
@@ -995,6 +1225,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     env.downcast(&mut infcx.at(terminator_span), &place, variant_idx)
                         .with_span(terminator_span)?;
                 }
+                Guard::Unwind(..) => {
+                    env.unblock_all(&mut infcx);
+                }
             }
             self.check_ghost_statements_at(
                 &mut infcx,
@@ -1010,7 +1243,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     fn check_goto(
         &mut self,
         mut infcx: InferCtxt<'_, 'genv, 'tcx>,
-        mut env: TypeEnv,
+        mut env: TypeEnv<'ck>,
         span: Span,
         target: BasicBlock,
     ) -> Result {
@@ -1052,12 +1285,14 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     .with_span(stmt_span)?;
                 Ok(Ty::array(ty, c.clone()))
             }
-            Rvalue::Ref(r, BorrowKind::Mut { .. }, place) => {
-                env.borrow(&mut infcx.at(stmt_span), *r, Mutability::Mut, place)
-                    .with_span(stmt_span)
-            }
-            Rvalue::Ref(r, BorrowKind::Shared | BorrowKind::Fake(..), place) => {
-                env.borrow(&mut infcx.at(stmt_span), *r, Mutability::Not, place)
+            Rvalue::Ref(r, kind, place) => {
+                let mutbl = match kind {
+                    BorrowKind::Mut { .. } => Mutability::Mut,
+                    BorrowKind::Shared | BorrowKind::Fake(..) => Mutability::Not,
+                };
+                self.check_raw_ptr_derefs(infcx, env, stmt_span, place)
+                    .with_span(stmt_span)?;
+                env.borrow(&mut infcx.at(stmt_span), *r, mutbl, place)
                     .with_span(stmt_span)
             }
             Rvalue::RawPtr(mutbl, place) => {
@@ -1065,7 +1300,20 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let ty = self
                     .refine_default(&env.lookup_rust_ty(genv, place).with_span(stmt_span)?)
                     .with_span(stmt_span)?;
-                Ok(BaseTy::RawPtr(ty, *mutbl).to_ty())
+                let bty = BaseTy::RawPtr(ty, *mutbl);
+                // `&raw const *p` is the same as `p` when `p` is a raw pointer. Otherwise, the
+                // pointer points to a single object.
+                if let [proj @ .., PlaceElem::Deref] = &place.projection[..] {
+                    let ptr = Place::new(place.local, proj.to_vec());
+                    let ptr_ty = env
+                        .lookup_place(&mut infcx.at(stmt_span), &ptr)
+                        .with_span(stmt_span)?;
+                    if let TyKind::Indexed(BaseTy::RawPtr(..), idx) = infcx.unpack(&ptr_ty).kind() {
+                        return Ok(Ty::indexed(bty, idx.clone()));
+                    }
+                }
+                let (_, off, len) = Expr::nu().raw_ptr_fields();
+                Ok(Ty::exists_with_constr(bty, Expr::and(Expr::eq(off, 0), Expr::eq(len, 1))))
             }
             Rvalue::Len(place) => self.check_len(infcx, env, stmt_span, place),
             Rvalue::Cast(kind, op, to) => {
@@ -1200,8 +1448,16 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
 
         match (ty1.kind(), ty2.kind()) {
             (TyKind::Indexed(bty1, idx1), TyKind::Indexed(bty2, idx2)) => {
-                let rule =
-                    primops::match_bin_op(bin_op, bty1, idx1, bty2, idx2, infcx.check_overflow);
+                let rule = primops::match_bin_op(
+                    bin_op,
+                    bty1,
+                    idx1,
+                    bty2,
+                    idx2,
+                    infcx.check_overflow,
+                    infcx.precise_bit_ops,
+                    infcx.check_raw_pointers,
+                );
                 if let Some(pre) = rule.precondition {
                     infcx.at(stmt_span).check_pred(pre.pred, pre.reason);
                 }
@@ -1233,7 +1489,13 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         let ty = self.check_operand(infcx, env, stmt_span, op)?;
         match ty.kind() {
             TyKind::Indexed(bty, idx) => {
-                let rule = primops::match_un_op(un_op, bty, idx, infcx.check_overflow);
+                let rule = primops::match_un_op(
+                    un_op,
+                    bty,
+                    idx,
+                    infcx.check_overflow,
+                    infcx.precise_bit_ops,
+                );
                 if let Some(pre) = rule.precondition {
                     infcx.at(stmt_span).check_pred(pre.pred, pre.reason);
                 }
@@ -1314,6 +1576,11 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             CastKind::PointerCoercion(mir::PointerCast::Unsize) => {
                 self.check_unsize_cast(infcx, env, stmt_span, from, to)?
             }
+            CastKind::PointerCoercion(mir::PointerCast::MutToConstPointer)
+                if let TyKind::Indexed(BaseTy::RawPtr(deref_ty, _), idx) = from.kind() =>
+            {
+                Ty::indexed(BaseTy::RawPtr(deref_ty.clone(), Mutability::Not), idx.clone())
+            }
             CastKind::FloatToInt
             | CastKind::IntToFloat
             | CastKind::PtrToPtr
@@ -1325,11 +1592,23 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 if let TyKind::Indexed(rty::BaseTy::FnDef(def_id, args), _) = from.kind()
                     && let TyKind::Indexed(BaseTy::FnPtr(super_sig), _) = to.kind()
                 {
-                    let current_did = infcx.def_id;
                     let sub_sig = infcx.genv.fn_sig(*def_id)?;
-                    // // TODO(RJ) dicey maneuver? assumes that sig_b is unrefined?
-                    check_fn_subtyping(infcx, &current_did, sub_sig, args, super_sig, stmt_span)?;
-                    to
+                    if has_strg_refs(&sub_sig) {
+                        // Function pointers cannot take strong references, so we check the
+                        // function against the unrefined signature instead.
+                        check_fn_subtyping(
+                            infcx,
+                            Some(*def_id),
+                            sub_sig,
+                            args,
+                            super_sig,
+                            stmt_span,
+                        )?;
+                        to
+                    } else {
+                        let fn_sig = fn_def_to_fn_ptr_sig(self.genv, *def_id, sub_sig, args)?;
+                        BaseTy::FnPtr(fn_sig).to_ty()
+                    }
                 } else {
                     tracked_span_bug!("invalid cast from `{from:?}` to `{to:?}`")
                 }
@@ -1368,7 +1647,15 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             src.clone()
         };
 
-        if let ty::TyKind::Ref(_, deref_ty, _) = dst.kind()
+        // `&T -> &dyn Trait` or `Box<T> -> Box<dyn Trait>`. Method calls on the trait object use
+        // the refined signature of the trait method. This relies on `trait_impl_subtyping` having
+        // checked the impl, which we trust for ignored impls and impls in other crates.
+        let dst_deref_ty = match dst.kind() {
+            ty::TyKind::Ref(_, deref_ty, _) => Some(deref_ty),
+            ty::TyKind::Adt(adt_def, args) if adt_def.is_box() => Some(args.box_args().0),
+            _ => None,
+        };
+        if let Some(deref_ty) = dst_deref_ty
             && let ty::TyKind::Dynamic(..) = deref_ty.kind()
         {
             return Ok(self.refine_default(dst)?);
@@ -1419,6 +1706,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         span: Span,
         operand: &Operand,
     ) -> InferResult<Ty> {
+        if let Operand::Copy(p) | Operand::Move(p) = operand {
+            self.check_raw_ptr_derefs(infcx, env, span, p)?;
+        }
         let ty = match operand {
             Operand::Copy(p) => env.lookup_place(&mut infcx.at(span), p)?,
             Operand::Move(p) => env.move_place(&mut infcx.at(span), p)?,
@@ -1441,7 +1731,13 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let idx = Expr::constant(rty::Constant::from(*b));
                 Ok(Ty::indexed(BaseTy::Bool, idx))
             }
-            Constant::Float(_, float_ty) => Ok(Ty::float(*float_ty)),
+            Constant::Float(bits, float_ty) => {
+                if let Some(r) = rty::Real::from_float_bits(*bits, *float_ty) {
+                    Ok(Ty::indexed(BaseTy::Float(*float_ty), Expr::from(r)))
+                } else {
+                    Ok(Ty::float(*float_ty))
+                }
+            }
             Constant::Unit => Ok(Ty::unit()),
             Constant::Str(s) => {
                 let idx = Expr::constant(rty::Constant::from(*s));
@@ -1514,7 +1810,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
 
     #[track_caller]
     fn marker_at_dominator(&self, bb: BasicBlock) -> &Marker {
-        marker_at_dominator(self.body, &self.markers, bb)
+        marker_at_dominator(self.body, &self.markers, &self.unwind_markers, bb)
     }
 
     fn dominators(&self) -> &'ck Dominators<BasicBlock> {
@@ -1534,6 +1830,14 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     }
 }
 
+/// Refinement arguments mapping the early bound refinement parameters in scope of `def_id` to
+/// themselves. Closures don't have refinement parameters of their own so these are the ones of the
+/// enclosing function.
+fn identity_refine_args(genv: GlobalEnv, def_id: DefId) -> QueryResult<RefineArgs> {
+    let root_id = genv.tcx().typeck_root_def_id(def_id);
+    RefineArgs::identity_for_item(genv, root_id)
+}
+
 fn instantiate_args_for_fun_call(
     genv: GlobalEnv,
     caller_id: DefId,
@@ -1726,7 +2030,7 @@ impl Mode for ShapeMode {
         let modified = match bb_envs.entry(ck.def_id).or_default().entry(target) {
             Entry::Occupied(mut entry) => entry.get_mut().join(env),
             Entry::Vacant(entry) => {
-                let scope = marker_at_dominator(ck.body, &ck.markers, target)
+                let scope = marker_at_dominator(ck.body, &ck.markers, &ck.unwind_markers, target)
                     .scope()
                     .unwrap_or_else(|| tracked_span_bug!());
                 entry.insert(env.into_infer(scope));
@@ -1858,12 +2162,18 @@ impl ShapeResult {
 fn marker_at_dominator<'a>(
     body: &Body,
     markers: &'a IndexVec<BasicBlock, Option<Marker>>,
+    unwind_markers: &'a IndexVec<BasicBlock, Option<Marker>>,
     bb: BasicBlock,
 ) -> &'a Marker {
     let dominator = body
         .dominators()
         .immediate_dominator(bb)
         .unwrap_or_else(|| tracked_span_bug!());
+    if body.basic_blocks[bb].is_cleanup
+        && let Some(marker) = &unwind_markers[dominator]
+    {
+        return marker;
+    }
     markers[dominator]
         .as_ref()
         .unwrap_or_else(|| tracked_span_bug!())
@@ -89,6 +89,10 @@ impl GhostStatements {
 
     fn add_unblocks<'tcx>(&mut self, tcx: TyCtxt<'tcx>, body: &Body<'tcx>) {
         for (location, borrows) in body.calculate_borrows_out_of_scope_at_location() {
+            // Every place is unblocked when unwinding into a cleanup block
+            if body.basic_blocks[location.block].is_cleanup {
+                continue;
+            }
             let stmts = borrows.into_iter().map(|bidx| {
                 let borrow = body.borrow_data(bidx);
                 let place = lowering::lower_place(tcx, &borrow.borrowed_place).unwrap();
@@ -8,7 +8,7 @@ use flux_rustc_bridge::{
     mir::{
         BasicBlock, Body, BorrowKind, FieldIdx, Local, Location, NonDivergingIntrinsic, Operand,
         Place, PlaceElem, PlaceRef, Rvalue, Statement, StatementKind, Terminator, TerminatorKind,
-        VariantIdx, FIRST_VARIANT,
+        UnwindAction, VariantIdx, FIRST_VARIANT,
     },
     ty::{AdtDef, GenericArgs, GenericArgsExt as _, List, Mutability, Ty, TyKind},
 };
@@ -362,16 +362,19 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
     ) -> QueryResult<Vec<(Env, BasicBlock)>> {
         let mut successors = vec![];
         match &terminator.kind {
-            TerminatorKind::Return => {
+            TerminatorKind::Return | TerminatorKind::UnwindResume => {
                 M::ret(self, &env);
             }
-            TerminatorKind::Call { args, destination, target, .. } => {
+            TerminatorKind::Call { args, destination, target, unwind, .. } => {
                 for arg in args {
                     self.operand(arg, &mut env)?;
                 }
                 M::projection(self, &mut env, destination)?;
                 if let Some(target) = target {
-                    successors.push((env, *target));
+                    successors.push((env.clone(), *target));
+                }
+                if let UnwindAction::Cleanup(cleanup) = unwind {
+                    successors.push((env, *cleanup));
                 }
             }
             TerminatorKind::SwitchInt { discr, targets } => {
@@ -423,9 +426,12 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
                 M::projection(self, &mut env, resume_arg)?;
                 successors.push((env, *resume));
             }
-            TerminatorKind::Drop { place, target, .. } => {
+            TerminatorKind::Drop { place, target, unwind } => {
                 M::projection(self, &mut env, place)?;
-                successors.push((env, *target));
+                successors.push((env.clone(), *target));
+                if let UnwindAction::Cleanup(cleanup) = unwind {
+                    successors.push((env, *cleanup));
+                }
             }
             TerminatorKind::Assert { cond, target, .. } => {
                 self.operand(cond, &mut env)?;
@@ -437,9 +443,7 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
             TerminatorKind::FalseUnwind { real_target, .. } => {
                 successors.push((env, *real_target));
             }
-            TerminatorKind::Unreachable
-            | TerminatorKind::UnwindResume
-            | TerminatorKind::CoroutineDrop => {}
+            TerminatorKind::Unreachable | TerminatorKind::CoroutineDrop => {}
         }
         Ok(successors)
     }
@@ -10,12 +10,15 @@ use rustc_infer::infer::TyCtxtInferExt;
 use rustc_middle::ty::TypingMode;
 use rustc_span::{Span, DUMMY_SP};
 
+use crate::stats::CheckStats;
+
 pub fn check_invariants(
     genv: GlobalEnv,
     cache: &mut FixQueryCache,
     def_id: MaybeExternId,
     invariants: &[fhir::Expr],
     adt_def: &rty::AdtDef,
+    stats: &mut CheckStats,
 ) -> Result<(), ErrorGuaranteed> {
     // FIXME(nilehmann) maybe we should record whether the invariants were generated with overflow
     // checking enabled and only assume them in code that also overflow checking enabled.
@@ -32,10 +35,11 @@ pub fn check_invariants(
         .enumerate()
         .try_for_each_exhaust(|(idx, invariant)| {
             let span = invariants[idx].span;
-            check_invariant(genv, cache, def_id, adt_def, span, invariant, opts)
+            check_invariant(genv, cache, def_id, adt_def, span, invariant, opts, stats)
         })
 }
 
+#[expect(clippy::too_many_arguments)]
 fn check_invariant(
     genv: GlobalEnv,
     cache: &mut FixQueryCache,
@@ -44,6 +48,7 @@ fn check_invariant(
     span: Span,
     invariant: &rty::Invariant,
     opts: InferOpts,
+    stats: &mut CheckStats,
 ) -> Result<(), ErrorGuaranteed> {
     let resolved_id = def_id.resolved_id();
     let mut infcx_root = genv.infcx_root(resolved_id, opts).build().emit(&genv)?;
@@ -69,9 +74,14 @@ fn check_invariant(
         let pred = invariant.apply(&variant.idx);
         rcx.check_pred(&pred, Tag::new(ConstrReason::Other, DUMMY_SP));
     }
-    let errors = infcx_root
-        .execute_fixpoint_query(cache, def_id, "fluxc")
-        .emit(&genv)?;
+    let solved = infcx_root
+        .encode_fixpoint_query(cache, def_id, "fluxc")
+        .emit(&genv)?
+        .solve_full(cache);
+    stats.record_query(def_id.local_id(), solved.stats);
+    let errors = solved
+        .outcome
+        .map_err(|err| crate::report_solver_error(genv, span, err))?;
 
     if errors.is_empty() {
         Ok(())
@@ -13,6 +13,10 @@
 )]
 
 extern crate rustc_data_structures;
+// Unit tests are linked into an executable, which needs the compiler's dylib
+#[cfg(test)]
+#[allow(unused_extern_crates)]
+extern crate rustc_driver;
 extern crate rustc_errors;
 
 extern crate rustc_hir;
@@ -30,35 +34,42 @@ mod ghost_statements;
 pub mod invariants;
 mod primops;
 mod queue;
+pub mod stats;
+mod suggest;
 mod type_env;
 
+use std::time::Instant;
+
 use checker::{trait_impl_subtyping, Checker};
-use flux_common::{dbg, result::ResultExt as _};
+use flux_common::{dbg, iter::IterExt, result::ResultExt as _};
 use flux_infer::{
-    fixpoint_encoding::FixQueryCache,
+    fixpoint_encoding::{
+        Counterexample, FixQueryCache, FixpointOutcome, FixpointQuery, LimitExceeded, SolverError,
+        SolverPool,
+    },
     infer::{ConstrReason, SubtypeReason, Tag},
 };
 use flux_macros::fluent_messages;
-use flux_middle::{
-    global_env::GlobalEnv,
-    queries::QueryResult,
-    rty::{self, ESpan},
-    MaybeExternId,
-};
+use flux_middle::{global_env::GlobalEnv, queries::QueryResult, rty, MaybeExternId};
+use flux_rustc_bridge::mir::{Body, TerminatorKind};
 use itertools::Itertools;
-use rustc_errors::ErrorGuaranteed;
+use rustc_errors::{Diagnostic, ErrorGuaranteed, Level};
 use rustc_hir::def_id::LocalDefId;
 use rustc_span::Span;
 
-use crate::{checker::errors::ResultExt as _, ghost_statements::compute_ghost_statements};
+use crate::{
+    checker::errors::ResultExt as _, ghost_statements::compute_ghost_statements, stats::CheckStats,
+};
 
 fluent_messages! { "../locales/en-US.ftl" }
 
 fn report_fixpoint_errors(
     genv: GlobalEnv,
     local_id: LocalDefId,
-    errors: Vec<Tag>,
+    outcome: FixpointOutcome<Tag>,
 ) -> Result<(), ErrorGuaranteed> {
+    let errors =
+        outcome.map_err(|err| report_solver_error(genv, genv.tcx().def_span(local_id), err))?;
     #[expect(clippy::collapsible_else_if, reason = "it looks better")]
     if genv.should_fail(local_id) {
         if errors.is_empty() {
@@ -75,10 +86,65 @@ fn report_fixpoint_errors(
     }
 }
 
+/// Determines when the fixpoint queries generated while checking a function are solved
+pub enum FixpointMode<'a> {
+    /// Solve each query as soon as it is generated and report errors right away
+    Immediate,
+    /// Submit queries to be solved in the background. Errors are reported by
+    /// [`DeferredChecks::report`].
+    Deferred(&'a mut DeferredChecks),
+}
+
+/// Functions whose fixpoint queries are being solved in the background
+pub struct DeferredChecks {
+    pool: SolverPool<Tag>,
+    /// Functions in the order they were checked with the number of queries submitted for each one
+    fns: Vec<(LocalDefId, usize)>,
+}
+
+impl DeferredChecks {
+    pub fn new(jobs: usize) -> Self {
+        DeferredChecks { pool: SolverPool::new(jobs), fns: vec![] }
+    }
+
+    fn submit(&mut self, local_id: LocalDefId, query: FixpointQuery<Tag>) {
+        match self.fns.last_mut() {
+            Some((last_id, count)) if *last_id == local_id => *count += 1,
+            _ => self.fns.push((local_id, 1)),
+        }
+        self.pool.submit(query);
+    }
+
+    /// Waits for all submitted queries and reports errors in the order functions were checked.
+    /// As in [`FixpointMode::Immediate`], we stop reporting errors for a function after the first
+    /// query that fails.
+    pub fn report(
+        self,
+        genv: GlobalEnv,
+        cache: &mut FixQueryCache,
+        stats: &mut CheckStats,
+    ) -> Result<(), ErrorGuaranteed> {
+        let mut results = self.pool.finish(cache).into_iter();
+        self.fns
+            .into_iter()
+            .try_for_each_exhaust(|(local_id, count)| {
+                let solved = results.by_ref().take(count).collect_vec();
+                for query in &solved {
+                    stats.record_query(local_id, query.stats.clone());
+                }
+                solved
+                    .into_iter()
+                    .try_for_each(|query| report_fixpoint_errors(genv, local_id, query.outcome))
+            })
+    }
+}
+
 pub fn check_fn(
     genv: GlobalEnv,
     cache: &mut FixQueryCache,
     def_id: MaybeExternId,
+    mut mode: FixpointMode,
+    stats: &mut CheckStats,
 ) -> Result<(), ErrorGuaranteed> {
     let span = genv.tcx().def_span(def_id);
 
@@ -110,11 +176,13 @@ pub fn check_fn(
         .map_err(|err| err.emit(genv, def_id))?
     {
         tracing::info!("check_fn::refine-subtyping");
-        let errors = infcx_root
-            .execute_fixpoint_query(cache, def_id, "sub.fluxc")
+        let query = infcx_root
+            .encode_fixpoint_query(cache, def_id, "sub.fluxc")
             .emit(&genv)?;
+        // The body is only checked if the method is a subtype of the trait method, so we need the
+        // answer right away even if queries are being deferred.
+        solve_or_defer(genv, cache, local_id, query, &mut FixpointMode::Immediate, stats)?;
         tracing::info!("check_fn::fixpoint-subtyping");
-        report_fixpoint_errors(genv, local_id, errors)?;
     }
 
     // Skip trusted functions
@@ -127,29 +195,90 @@ pub fn check_fn(
             .with_span(span)
             .map_err(|err| err.emit(genv, def_id))?;
 
+        // Make the assumption visible when there's cleanup code that won't be checked
+        if opts.assume_panic_abort {
+            let body = genv
+                .mir(local_id)
+                .with_span(span)
+                .map_err(|err| err.emit(genv, def_id))?;
+            if has_cleanup_drops(&body) {
+                let name = genv.tcx().item_name(local_id.to_def_id());
+                genv.sess()
+                    .emit_warn(errors::PanicAbortAssumed { span, name });
+            }
+        }
+
         // PHASE 1: infer shape of `TypeEnv` at the entry of join points
+        let start = Instant::now();
         let shape_result = Checker::run_in_shape_mode(genv, local_id, &ghost_stmts, opts)
             .map_err(|err| err.emit(genv, def_id))?;
+        stats.record_shape(local_id, start.elapsed());
         tracing::info!("check_fn::shape");
 
         // PHASE 2: generate refinement tree constraint
-        let infcx_root =
+        let start = Instant::now();
+        let (infcx_root, holes) =
             Checker::run_in_refine_mode(genv, local_id, &ghost_stmts, shape_result, opts)
                 .map_err(|err| err.emit(genv, def_id))?;
+        stats.record_refine(local_id, start.elapsed());
         tracing::info!("check_fn::refine");
 
         // PHASE 3: invoke fixpoint on the constraint
-        let errors = infcx_root
-            .execute_fixpoint_query(cache, def_id, "fluxc")
+        let query = infcx_root
+            .encode_fixpoint_query(cache, def_id, "fluxc")
             .emit(&genv)?;
+        if let Some(holes) = holes {
+            // We need the solution to suggest a signature so we cannot defer the query
+            let solved = query.solve_full(cache);
+            stats.record_query(local_id, solved.stats);
+            let safe = matches!(&solved.outcome, Ok(errors) if errors.is_empty());
+            report_fixpoint_errors(genv, local_id, solved.outcome)?;
+            if safe {
+                holes.suggest(genv, &solved.solution);
+            }
+        } else {
+            solve_or_defer(genv, cache, local_id, query, &mut mode, stats)?;
+        }
         tracing::info!("check_fn::fixpoint");
-        report_fixpoint_errors(genv, local_id, errors)?;
         Ok(())
     })?;
 
     dbg::check_fn_span!(genv.tcx(), local_id).in_scope(|| Ok(()))
 }
 
+/// Whether the cleanup code of `body` drops anything. Every function with calls has cleanup blocks
+/// that just resume unwinding, which are not worth reporting.
+fn has_cleanup_drops(body: &Body) -> bool {
+    body.basic_blocks.iter().any(|data| {
+        data.is_cleanup
+            && data
+                .terminator
+                .as_ref()
+                .is_some_and(|term| matches!(term.kind, TerminatorKind::Drop { .. }))
+    })
+}
+
+fn solve_or_defer(
+    genv: GlobalEnv,
+    cache: &mut FixQueryCache,
+    local_id: LocalDefId,
+    query: FixpointQuery<Tag>,
+    mode: &mut FixpointMode,
+    stats: &mut CheckStats,
+) -> Result<(), ErrorGuaranteed> {
+    match mode {
+        FixpointMode::Immediate => {
+            let solved = query.solve_full(cache);
+            stats.record_query(local_id, solved.stats);
+            report_fixpoint_errors(genv, local_id, solved.outcome)
+        }
+        FixpointMode::Deferred(deferred) => {
+            deferred.submit(local_id, query);
+            Ok(())
+        }
+    }
+}
+
 fn force_conv(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult {
     genv.generics_of(def_id)?;
     genv.refinement_generics_of(def_id)?;
@@ -158,39 +287,36 @@ fn force_conv(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult {
     Ok(())
 }
 
-fn call_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
-    genv.sess()
-        .emit_err(errors::RefineError::call(span, dst_span))
-}
-
-fn ret_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
-    genv.sess()
-        .emit_err(errors::RefineError::ret(span, dst_span))
-}
-
-fn report_errors(genv: GlobalEnv, errors: Vec<Tag>) -> Result<(), ErrorGuaranteed> {
+fn report_errors(
+    genv: GlobalEnv,
+    errors: Vec<(Tag, Counterexample)>,
+) -> Result<(), ErrorGuaranteed> {
     let mut e = None;
-    for err in errors {
+    for (err, cex) in errors {
         let span = err.src_span;
         e = Some(match err.reason {
             ConstrReason::Call
             | ConstrReason::Subtype(SubtypeReason::Input)
             | ConstrReason::Subtype(SubtypeReason::Requires) => {
-                call_error(genv, span, err.dst_span)
+                emit_err(genv, errors::RefineError::call(span, err.dst_span), &cex)
             }
-            ConstrReason::Assign => genv.sess().emit_err(errors::AssignError { span }),
+            ConstrReason::Assign => emit_err(genv, errors::AssignError { span }, &cex),
             ConstrReason::Ret
             | ConstrReason::Subtype(SubtypeReason::Output)
-            | ConstrReason::Subtype(SubtypeReason::Ensures) => ret_error(genv, span, err.dst_span),
-            ConstrReason::Div => genv.sess().emit_err(errors::DivError { span }),
-            ConstrReason::Rem => genv.sess().emit_err(errors::RemError { span }),
-            ConstrReason::Goto(_) => genv.sess().emit_err(errors::GotoError { span }),
-            ConstrReason::Assert(msg) => genv.sess().emit_err(errors::AssertError { span, msg }),
+            | ConstrReason::Subtype(SubtypeReason::Ensures) => {
+                emit_err(genv, errors::RefineError::ret(span, err.dst_span), &cex)
+            }
+            ConstrReason::Div => emit_err(genv, errors::DivError { span }, &cex),
+            ConstrReason::Rem => emit_err(genv, errors::RemError { span }, &cex),
+            ConstrReason::Goto(_) => emit_err(genv, errors::GotoError { span }, &cex),
+            ConstrReason::Assert(msg) => emit_err(genv, errors::AssertError { span, msg }, &cex),
             ConstrReason::Fold | ConstrReason::FoldLocal => {
-                genv.sess().emit_err(errors::FoldError { span })
+                emit_err(genv, errors::FoldError { span }, &cex)
             }
-            ConstrReason::Overflow => genv.sess().emit_err(errors::OverflowError { span }),
-            ConstrReason::Other => genv.sess().emit_err(errors::UnknownError { span }),
+            ConstrReason::Overflow => emit_err(genv, errors::OverflowError { span }, &cex),
+            ConstrReason::RawPtrDeref => emit_err(genv, errors::RawPtrDerefError { span }, &cex),
+            ConstrReason::RawPtrOffset => emit_err(genv, errors::RawPtrOffsetError { span }, &cex),
+            ConstrReason::Other => emit_err(genv, errors::UnknownError { span }, &cex),
         });
     }
 
@@ -201,6 +327,43 @@ fn report_errors(genv: GlobalEnv, errors: Vec<Tag>) -> Result<(), ErrorGuarantee
     }
 }
 
+/// Emits `err` adding a note with the counterexample if there's one. Only the native backend
+/// reports models, so the counterexample is always empty with the `fixpoint` binary.
+fn emit_err<'a>(
+    genv: GlobalEnv<'a, '_>,
+    err: impl Diagnostic<'a>,
+    cex: &Counterexample,
+) -> ErrorGuaranteed {
+    use crate::fluent_generated as fluent;
+
+    let dcx = genv.sess().dcx().handle();
+    let mut diag = err.into_diag(dcx, Level::Error);
+    if !cex.is_empty() {
+        diag.note(fluent::refineck_counterexample_note);
+        diag.arg("counterexample", cex.to_string());
+    }
+    diag.emit()
+}
+
+/// Reports that the solver was stopped before it could check the item at `span`
+pub(crate) fn report_solver_error(
+    genv: GlobalEnv,
+    span: Span,
+    err: SolverError,
+) -> ErrorGuaranteed {
+    match err {
+        SolverError::LimitExceeded(LimitExceeded::Timeout(timeout)) => {
+            genv.sess()
+                .emit_err(errors::TimeoutError { span, secs: timeout.as_secs() })
+        }
+        SolverError::LimitExceeded(LimitExceeded::Memory(bytes)) => {
+            genv.sess()
+                .emit_err(errors::OutOfMemoryError { span, megabytes: bytes / (1024 * 1024) })
+        }
+        SolverError::Crash(msg) => genv.sess().emit_err(errors::SolverCrash { span, msg }),
+    }
+}
+
 fn report_expected_neg(genv: GlobalEnv, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
     Err(genv.sess().emit_err(errors::ExpectedNeg {
         span: genv.tcx().def_span(def_id),
@@ -212,7 +375,7 @@ mod errors {
     use flux_errors::E0999;
     use flux_macros::{Diagnostic, Subdiagnostic};
     use flux_middle::rty::ESpan;
-    use rustc_span::Span;
+    use rustc_span::{Span, Symbol};
 
     #[derive(Diagnostic)]
     #[diag(refineck_goto_error, code = E0999)]
@@ -312,6 +475,20 @@ mod errors {
         pub span: Span,
     }
 
+    #[derive(Diagnostic)]
+    #[diag(refineck_raw_ptr_deref_error, code = E0999)]
+    pub struct RawPtrDerefError {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_raw_ptr_offset_error, code = E0999)]
+    pub struct RawPtrOffsetError {
+        #[primary_span]
+        pub span: Span,
+    }
+
     #[derive(Diagnostic)]
     #[diag(refineck_unknown_error, code = E0999)]
     pub struct UnknownError {
@@ -326,4 +503,42 @@ mod errors {
         pub span: Span,
         pub def_descr: &'static str,
     }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_timeout_error, code = E0999)]
+    #[note]
+    #[help]
+    pub struct TimeoutError {
+        #[primary_span]
+        pub span: Span,
+        pub secs: u64,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_out_of_memory_error, code = E0999)]
+    #[note]
+    #[help]
+    pub struct OutOfMemoryError {
+        #[primary_span]
+        pub span: Span,
+        pub megabytes: u64,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_solver_crash, code = E0999)]
+    #[note]
+    pub struct SolverCrash {
+        #[primary_span]
+        pub span: Span,
+        pub msg: String,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_panic_abort_assumed)]
+    #[note]
+    pub struct PanicAbortAssumed {
+        #[primary_span]
+        pub span: Span,
+        pub name: Symbol,
+    }
 }
@@ -23,15 +23,37 @@
 ///     }
 /// }
 /// ```
+///
+/// Floating-point values are indexed by the (real) number they denote. Infinities are indexed by
+/// some number larger (or smaller) than the finite values they are compared with and the index of
+/// NaN is unspecified. Since comparisons involving NaN are always false, a comparison only tells us
+/// something about its operands when it holds, e.g., `a < b` has type `bool{v: v => a < b}`, and
+/// `a != b` only tells us that `a == b` when it doesn't hold. Negation is exact. The result of an
+/// arithmetic operation is the exact result rounded to a representable value, so we cannot give it
+/// a precise index. However, rounding is monotone and maps representable values to themselves,
+/// which lets us soundly bound the result by comparing it with the operands, e.g., if `b >= 0.0`
+/// then `a + b >= a`.
+///
+/// By default, bitwise and shift operators on integers are unrefined. When `precise_bit_ops` is
+/// enabled, their results are defined by converting the operands to bit-vectors of the width of the
+/// type. Since reasoning about these conversions is hard for the solver, we also give linear bounds
+/// that hold in common cases, e.g., `a & b <= b` if `b >= 0`.
+///
+/// The `offset` intrinsic moves the offset of a raw pointer. When `check_raw_pointers` is enabled,
+/// the result must stay within the allocation or point one element past its end.
 use std::{hash::Hash, sync::LazyLock};
 
 use flux_common::tracked_span_bug;
 use flux_infer::infer::ConstrReason;
 use flux_macros::primop_rules;
-use flux_middle::rty::{self, BaseTy, Expr};
+use flux_middle::{
+    fhir::SpecFuncKind,
+    rty::{self, BaseTy, Constant, Expr, List, Real},
+};
 use flux_rustc_bridge::mir;
 use rty::{BinOp::Mod, Expr as E};
 use rustc_data_structures::unord::UnordMap;
+use rustc_span::Symbol;
 
 pub(crate) struct MatchedRule {
     pub precondition: Option<Pre>,
@@ -43,6 +65,7 @@ pub(crate) struct Pre {
     pub pred: Expr,
 }
 
+#[expect(clippy::too_many_arguments)]
 pub(crate) fn match_bin_op(
     op: mir::BinOp,
     bty1: &BaseTy,
@@ -50,9 +73,18 @@ pub(crate) fn match_bin_op(
     bty2: &BaseTy,
     idx2: &Expr,
     check_overflow: bool,
+    precise_bit_ops: bool,
+    check_raw_pointers: bool,
 ) -> MatchedRule {
+    let inputs = [(bty1.clone(), idx1.clone()), (bty2.clone(), idx2.clone())];
+    if precise_bit_ops && let Some(rule) = PRECISE_BIT_BIN_OPS.try_match_inputs(&op, &inputs) {
+        return rule;
+    }
+    if check_raw_pointers && let Some(rule) = RAW_PTR_BIN_OPS.try_match_inputs(&op, &inputs) {
+        return rule;
+    }
     let table = if check_overflow { &OVERFLOW_BIN_OPS } else { &DEFAULT_BIN_OPS };
-    table.match_inputs(&op, [(bty1.clone(), idx1.clone()), (bty2.clone(), idx2.clone())])
+    table.match_inputs(&op, inputs)
 }
 
 pub(crate) fn match_un_op(
@@ -60,9 +92,14 @@ pub(crate) fn match_un_op(
     bty: &BaseTy,
     idx: &Expr,
     check_overflow: bool,
+    precise_bit_ops: bool,
 ) -> MatchedRule {
+    let inputs = [(bty.clone(), idx.clone())];
+    if precise_bit_ops && let Some(rule) = PRECISE_BIT_UN_OPS.try_match_inputs(&op, &inputs) {
+        return rule;
+    }
     let table = if check_overflow { &OVERFLOW_UN_OPS } else { &DEFAULT_UN_OPS };
-    table.match_inputs(&op, [(bty.clone(), idx.clone())])
+    table.match_inputs(&op, inputs)
 }
 
 struct RuleTable<Op: Eq + Hash, const N: usize> {
@@ -74,6 +111,12 @@ impl<Op: Eq + Hash, const N: usize> RuleTable<Op, N> {
         (self.rules[op])(&inputs)
             .unwrap_or_else(|| tracked_span_bug!("no primop rule for {inputs:?}"))
     }
+
+    /// Like [`RuleTable::match_inputs`] but returns `None` if there's no rule for the operator or
+    /// none of its rules match the inputs.
+    fn try_match_inputs(&self, op: &Op, inputs: &[(BaseTy, Expr); N]) -> Option<MatchedRule> {
+        (self.rules.get(op)?)(inputs)
+    }
 }
 
 type RuleMatcher<const N: usize> = fn(&[(BaseTy, Expr); N]) -> Option<MatchedRule>;
@@ -102,6 +145,8 @@ static DEFAULT_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new(|| {
             // Shifts
             (Shl, mk_shl_rules()),
             (Shr, mk_shr_rules()),
+            // Pointers
+            (Offset, mk_offset_rules()),
         ]
         .into_iter()
         .collect(),
@@ -132,12 +177,42 @@ static OVERFLOW_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new(|| {
             // Shifts
             (Shl, mk_shl_rules()),
             (Shr, mk_shr_rules()),
+            // Pointers
+            (Offset, mk_offset_rules()),
+        ]
+        .into_iter()
+        .collect(),
+    }
+});
+
+/// Rules for bitwise and shift operators used when `precise_bit_ops` is enabled. Operators or inputs
+/// not covered by this table fall back to the default rules.
+static PRECISE_BIT_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new(|| {
+    use mir::BinOp::*;
+    RuleTable {
+        rules: [
+            (BitAnd, mk_precise_bit_and_rules()),
+            (BitOr, mk_precise_bit_or_rules()),
+            (BitXor, mk_precise_bit_xor_rules()),
+            (Shl, mk_precise_shl_rules()),
+            (Shr, mk_precise_shr_rules()),
         ]
         .into_iter()
         .collect(),
     }
 });
 
+/// Rules for pointer operators used when `check_raw_pointers` is enabled
+static RAW_PTR_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new(|| {
+    use mir::BinOp::*;
+    RuleTable { rules: [(Offset, mk_checked_offset_rules())].into_iter().collect() }
+});
+
+static PRECISE_BIT_UN_OPS: LazyLock<RuleTable<mir::UnOp, 1>> = LazyLock::new(|| {
+    use mir::UnOp::*;
+    RuleTable { rules: [(Not, mk_precise_not_rules())].into_iter().collect() }
+});
+
 static DEFAULT_UN_OPS: LazyLock<RuleTable<mir::UnOp, 1>> = LazyLock::new(|| {
     use mir::UnOp::*;
     RuleTable {
@@ -171,6 +246,9 @@ fn mk_add_rules(check_overflow: bool) -> RuleMatcher<2> {
             requires E::le(a + b, E::uint_max(uint_ty)) => ConstrReason::Overflow
             if let &BaseTy::Uint(uint_ty) = T
 
+            fn(a: T, b: T) -> T{v: float_add_bounds(a, b, &v)}
+            if T.is_float()
+
             fn(a: T, b: T) -> T
         }
     } else {
@@ -178,6 +256,9 @@ fn mk_add_rules(check_overflow: bool) -> RuleMatcher<2> {
             fn(a: T, b: T) -> T[a + b]
             if T.is_integral()
 
+            fn(a: T, b: T) -> T{v: float_add_bounds(a, b, &v)}
+            if T.is_float()
+
             fn(a: T, b: T) -> T
         }
     }
@@ -198,6 +279,9 @@ fn mk_mul_rules(check_overflow: bool) -> RuleMatcher<2> {
             requires E::le(a * b, E::uint_max(uint_ty)) => ConstrReason::Overflow
             if let &BaseTy::Uint(uint_ty) = T
 
+            fn(a: T, b: T) -> T{v: float_mul_bounds(a, b, &v)}
+            if T.is_float()
+
             fn(a: T, b: T) -> T
         }
     } else {
@@ -205,7 +289,7 @@ fn mk_mul_rules(check_overflow: bool) -> RuleMatcher<2> {
             fn(a: T, b: T) -> T[a * b]
             if T.is_integral()
 
-            fn(a: T, b: T) -> T
+            fn(a: T, b: T) -> T{v: float_mul_bounds(a, b, &v)}
             if T.is_float()
         )
     }
@@ -229,6 +313,9 @@ fn mk_sub_rules(check_overflow: bool) -> RuleMatcher<2> {
                      ) => ConstrReason::Overflow
             if let &BaseTy::Uint(uint_ty) = T
 
+            fn(a: T, b: T) -> T{v: float_sub_bounds(a, b, &v)}
+            if T.is_float()
+
             fn(a: T, b: T) -> T
         }
     } else {
@@ -240,7 +327,7 @@ fn mk_sub_rules(check_overflow: bool) -> RuleMatcher<2> {
             fn(a: T, b: T) -> T[a - b]
             if T.is_signed()
 
-            fn(a: T, b: T) -> T
+            fn(a: T, b: T) -> T{v: float_sub_bounds(a, b, &v)}
             if T.is_float()
         }
     }
@@ -253,7 +340,7 @@ fn mk_div_rules() -> RuleMatcher<2> {
         requires E::ne(b, 0) => ConstrReason::Div
         if T.is_integral()
 
-        fn(a: T, b: T) -> T
+        fn(a: T, b: T) -> T{v: float_div_bounds(a, b, &v)}
         if T.is_float()
     }
 }
@@ -307,6 +394,9 @@ fn mk_eq_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::eq(a, b)]
         if T.is_integral() || T.is_bool() || T.is_char() || T.is_str()
 
+        fn(a: T, b: T) -> bool{v: E::implies(&v, E::eq(a, b))}
+        if T.is_float()
+
         fn(a: T, b: T) -> bool
     }
 }
@@ -317,6 +407,9 @@ fn mk_ne_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::ne(a, b)]
         if T.is_integral() || T.is_bool()
 
+        fn(a: T, b: T) -> bool{v: E::implies(v.not(), E::eq(a, b))}
+        if T.is_float()
+
         fn(a: T, b: T) -> bool
     }
 }
@@ -327,6 +420,9 @@ fn mk_le_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::le(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(&v, E::le(a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::implies(a, b)]
 
         fn(a: T, b: T) -> bool
@@ -339,6 +435,9 @@ fn mk_ge_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::ge(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(&v, E::ge(a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::implies(b, a)]
 
         fn(a: T, b: T) -> bool
@@ -351,6 +450,9 @@ fn mk_lt_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::lt(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(&v, E::lt(a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::and(a.not(), b)]
 
         fn(a: T, b: T) -> bool
@@ -363,6 +465,9 @@ fn mk_gt_rules() -> RuleMatcher<2> {
         fn(a: T, b: T) -> bool[E::gt(a, b)]
         if T.is_integral()
 
+        fn(a: T, b: T) -> bool{v: E::implies(&v, E::gt(a, b))}
+        if T.is_float()
+
         fn(a: bool, b: bool) -> bool[E::and(a, b.not())]
 
         fn(a: T, b: T) -> bool
@@ -385,6 +490,23 @@ fn mk_shr_rules() -> RuleMatcher<2> {
     }
 }
 
+/// `offset(a, b)`
+fn mk_offset_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T[offset_raw_ptr(a, b)]
+        if T.is_raw_ptr() && S.is_integral()
+    }
+}
+
+/// `offset(a, b)` when `check_raw_pointers` is enabled
+fn mk_checked_offset_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T[offset_raw_ptr(a, b)]
+        requires offset_in_bounds(a, b) => ConstrReason::RawPtrOffset
+        if T.is_raw_ptr() && S.is_integral()
+    }
+}
+
 /// `-a`
 fn mk_neg_rules(check_overflow: bool) -> RuleMatcher<1> {
     if check_overflow {
@@ -399,10 +521,7 @@ fn mk_neg_rules(check_overflow: bool) -> RuleMatcher<1> {
     } else {
         primop_rules! {
             fn(a: T) -> T[a.neg()]
-            if T.is_integral()
-
-            fn(a: T) -> T
-            if T.is_float()
+            if T.is_integral() || T.is_float()
         }
     }
 }
@@ -416,3 +535,200 @@ fn mk_not_rules() -> RuleMatcher<1> {
         if T.is_integral()
     }
 }
+
+/// `a & b` when `precise_bit_ops` is enabled
+fn mk_precise_bit_and_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T{v: E::and_from_iter([
+                                   E::eq(&v, bv_app(T, "and", [a, b])),
+                                   E::implies(E::ge(a, 0), E::and(E::ge(&v, 0), E::le(&v, a))),
+                                   E::implies(E::ge(b, 0), E::and(E::ge(&v, 0), E::le(&v, b))),
+                               ])}
+        if T.is_integral()
+    }
+}
+
+/// `a | b` when `precise_bit_ops` is enabled
+fn mk_precise_bit_or_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T{v: E::and_from_iter([
+                                   E::eq(&v, bv_app(T, "or", [a, b])),
+                                   E::implies(
+                                       E::and(E::ge(a, 0), E::ge(b, 0)),
+                                       E::and(E::ge(&v, a), E::ge(&v, b)),
+                                   ),
+                               ])}
+        if T.is_integral()
+    }
+}
+
+/// `a ^ b` when `precise_bit_ops` is enabled
+fn mk_precise_bit_xor_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[bv_app(T, "xor", [a, b])]
+        if T.is_integral()
+    }
+}
+
+/// `a << b` when `precise_bit_ops` is enabled. The result is only defined when the shift amount is
+/// smaller than the width of the type. Larger amounts either panic or are masked, depending on
+/// whether overflow checks are enabled in rustc.
+fn mk_precise_shl_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T{v: E::implies(
+                                   valid_shift_amount(T, b),
+                                   E::eq(&v, bv_app(T, "shl", [a, b])),
+                               )}
+        if T.is_integral() && S.is_integral()
+    }
+}
+
+/// `a >> b` when `precise_bit_ops` is enabled. Shifting right an unsigned integer by any amount
+/// results in a smaller value.
+fn mk_precise_shr_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T{v: E::and(
+                                   E::implies(
+                                       valid_shift_amount(T, b),
+                                       E::eq(&v, bv_app(T, "lshr", [a, b])),
+                                   ),
+                                   E::le(&v, a),
+                               )}
+        if T.is_unsigned() && S.is_integral()
+
+        fn(a: T, b: S) -> T{v: E::implies(
+                                   valid_shift_amount(T, b),
+                                   E::eq(&v, bv_app(T, "ashr", [a, b])),
+                               )}
+        if T.is_signed() && S.is_integral()
+    }
+}
+
+/// `!a` when `precise_bit_ops` is enabled. Flipping all the bits of an integer is a linear
+/// operation so we don't need bit-vectors.
+fn mk_precise_not_rules() -> RuleMatcher<1> {
+    primop_rules! {
+        fn(a: T) -> T[E::uint_max(uint_ty) - a]
+        if let &BaseTy::Uint(uint_ty) = T
+
+        fn(a: T) -> T[a.neg() - 1]
+        if T.is_signed()
+    }
+}
+
+/// Width in bits of the integral type `bty`
+fn bit_width(bty: &BaseTy) -> u64 {
+    match bty {
+        BaseTy::Int(int_ty) => int_ty.bit_width(),
+        BaseTy::Uint(uint_ty) => uint_ty.bit_width(),
+        _ => tracked_span_bug!("expected an integral type, found `{bty:?}`"),
+    }
+    .unwrap_or(flux_config::pointer_width().bits())
+}
+
+/// Whether `b` is a valid amount to shift a value of type `bty`, i.e., if it's smaller than the
+/// width of `bty`. The amount is converted to a bit-vector of the same width as the shifted value,
+/// which preserves its value only if it's valid.
+fn valid_shift_amount(bty: &BaseTy, b: &Expr) -> Expr {
+    E::and(E::ge(b, 0), E::lt(b, bit_width(bty) as i32))
+}
+
+/// Applies the bit-vector operation `bv_{op}` to the integers `args` of type `bty` converting them
+/// to bit-vectors of the width of `bty`. The resulting bit-vector is converted back to an integer
+/// of type `bty`, interpreting it in two's complement if `bty` is signed.
+fn bv_app<const N: usize>(bty: &BaseTy, op: &str, args: [&Expr; N]) -> Expr {
+    let width = bit_width(bty);
+    let int_to_bv = sized_theory_func("bv_int_to_bv", "int_to_bv", width);
+    let bv_to_int = sized_theory_func("bv_bv_to_int", "bv_to_int", width);
+    let func = E::global_func(
+        Symbol::intern(&format!("bv_{op}")),
+        SpecFuncKind::Thy(Symbol::intern(&format!("bv{op}"))),
+    );
+    let args = args
+        .into_iter()
+        .map(|arg| E::app(&int_to_bv, List::singleton(arg.clone())))
+        .collect();
+    let res = E::app(bv_to_int, List::singleton(E::app(func, List::from_vec(args))));
+    if let BaseTy::Int(int_ty) = bty {
+        // `res` is in the range `[0, 2^width)` so we subtract `2^width` if the sign bit is set
+        let modulus = Expr::constant(Constant::uint_max(width as u32)) + 1;
+        E::ite(E::gt(&res, E::int_max(*int_ty)), &res - modulus, res)
+    } else {
+        res
+    }
+}
+
+/// The instance of the sized theory function `name` for bit-vectors of the given width
+fn sized_theory_func(name: &str, fixpoint_name: &str, width: u64) -> Expr {
+    let instance =
+        flux_middle::sized_theory_func_instance(Symbol::intern(fixpoint_name), &[width as usize])
+            .unwrap_or_else(|| tracked_span_bug!("invalid width `{width}` for `{name}`"));
+    E::global_func(Symbol::intern(name), SpecFuncKind::Thy(instance))
+}
+
+/// Bounds for the result `v` of the floating-point addition `a + b`
+fn float_add_bounds(a: &Expr, b: &Expr, v: &Expr) -> Expr {
+    let zero = Expr::from(Real::ZERO);
+    Expr::and_from_iter([
+        E::implies(E::ge(b, &zero), E::ge(v, a)),
+        E::implies(E::le(b, &zero), E::le(v, a)),
+        E::implies(E::ge(a, &zero), E::ge(v, b)),
+        E::implies(E::le(a, &zero), E::le(v, b)),
+    ])
+}
+
+/// Bounds for the result `v` of the floating-point subtraction `a - b`
+fn float_sub_bounds(a: &Expr, b: &Expr, v: &Expr) -> Expr {
+    let zero = Expr::from(Real::ZERO);
+    Expr::and_from_iter([
+        E::implies(E::ge(b, &zero), E::le(v, a)),
+        E::implies(E::le(b, &zero), E::ge(v, a)),
+        E::implies(E::ge(a, b), E::ge(v, &zero)),
+        E::implies(E::le(a, b), E::le(v, &zero)),
+    ])
+}
+
+/// Bounds for the result `v` of the floating-point multiplication `a * b`. Besides the sign of the
+/// result, if one of the operands is in the interval `[0, 1]` and the other one is non-negative, the
+/// result is bounded by the latter.
+fn float_mul_bounds(a: &Expr, b: &Expr, v: &Expr) -> Expr {
+    let zero = Expr::from(Real::ZERO);
+    let one = Expr::from(Real::ONE);
+    let nonneg = |e| E::ge(e, &zero);
+    let nonpos = |e| E::le(e, &zero);
+    let unit = |e| E::and(E::ge(e, &zero), E::le(e, &one));
+    Expr::and_from_iter([
+        E::implies(E::and(nonneg(a), nonneg(b)), nonneg(v)),
+        E::implies(E::and(nonpos(a), nonpos(b)), nonneg(v)),
+        E::implies(E::and(nonneg(a), nonpos(b)), nonpos(v)),
+        E::implies(E::and(nonpos(a), nonneg(b)), nonpos(v)),
+        E::implies(E::and(unit(a), nonneg(b)), E::le(v, b)),
+        E::implies(E::and(nonneg(a), unit(b)), E::le(v, a)),
+    ])
+}
+
+/// Bounds for the result `v` of the floating-point division `a / b`. Division by zero is not
+/// modelled (it results in an infinity or NaN), so all bounds require a positive divisor.
+fn float_div_bounds(a: &Expr, b: &Expr, v: &Expr) -> Expr {
+    let zero = Expr::from(Real::ZERO);
+    let one = Expr::from(Real::ONE);
+    Expr::and_from_iter([
+        E::implies(E::and(E::ge(a, &zero), E::gt(b, &zero)), E::ge(v, &zero)),
+        E::implies(E::and(E::le(a, &zero), E::gt(b, &zero)), E::le(v, &zero)),
+        E::implies(E::and(E::ge(a, &zero), E::ge(b, &one)), E::le(v, a)),
+    ])
+}
+
+/// The index of the raw pointer `a` moved `b` elements forward.
+fn offset_raw_ptr(a: &Expr, b: &Expr) -> Expr {
+    let (base, off, len) = a.raw_ptr_fields();
+    E::raw_ptr(base, E::binary_op(rty::BinOp::Add, off, b), len)
+}
+
+/// Whether moving the raw pointer `a` by `b` elements stays in its allocation. Pointing one
+/// element past the end is allowed.
+fn offset_in_bounds(a: &Expr, b: &Expr) -> Expr {
+    let (_, off, len) = a.raw_ptr_fields();
+    let off = E::binary_op(rty::BinOp::Add, off, b);
+    E::and(E::ge(&off, 0), E::le(off, len))
+}
@@ -0,0 +1,193 @@
+//! Statistics about checking each item in a crate. Reported with `FLUX_DUMP_STATS=1`.
+
+use std::{io, path::Path, time::Duration};
+
+use flux_infer::fixpoint_encoding::QueryStats;
+use flux_middle::global_env::GlobalEnv;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_hir::def_id::LocalDefId;
+use serde::Serialize;
+
+/// Statistics for the items checked so far (functions and types with invariants), in the order
+/// they were checked
+#[derive(Default)]
+pub struct CheckStats {
+    items: FxIndexMap<LocalDefId, ItemStats>,
+}
+
+#[derive(Default)]
+struct ItemStats {
+    shape_time: Duration,
+    refine_time: Duration,
+    /// One entry for each fixpoint query that was not trivial
+    queries: Vec<QueryStats>,
+}
+
+/// A row in the report. Times are in milliseconds and the fixpoint statistics are added up over
+/// all the queries of the item.
+#[derive(Serialize)]
+struct ItemReport {
+    def_id: String,
+    kind: &'static str,
+    span: String,
+    shape_ms: f64,
+    refine_ms: f64,
+    fixpoint_ms: f64,
+    queries: usize,
+    /// Whether the results of all the queries were found in the cache
+    cached: bool,
+    kvars: usize,
+    qualifiers: usize,
+    num_cstr: i32,
+    num_iter: i32,
+    num_chck: i32,
+    num_vald: i32,
+}
+
+impl CheckStats {
+    pub(crate) fn record_shape(&mut self, local_id: LocalDefId, time: Duration) {
+        self.items.entry(local_id).or_default().shape_time += time;
+    }
+
+    pub(crate) fn record_refine(&mut self, local_id: LocalDefId, time: Duration) {
+        self.items.entry(local_id).or_default().refine_time += time;
+    }
+
+    pub(crate) fn record_query(&mut self, local_id: LocalDefId, stats: Option<QueryStats>) {
+        let item_stats = self.items.entry(local_id).or_default();
+        item_stats.queries.extend(stats);
+    }
+
+    /// Writes the statistics as a JSON array with an object per item
+    pub fn write_report(&self, genv: GlobalEnv, path: &Path) -> io::Result<()> {
+        let tcx = genv.tcx();
+        let rows: Vec<_> = self
+            .items
+            .iter()
+            .map(|(local_id, stats)| {
+                let def_id = local_id.to_def_id();
+                stats.report(
+                    tcx.def_path_str(def_id),
+                    tcx.def_descr(def_id),
+                    tcx.sess
+                        .source_map()
+                        .span_to_embeddable_string(tcx.def_span(*local_id)),
+                )
+            })
+            .collect();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(io::BufWriter::new(file), &rows)?;
+        Ok(())
+    }
+}
+
+impl ItemStats {
+    fn report(&self, def_id: String, kind: &'static str, span: String) -> ItemReport {
+        let mut row = ItemReport {
+            def_id,
+            kind,
+            span,
+            shape_ms: as_millis(self.shape_time),
+            refine_ms: as_millis(self.refine_time),
+            fixpoint_ms: 0.0,
+            queries: self.queries.len(),
+            cached: !self.queries.is_empty(),
+            kvars: 0,
+            qualifiers: 0,
+            num_cstr: 0,
+            num_iter: 0,
+            num_chck: 0,
+            num_vald: 0,
+        };
+        for query in &self.queries {
+            row.fixpoint_ms += as_millis(query.solve_time);
+            row.cached &= query.cached;
+            row.kvars += query.kvars;
+            row.qualifiers += query.qualifiers;
+            if let Some(fixpoint) = &query.fixpoint {
+                row.num_cstr += fixpoint.num_cstr;
+                row.num_iter += fixpoint.num_iter;
+                row.num_chck += fixpoint.num_chck;
+                row.num_vald += fixpoint.num_vald;
+            }
+        }
+        row
+    }
+}
+
+fn as_millis(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use liquid_fixpoint::Stats;
+
+    use super::*;
+
+    fn report(stats: &ItemStats) -> serde_json::Value {
+        serde_json::to_value(stats.report("f".to_string(), "function", "lib.rs:1:1".to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn add_up_queries() {
+        let stats = ItemStats {
+            shape_time: Duration::from_millis(2),
+            refine_time: Duration::from_millis(3),
+            queries: vec![
+                QueryStats {
+                    kvars: 1,
+                    qualifiers: 11,
+                    cached: true,
+                    solve_time: Duration::ZERO,
+                    fixpoint: Some(Stats { num_cstr: 1, num_iter: 2, num_chck: 3, num_vald: 3 }),
+                },
+                QueryStats {
+                    kvars: 2,
+                    qualifiers: 11,
+                    cached: false,
+                    solve_time: Duration::from_millis(5),
+                    fixpoint: Some(Stats { num_cstr: 2, num_iter: 1, num_chck: 4, num_vald: 2 }),
+                },
+            ],
+        };
+        assert_eq!(
+            report(&stats),
+            serde_json::json!({
+                "def_id": "f",
+                "kind": "function",
+                "span": "lib.rs:1:1",
+                "shape_ms": 2.0,
+                "refine_ms": 3.0,
+                "fixpoint_ms": 5.0,
+                "queries": 2,
+                "cached": false,
+                "kvars": 3,
+                "qualifiers": 22,
+                "num_cstr": 3,
+                "num_iter": 3,
+                "num_chck": 7,
+                "num_vald": 5,
+            })
+        );
+    }
+
+    #[test]
+    fn cached_queries() {
+        // A query that failed has no statistics from fixpoint
+        let query = QueryStats { cached: true, ..QueryStats::default() };
+        let stats = ItemStats { queries: vec![query.clone(), query], ..ItemStats::default() };
+        let row = report(&stats);
+        assert_eq!(row["cached"], true);
+        assert_eq!(row["num_cstr"], 0);
+
+        // An item with only trivial queries has nothing in the cache
+        let row = report(&ItemStats::default());
+        assert_eq!(row["queries"], 0);
+        assert_eq!(row["cached"], false);
+    }
+}
@@ -0,0 +1,415 @@
+//! Signature suggestions for functions without a `#[flux::sig]`.
+//!
+//! When [`InferOpts::suggest_sigs`] is set, functions without a signature are checked against a
+//! signature with *holes*: the index of every input that can be refined is bound to the name of the
+//! argument and the refinement of the output is a fresh kvar over the output and the inputs. The
+//! kvar is in head position when checking the body of the function, so the solution found by
+//! fixpoint is the strongest postcondition that can be expressed with the available qualifiers.
+//! After the function is verified, the solution is printed back as a signature, dropping the
+//! conjuncts that are implied by another one.
+//!
+//! Preconditions are not inferred, i.e., the refinements of the inputs are not holes. A kvar
+//! refining an input is only assumed when checking the body, so its strongest solution would be
+//! `false`. Finding a useful precondition requires the constraints of every caller of the
+//! function, which are checked separately, so it is out of scope for now.
+//!
+//! [`InferOpts::suggest_sigs`]: flux_config::InferOpts::suggest_sigs
+
+use std::fmt::Write;
+
+use flux_infer::{
+    fixpoint_encoding::{KVarEncoding, KVarSolutions},
+    infer::InferCtxt,
+};
+use flux_middle::{
+    global_env::GlobalEnv,
+    queries::QueryResult,
+    rty::{
+        self, fold::TypeFoldable, BaseTy, BinOp, Binder, BoundReftKind, BoundVariableKind, Expr,
+        ExprKind, FieldProj, FnOutput, FnSig, InferMode, PolyFnSig, Ty, TyKind, UnOp, INNERMOST,
+    },
+};
+use itertools::Itertools;
+use rustc_data_structures::fx::FxIndexSet;
+use rustc_hir::{def::DefKind, def_id::LocalDefId, FnRetTy, ImplicitSelfKind};
+use rustc_span::{symbol::kw, Symbol};
+use rustc_type_ir::BoundVar;
+
+/// The holes added to the signature of a function
+pub(crate) struct SigHoles {
+    local_id: LocalDefId,
+    /// The kvar refining the output
+    kvar: rty::KVar,
+}
+
+/// Replaces the signature of `local_id` with one with holes if the function doesn't have a
+/// signature and its output can be refined. Returns the signature unchanged otherwise.
+pub(crate) fn add_holes(
+    genv: GlobalEnv,
+    infcx: &InferCtxt,
+    local_id: LocalDefId,
+    poly_sig: PolyFnSig,
+) -> QueryResult<(PolyFnSig, Option<SigHoles>)> {
+    if !matches!(genv.tcx().def_kind(local_id), DefKind::Fn | DefKind::AssocFn) {
+        return Ok((poly_sig, None));
+    }
+    let Some(fn_sig) = genv.map().expect_owner_node(local_id)?.fn_sig() else {
+        return Ok((poly_sig, None));
+    };
+    if !fn_sig.decl.lifted {
+        return Ok((poly_sig, None));
+    }
+    let sig = poly_sig.skip_binder_ref();
+    let output = sig.output();
+    let Some((ret_bty, ret_sort)) = unrefined_base_ty(&output.skip_binder_ref().ret) else {
+        return Ok((poly_sig, None));
+    };
+
+    // Bind the index of each input to a new refinement parameter named after the argument
+    let arg_names = genv.tcx().fn_arg_names(local_id);
+    let mut vars = poly_sig.vars().to_vec();
+    let inputs = sig
+        .inputs()
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| {
+            let Some((bty, sort)) = unrefined_base_ty(ty) else { return ty.clone() };
+            let kind = match arg_names.get(i) {
+                Some(ident) if is_named(ident.name) => BoundReftKind::Named(ident.name),
+                _ => BoundReftKind::Annon,
+            };
+            let var = BoundVar::from_usize(vars.len());
+            vars.push(BoundVariableKind::Refine(sort, InferMode::EVar, kind));
+            Ty::indexed(bty.shift_out_escaping(1), Expr::bvar(INNERMOST, var, kind))
+        })
+        .collect();
+    let vars = rty::List::from_vec(vars);
+
+    // Refine the output with a kvar over the output and the inputs
+    let ret_vars = rty::List::singleton(BoundVariableKind::Refine(
+        ret_sort,
+        InferMode::EVar,
+        BoundReftKind::Named(Symbol::intern("v")),
+    ));
+    let kvar = infcx
+        .fresh_kvar(&[vars.clone(), output.vars().clone(), ret_vars.clone()], KVarEncoding::Single);
+    let ExprKind::KVar(kvar_data) = kvar.kind() else {
+        return Ok((poly_sig, None));
+    };
+    let kvar_data = kvar_data.clone();
+    let ret = Ty::exists(Binder::bind_with_vars(
+        Ty::constr(kvar, Ty::indexed(ret_bty, Expr::nu())),
+        ret_vars,
+    ));
+    let output = output.map(|output| FnOutput::new(ret, output.ensures));
+
+    let sig = FnSig::new(sig.safety, sig.abi, sig.requires.clone(), inputs, output);
+    Ok((Binder::bind_with_vars(sig, vars), Some(SigHoles { local_id, kvar: kvar_data })))
+}
+
+/// Returns the base type and sort of a type of the form `∃v. B[v]` if its sort is not trivial.
+/// The base type is returned under the existential binder.
+fn unrefined_base_ty(ty: &Ty) -> Option<(BaseTy, rty::Sort)> {
+    let TyKind::Exists(ty) = ty.kind() else { return None };
+    let TyKind::Indexed(bty, idx) = ty.skip_binder_ref().kind() else { return None };
+    let sort = bty.sort();
+    (idx.is_nu() && !sort.is_unit()).then(|| (bty.clone(), sort))
+}
+
+fn is_named(name: Symbol) -> bool {
+    name != kw::Empty && name != kw::Underscore
+}
+
+impl SigHoles {
+    /// Emits a warning with the inferred signature. Nothing is emitted if nothing interesting was
+    /// inferred for the output.
+    pub(crate) fn suggest(self, genv: GlobalEnv, solutions: &KVarSolutions) {
+        let tcx = genv.tcx();
+        let span = tcx.def_span(self.local_id);
+        let name = tcx.item_name(self.local_id.to_def_id());
+        let Some(solution) = solutions.get(self.kvar.kvid) else {
+            // This happens if the solver doesn't support reporting solutions, e.g., an old
+            // version of fixpoint. Let the user know instead of silently suggesting nothing.
+            genv.sess().emit_warn(errors::NoSolution { span, name });
+            return;
+        };
+        // The output is the only self argument of the kvar
+        let Some(nu) = self.kvar.args.first() else { return };
+        let pred = solution.apply(&self.kvar.args);
+        let conjuncts = simplify_conjuncts(pred.flatten_conjs(), nu)
+            .iter()
+            .filter_map(|e| fmt_expr(genv, e))
+            .sorted()
+            .collect_vec();
+        if conjuncts.is_empty() {
+            return;
+        }
+        let Some(sig) = self.fmt_sig(genv, &conjuncts.join(" && ")) else { return };
+        let indent = tcx
+            .sess
+            .source_map()
+            .span_to_margin(span)
+            .unwrap_or_default();
+        genv.sess().emit_warn(errors::SuggestedSig {
+            span,
+            name,
+            suggestion: span.shrink_to_lo(),
+            sig,
+            indent: " ".repeat(indent),
+        });
+    }
+
+    /// Prints the signature using the types as written in the source
+    fn fmt_sig(&self, genv: GlobalEnv, pred: &str) -> Option<String> {
+        let tcx = genv.tcx();
+        let decl = tcx.hir_node_by_def_id(self.local_id).fn_decl()?;
+        let source_map = tcx.sess.source_map();
+        let arg_names = tcx.fn_arg_names(self.local_id);
+        let inputs = decl
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                let name = arg_names.get(i).filter(|ident| is_named(ident.name));
+                let ty = if i == 0 && decl.implicit_self.has_implicit_self() {
+                    match decl.implicit_self {
+                        ImplicitSelfKind::RefImm => "&Self".to_string(),
+                        ImplicitSelfKind::RefMut => "&mut Self".to_string(),
+                        _ => "Self".to_string(),
+                    }
+                } else {
+                    source_map.span_to_snippet(ty.span).ok()?
+                };
+                Some(match name {
+                    Some(name) => format!("{name}: {ty}"),
+                    None => ty,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let FnRetTy::Return(ret) = decl.output else { return None };
+        let ret = source_map.span_to_snippet(ret.span).ok()?;
+        Some(format!("fn({}) -> {ret}{{v: {pred}}}", inputs.join(", ")))
+    }
+}
+
+/// Removes the conjuncts of a solution that are repeated or implied by another conjunct. The
+/// solution contains every instance of a qualifier that holds, so the same fact usually shows up in
+/// several forms, e.g., `v > x`, `x < v`, `v >= x` and `x <= v - 1`. Comparisons are normalized
+/// before looking for duplicates and then oriented so the output `nu` is on the left.
+fn simplify_conjuncts(conjuncts: Vec<&Expr>, nu: &Expr) -> Vec<Expr> {
+    let conjuncts: FxIndexSet<Expr> = conjuncts
+        .into_iter()
+        .filter(|e| !e.is_trivially_true())
+        .map(|e| normalize_cmp(e, nu))
+        .collect();
+    conjuncts
+        .iter()
+        .enumerate()
+        .filter(|(i, e)| !is_implied(*i, e, &conjuncts))
+        .map(|(_, e)| e)
+        .map(|e| orient_cmp(e, nu))
+        .collect()
+}
+
+/// Writes `a < b` and `a <= b` as `b > a` and `b >= a`. For integers, `a - 1 >= b` and
+/// `a >= b + 1` are written as `a > b`. Equalities and disequalities are oriented so `nu` is on
+/// the left.
+fn normalize_cmp(e: &Expr, nu: &Expr) -> Expr {
+    let ExprKind::BinaryOp(op, e1, e2) = e.kind() else { return e.clone() };
+    let (op, e1, e2) = match op {
+        BinOp::Lt(sort) => (BinOp::Gt(sort.clone()), e2, e1),
+        BinOp::Le(sort) => (BinOp::Ge(sort.clone()), e2, e1),
+        BinOp::Eq | BinOp::Ne if e2 == nu => (op.clone(), e2, e1),
+        _ => (op.clone(), e1, e2),
+    };
+    if let BinOp::Ge(rty::Sort::Int) = op {
+        if let ExprKind::BinaryOp(BinOp::Sub, a, one) = e1.kind()
+            && is_one(one)
+        {
+            return Expr::gt(a, e2);
+        }
+        if let ExprKind::BinaryOp(BinOp::Add, b, one) = e2.kind()
+            && is_one(one)
+        {
+            return Expr::gt(e1, b);
+        }
+    }
+    Expr::binary_op(op, e1, e2)
+}
+
+fn is_one(e: &Expr) -> bool {
+    matches!(e.kind(), ExprKind::Constant(c) if *c == rty::Constant::ONE)
+}
+
+/// Whether the normalized comparison at index `i` is implied by another one in `conjuncts`:
+/// `a >= b` is implied by `a > b` and `a == b`, and `a != b` by `a > b` and `b > a`. Of `a == b`
+/// and `b == a` only the first one is kept.
+fn is_implied(i: usize, e: &Expr, conjuncts: &FxIndexSet<Expr>) -> bool {
+    let ExprKind::BinaryOp(op, e1, e2) = e.kind() else { return false };
+    let index_of = |op: BinOp, e1: &Expr, e2: &Expr| {
+        conjuncts.get_index_of(&Expr::binary_op(op, e1.clone(), e2.clone()))
+    };
+    let contains = |op: BinOp, e1: &Expr, e2: &Expr| index_of(op, e1, e2).is_some();
+    match op {
+        BinOp::Eq => index_of(BinOp::Eq, e2, e1).is_some_and(|j| j < i),
+        BinOp::Ne if index_of(BinOp::Ne, e2, e1).is_some_and(|j| j < i) => true,
+        BinOp::Ge(sort) => {
+            contains(BinOp::Gt(sort.clone()), e1, e2)
+                || contains(BinOp::Eq, e1, e2)
+                || contains(BinOp::Eq, e2, e1)
+        }
+        BinOp::Ne => {
+            // The sort of the comparison is not known here, so we try the ones we can print
+            [rty::Sort::Int, rty::Sort::Real].into_iter().any(|sort| {
+                contains(BinOp::Gt(sort.clone()), e1, e2) || contains(BinOp::Gt(sort), e2, e1)
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Writes `a > b` and `a >= b` as `b < a` and `b <= a` if `b` is `nu` or `a` is a constant
+fn orient_cmp(e: &Expr, nu: &Expr) -> Expr {
+    if let ExprKind::BinaryOp(op, e1, e2) = e.kind()
+        && e1 != nu
+        && (e2 == nu || matches!(e1.kind(), ExprKind::Constant(_)))
+    {
+        match op {
+            BinOp::Gt(sort) => return Expr::binary_op(BinOp::Lt(sort.clone()), e2, e1),
+            BinOp::Ge(sort) => return Expr::binary_op(BinOp::Le(sort.clone()), e2, e1),
+            _ => {}
+        }
+    }
+    e.clone()
+}
+
+/// Prints an expression in the surface syntax. Returns `None` if the expression mentions
+/// something that cannot be written in a signature, e.g., a variable without a name.
+fn fmt_expr(genv: GlobalEnv, expr: &Expr) -> Option<String> {
+    let mut buf = String::new();
+    fmt_expr_aux(genv, expr, &mut buf)?;
+    Some(buf)
+}
+
+fn fmt_expr_aux(genv: GlobalEnv, expr: &Expr, buf: &mut String) -> Option<()> {
+    match expr.kind() {
+        ExprKind::Var(rty::Var::Bound(_, breft)) => {
+            let BoundReftKind::Named(name) = breft.kind else { return None };
+            write!(buf, "{name}").ok()?;
+        }
+        ExprKind::Var(rty::Var::EarlyParam(param)) => write!(buf, "{}", param.name).ok()?,
+        ExprKind::Constant(c) => {
+            match c {
+                rty::Constant::Int(n) => write!(buf, "{n}").ok()?,
+                rty::Constant::Bool(b) => write!(buf, "{b}").ok()?,
+                rty::Constant::Str(s) => write!(buf, "{:?}", s.as_str()).ok()?,
+                rty::Constant::Real(_) | rty::Constant::Char(_) => return None,
+            }
+        }
+        ExprKind::BinaryOp(op, e1, e2) => {
+            let op = match op {
+                BinOp::Iff => "<=>",
+                BinOp::Imp => "=>",
+                BinOp::Or => "||",
+                BinOp::And => "&&",
+                BinOp::Eq => "==",
+                BinOp::Ne => "!=",
+                BinOp::Gt(_) => ">",
+                BinOp::Ge(_) => ">=",
+                BinOp::Lt(_) => "<",
+                BinOp::Le(_) => "<=",
+                BinOp::Add => "+",
+                BinOp::Sub => "-",
+                BinOp::Mul => "*",
+                BinOp::Div => "/",
+                BinOp::Mod => "%",
+            };
+            fmt_operand(genv, e1, buf)?;
+            write!(buf, " {op} ").ok()?;
+            fmt_operand(genv, e2, buf)?;
+        }
+        ExprKind::UnaryOp(op, e) => {
+            buf.push(if let UnOp::Not = op { '!' } else { '-' });
+            fmt_operand(genv, e, buf)?;
+        }
+        ExprKind::FieldProj(e, proj) => {
+            fmt_operand(genv, e, buf)?;
+            match proj {
+                FieldProj::Tuple { field, .. } => write!(buf, ".{field}").ok()?,
+                FieldProj::Adt { def_id, field } => {
+                    let sort_def = genv.adt_sort_def_of(*def_id).ok()?;
+                    let name = sort_def.field_names().get(*field as usize)?;
+                    write!(buf, ".{name}").ok()?;
+                }
+            }
+        }
+        ExprKind::IfThenElse(p, e1, e2) => {
+            buf.push_str("if ");
+            fmt_expr_aux(genv, p, buf)?;
+            buf.push_str(" { ");
+            fmt_expr_aux(genv, e1, buf)?;
+            buf.push_str(" } else { ");
+            fmt_expr_aux(genv, e2, buf)?;
+            buf.push_str(" }");
+        }
+        ExprKind::App(func, args) => {
+            let ExprKind::GlobalFunc(name, _) = func.kind() else { return None };
+            write!(buf, "{name}(").ok()?;
+            for (i, arg) in args.iter().enumerate() {
+                if i > 0 {
+                    buf.push_str(", ");
+                }
+                fmt_expr_aux(genv, arg, buf)?;
+            }
+            buf.push(')');
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Prints an operand of an operator adding parentheses if it's not atomic
+fn fmt_operand(genv: GlobalEnv, expr: &Expr, buf: &mut String) -> Option<()> {
+    let atomic = matches!(
+        expr.kind(),
+        ExprKind::Var(_) | ExprKind::Constant(_) | ExprKind::FieldProj(..) | ExprKind::App(..)
+    );
+    if atomic {
+        fmt_expr_aux(genv, expr, buf)
+    } else {
+        buf.push('(');
+        fmt_expr_aux(genv, expr, buf)?;
+        buf.push(')');
+        Some(())
+    }
+}
+
+mod errors {
+    use flux_macros::Diagnostic;
+    use rustc_span::{Span, Symbol};
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_suggested_sig)]
+    pub(super) struct SuggestedSig {
+        #[primary_span]
+        pub span: Span,
+        pub name: Symbol,
+        #[suggestion(
+            code = "#[flux::sig({sig})]\n{indent}",
+            applicability = "maybe-incorrect",
+            style = "verbose"
+        )]
+        pub suggestion: Span,
+        pub sig: String,
+        pub indent: String,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_suggested_sig_no_solution)]
+    #[note]
+    pub(super) struct NoSolution {
+        #[primary_span]
+        pub span: Span,
+        pub name: Symbol,
+    }
+}
@@ -15,7 +15,10 @@ use flux_middle::{
     queries::QueryResult,
     rty::{
         canonicalize::{Hoister, LocalHoister},
-        fold::{FallibleTypeFolder, TypeFoldable, TypeVisitable, TypeVisitor},
+        fold::{
+            FallibleTypeFolder, TypeFoldable, TypeFolder, TypeSuperFoldable, TypeVisitable,
+            TypeVisitor,
+        },
         region_matching::{rty_match_regions, ty_match_regions},
         BaseTy, Binder, BoundReftKind, Ensures, Expr, ExprKind, FnSig, GenericArg, HoleKind,
         Lambda, List, Loc, Mutability, Path, PtrKind, Region, SortCtor, SubsetTy, Ty, TyKind,
@@ -276,6 +279,35 @@ impl<'a> TypeEnv<'a> {
         self.bindings.unblock(infcx, place);
     }
 
+    /// Forgets what we know about the locations pointed to by the mutable pointers in `tys`. This
+    /// is used when unwinding from a call, which may have written anything through them.
+    pub(crate) fn havoc_ptrs(&mut self, tys: &[Ty]) {
+        for ty in tys {
+            if let TyKind::Ptr(PtrKind::Mut(_), path) = ty.kind() {
+                let havoc = self.get(path).with_holes().replace_holes(|_, kind| {
+                    debug_assert_eq!(kind, HoleKind::Pred);
+                    Expr::tt()
+                });
+                self.update_path(path, havoc);
+            }
+        }
+    }
+
+    /// Unblocks every place in the environment. This is used when unwinding: the borrows that are
+    /// still live can only be used by drops in cleanup code, which don't need the blocked types.
+    pub(crate) fn unblock_all(&mut self, infcx: &mut InferCtxt) {
+        struct Unblocker;
+
+        impl TypeFolder for Unblocker {
+            fn fold_ty(&mut self, ty: &Ty) -> Ty {
+                ty.unblocked().super_fold_with(self)
+            }
+        }
+
+        self.bindings
+            .fmap_mut(|ty| infcx.hoister(true).hoist(&ty.fold_with(&mut Unblocker)));
+    }
+
     pub(crate) fn check_goto(
         self,
         infcx: &mut InferCtxtAt,
@@ -386,7 +418,7 @@ impl<'a> TypeEnv<'a> {
                     at.subtyping(&actual_ty, ty, reason)?;
                 }
                 Ensures::Pred(e) => {
-                    at.check_pred(e, ConstrReason::Ret);
+                    at.check_pred(e, reason);
                 }
             }
         }
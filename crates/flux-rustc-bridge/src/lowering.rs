@@ -571,6 +571,7 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
             rustc_mir::BinOp::BitXor => Ok(BinOp::BitXor),
             rustc_mir::BinOp::Shl => Ok(BinOp::Shl),
             rustc_mir::BinOp::Shr => Ok(BinOp::Shr),
+            rustc_mir::BinOp::Offset => Ok(BinOp::Offset),
             rustc_mir::BinOp::AddUnchecked
             | rustc_mir::BinOp::SubUnchecked
             | rustc_mir::BinOp::MulUnchecked
@@ -579,8 +580,7 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
             | rustc_mir::BinOp::AddWithOverflow
             | rustc_mir::BinOp::SubWithOverflow
             | rustc_mir::BinOp::MulWithOverflow
-            | rustc_mir::BinOp::Cmp
-            | rustc_mir::BinOp::Offset => {
+            | rustc_mir::BinOp::Cmp => {
                 Err(UnsupportedReason::new(format!("unsupported binary op `{bin_op:?}`")))
             }
         }
@@ -266,6 +266,7 @@ pub enum BinOp {
     BitXor,
     Shl,
     Shr,
+    Offset,
 }
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
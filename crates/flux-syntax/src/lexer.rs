@@ -38,8 +38,12 @@ pub enum Token {
     Iff,
     FatArrow,
     Mut,
+    Const,
     Where,
     Forall,
+    Exists,
+    Let,
+    Match,
     Impl,
     Requires,
     Ensures,
@@ -92,6 +96,7 @@ struct Symbols {
     hrn: Symbol,
     hdl: Symbol,
     forall: Symbol,
+    exists: Symbol,
 }
 
 struct Frame<'t> {
@@ -122,6 +127,7 @@ impl<'t> Cursor<'t> {
                 hrn: Symbol::intern("hrn"),
                 hdl: Symbol::intern("hdl"),
                 forall: Symbol::intern("forall"),
+                exists: Symbol::intern("exists"),
             },
         }
     }
@@ -165,11 +171,15 @@ impl<'t> Cursor<'t> {
             TokenKind::Ident(symb, _) if symb == self.symbs.hrn => Token::Hrn,
             TokenKind::Ident(symb, _) if symb == self.symbs.hdl => Token::Hdl,
             TokenKind::Ident(symb, _) if symb == self.symbs.forall => Token::Forall,
+            TokenKind::Ident(symb, _) if symb == self.symbs.exists => Token::Exists,
             TokenKind::Ident(symb, _) if symb == kw::Mut => Token::Mut,
+            TokenKind::Ident(symb, _) if symb == kw::Const => Token::Const,
             TokenKind::Ident(symb, _) if symb == kw::Where => Token::Where,
             TokenKind::Ident(symb, _) if symb == kw::Impl => Token::Impl,
             TokenKind::Ident(symb, _) if symb == kw::Type => Token::Type,
             TokenKind::Ident(symb, _) if symb == kw::If => Token::If,
+            TokenKind::Ident(symb, _) if symb == kw::Let => Token::Let,
+            TokenKind::Ident(symb, _) if symb == kw::Match => Token::Match,
             TokenKind::Ident(symb, _) if symb == kw::Else => Token::Else,
             TokenKind::Ident(symb, _) if symb == kw::Async => Token::Async,
             TokenKind::Ident(symb, _) if symb == kw::As => Token::As,
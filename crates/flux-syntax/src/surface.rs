@@ -25,6 +25,22 @@ impl NodeId {
 #[derive(Debug)]
 pub struct SortDecl {
     pub name: Ident,
+    pub kind: SortDeclKind,
+}
+
+#[derive(Debug)]
+pub enum SortDeclKind {
+    /// `opaque sort S;`
+    Opaque,
+    /// `sort S { A, B(int, bool) }`: a data sort with a list of variants
+    Data(Vec<SortVariant>),
+}
+
+#[derive(Debug)]
+pub struct SortVariant {
+    pub name: Ident,
+    pub fields: Vec<BaseSort>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
@@ -345,6 +361,9 @@ pub enum TyKind {
     Array(Box<Ty>, ConstArg),
     /// The `NodeId` is used to resolve the type to a corresponding `OpaqueTy`
     ImplTrait(NodeId, GenericBounds),
+    /// A function pointer type, e.g., `fn(i32{v: v > 0}) -> i32`. The signature has no name,
+    /// generics, or clauses.
+    BareFn(Box<FnSig>),
     Hole,
 }
 
@@ -361,6 +380,7 @@ impl Ty {
                     | TyKind::Ref(..)
                     | TyKind::Array(..)
                     | TyKind::ImplTrait(..)
+                    | TyKind::BareFn(..)
                     | TyKind::Hole
                     | TyKind::Base(_) => {
                         visit::walk_ty(self, ty);
@@ -388,6 +408,8 @@ pub struct BaseTy {
 pub enum BaseTyKind {
     Path(Option<Box<Ty>>, Path),
     Slice(Box<Ty>),
+    /// `*const T` or `*mut T`
+    RawPtr(Mutability, Box<Ty>),
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Copy)]
@@ -496,6 +518,27 @@ pub enum ExprKind {
     Alias(AliasReft, Vec<Expr>),
     IfThenElse(Box<[Expr; 3]>),
     Constructor(Option<ExprPath>, Vec<ConstructorArg>),
+    /// `forall x: int. p` or `exists x: int. p`. The body extends as far to the right as possible.
+    Quant(QuantKind, RefineParams, Box<Expr>),
+    /// `let x = e1; e2`. The parameter is only in scope in `e2`.
+    Let(RefineParam, Box<[Expr; 2]>),
+    /// `match e { A(x, y) => e1, _ => e2 }` on a value of a data sort
+    Match(Box<Expr>, Vec<MatchArm>),
+}
+
+#[derive(Debug)]
+pub struct MatchArm {
+    pub pat: MatchPat,
+    pub body: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug)]
+pub enum MatchPat {
+    /// A variant of a data sort binding each of its fields, e.g., `A(x, y)`
+    Ctor { ctor: Ident, params: RefineParams, node_id: NodeId },
+    /// `_`
+    Wildcard(Span),
 }
 
 /// A [`Path`] but for refinement expressions
@@ -553,6 +596,21 @@ impl fmt::Debug for BinOp {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub enum QuantKind {
+    Forall,
+    Exists,
+}
+
+impl fmt::Debug for QuantKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuantKind::Forall => write!(f, "∀"),
+            QuantKind::Exists => write!(f, "∃"),
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum UnOp {
     Not,
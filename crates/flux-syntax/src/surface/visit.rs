@@ -4,9 +4,9 @@ use super::{
     AliasReft, Async, BaseSort, BaseTy, BaseTyKind, ConstArg, ConstantInfo, ConstructorArg,
     Ensures, EnumDef, Expr, ExprKind, ExprPath, ExprPathSegment, FieldExpr, FnInput, FnOutput,
     FnRetTy, FnSig, GenericArg, GenericArgKind, GenericParam, Generics, Impl, ImplAssocReft,
-    Indices, Lit, Path, PathSegment, Qualifier, RefineArg, RefineParam, Sort, SortPath, SpecFunc,
-    StructDef, Trait, TraitAssocReft, TraitRef, Ty, TyAlias, TyKind, VariantDef, VariantRet,
-    WhereBoundPredicate,
+    Indices, Lit, MatchArm, MatchPat, Path, PathSegment, Qualifier, RefineArg, RefineParam, Sort,
+    SortDecl, SortDeclKind, SortPath, SpecFunc, StructDef, Trait, TraitAssocReft, TraitRef, Ty,
+    TyAlias, TyKind, VariantDef, VariantRet, WhereBoundPredicate,
 };
 
 #[macro_export]
@@ -30,6 +30,10 @@ pub trait Visitor: Sized {
         walk_defn(self, defn);
     }
 
+    fn visit_sort_decl(&mut self, sort_decl: &SortDecl) {
+        walk_sort_decl(self, sort_decl);
+    }
+
     fn visit_refine_param(&mut self, param: &RefineParam) {
         walk_refine_param(self, param);
     }
@@ -158,6 +162,10 @@ pub trait Visitor: Sized {
         walk_expr(self, expr);
     }
 
+    fn visit_match_arm(&mut self, arm: &MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
     fn visit_constructor_args(&mut self, expr: &ConstructorArg) {
         match expr {
             ConstructorArg::FieldExpr(field_expr) => walk_field_expr(self, field_expr),
@@ -198,6 +206,16 @@ pub fn walk_defn<V: Visitor>(vis: &mut V, defn: &SpecFunc) {
     }
 }
 
+pub fn walk_sort_decl<V: Visitor>(vis: &mut V, sort_decl: &SortDecl) {
+    vis.visit_ident(sort_decl.name);
+    if let SortDeclKind::Data(variants) = &sort_decl.kind {
+        for variant in variants {
+            vis.visit_ident(variant.name);
+            walk_list!(vis, visit_base_sort, &variant.fields);
+        }
+    }
+}
+
 pub fn walk_refine_param<V: Visitor>(vis: &mut V, param: &RefineParam) {
     vis.visit_ident(param.ident);
     vis.visit_sort(&param.sort);
@@ -449,6 +467,7 @@ pub fn walk_ty<V: Visitor>(vis: &mut V, ty: &Ty) {
         TyKind::ImplTrait(_node_id, trait_ref) => {
             walk_list!(vis, visit_trait_ref, trait_ref);
         }
+        TyKind::BareFn(fn_sig) => vis.visit_fn_sig(fn_sig),
         TyKind::Hole => {}
     }
 }
@@ -461,7 +480,7 @@ pub fn walk_bty<V: Visitor>(vis: &mut V, bty: &BaseTy) {
             }
             vis.visit_path(path);
         }
-        BaseTyKind::Slice(ty) => vis.visit_ty(ty),
+        BaseTyKind::Slice(ty) | BaseTyKind::RawPtr(_, ty) => vis.visit_ty(ty),
     }
 }
 
@@ -519,7 +538,28 @@ pub fn walk_expr<V: Visitor>(vis: &mut V, expr: &Expr) {
             }
             walk_list!(vis, visit_constructor_args, exprs);
         }
+        ExprKind::Quant(_, params, body) => {
+            walk_list!(vis, visit_refine_param, params);
+            vis.visit_expr(body);
+        }
+        ExprKind::Let(param, box [init, body]) => {
+            vis.visit_expr(init);
+            vis.visit_refine_param(param);
+            vis.visit_expr(body);
+        }
+        ExprKind::Match(scrutinee, arms) => {
+            vis.visit_expr(scrutinee);
+            walk_list!(vis, visit_match_arm, arms);
+        }
+    }
+}
+
+pub fn walk_match_arm<V: Visitor>(vis: &mut V, arm: &MatchArm) {
+    if let MatchPat::Ctor { ctor, params, .. } = &arm.pat {
+        vis.visit_ident(*ctor);
+        walk_list!(vis, visit_refine_param, params);
     }
+    vis.visit_expr(&arm.body);
 }
 
 pub fn walk_path_expr<V: Visitor>(vis: &mut V, qpath: &ExprPath) {
@@ -128,15 +128,13 @@ fn has_opaque_attr(attrs: &[syn::Attribute]) -> bool {
 }
 
 fn path_matches(path: &syn::Path, x: &[&str]) -> bool {
-    let mut i = 0;
-    for segment in &path.segments {
+    for (i, segment) in path.segments.iter().enumerate() {
         if i == x.len() {
             return false;
         }
         if segment.ident != x[i] {
             return false;
         }
-        i += 1;
     }
     true
 }
@@ -224,7 +222,9 @@ impl Parse for ExternItem {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let attrs = input.call(Attribute::parse_outer)?;
         let lookahead = input.lookahead1();
-        let mut item = if lookahead.peek(Token![fn]) {
+        let mut item = if lookahead.peek(Token![fn])
+            || (input.peek(Token![unsafe]) && input.peek2(Token![fn]))
+        {
             ExternItem::Fn(input.parse()?)
         } else if lookahead.peek(Token![impl]) {
             ExternItem::Impl(input.parse()?)
@@ -331,7 +331,7 @@ impl ToTokens for ExternItemTrait {
             for item in &self.items {
                 item.to_tokens(tokens);
             }
-        })
+        });
     }
 }
 
@@ -529,6 +529,15 @@ fn create_dummy_ident(dummy_prefix: &mut String, ty: &syn::Type) -> syn::Result<
             dummy_prefix.push_str("Slice");
             create_dummy_ident(dummy_prefix, ty_slice.elem.as_ref())
         }
+        Ptr(ty_ptr) => {
+            if ty_ptr.mutability.is_some() {
+                dummy_prefix.push_str("Mut");
+            } else {
+                dummy_prefix.push_str("Const");
+            }
+            dummy_prefix.push_str("Ptr");
+            create_dummy_ident(dummy_prefix, ty_ptr.elem.as_ref())
+        }
         Path(ty_path) => create_dummy_ident_from_path(dummy_prefix, &ty_path.path),
         _ => {
             Err(syn::Error::new(
@@ -663,6 +672,6 @@ struct UseWildcard(syn::Path);
 impl ToTokens for UseWildcard {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let path = &self.0;
-        tokens.extend(quote!(use #path::*;))
+        tokens.extend(quote!(use #path::*;));
     }
 }
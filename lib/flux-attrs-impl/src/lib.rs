@@ -18,6 +18,7 @@ pub const FLUX_ATTRS: &[&str] = &[
     "variant",
     "should_fail",
     "opts",
+    "reflect",
 ];
 
 pub fn extern_spec(attr: TokenStream, tokens: TokenStream) -> TokenStream {
@@ -111,7 +112,6 @@ fn transform_flux_attr(attr: &mut syn::Attribute) {
         if ident == "flux" || ident == "flux_rs" {
             *ident = Ident::new("flux_tool", ident.span());
         }
-        return;
     } else if path_is_one_of(path, FLUX_ATTRS) {
         *path = parse_quote!(flux_tool::#path);
     }
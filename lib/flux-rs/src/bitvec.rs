@@ -1,3 +1,9 @@
+#![allow(
+    clippy::derived_hash_with_manual_eq,
+    clippy::partialeq_ne_impl,
+    clippy::suspicious_arithmetic_impl
+)]
+
 use core::{
     cmp::Ordering,
     ops::{Add, BitAnd, BitOr, Not, Rem, Shl, Shr, Sub},
@@ -5,158 +11,232 @@ use core::{
 
 use flux_attrs::*;
 
-#[derive(Debug, Clone, Copy, Hash)]
-#[opaque]
-#[refined_by(x: bitvec<32>)]
-pub struct BV32(u32);
-
-#[trusted]
-impl PartialOrd for BV32 {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
-    }
-
-    #[sig(fn(&BV32[@x], &BV32[@y]) -> bool[bv_ule(x, y)])]
-    fn le(&self, other: &Self) -> bool {
-        self.0 <= other.0
-    }
-
-    #[sig(fn(&BV32[@x], &BV32[@y]) -> bool[bv_ult(x, y)])]
-    fn lt(&self, other: &Self) -> bool {
-        self.0 < other.0
-    }
-
-    #[sig(fn(&BV32[@x], &BV32[@y]) -> bool[bv_uge(x, y)])]
-    fn ge(&self, other: &Self) -> bool {
-        self.0 >= other.0
-    }
-
-    #[sig(fn(&BV32[@x], &BV32[@y]) -> bool[bv_ugt(x, y)])]
-    fn gt(&self, other: &Self) -> bool {
-        self.0 > other.0
-    }
-}
-
-#[trusted]
-impl BV32 {
-    #[sig(fn(BV32[@x], BV32[@y]) -> BV32[bv_add(x, y)])]
-    pub fn wrapping_add(self, other: BV32) -> BV32 {
-        BV32(self.0.wrapping_add(other.0))
-    }
-
-    #[sig(fn (u32[@val]) -> BV32[bv_int_to_bv32(val)])]
-    pub const fn new(value: u32) -> BV32 {
-        BV32(value)
-    }
-}
-
-impl From<u32> for BV32 {
-    #[trusted]
-    #[sig(fn(u32[@val]) -> BV32[bv_int_to_bv32(val)])]
-    fn from(value: u32) -> BV32 {
-        BV32(value)
-    }
-}
-
-impl Into<u32> for BV32 {
-    #[trusted]
-    #[sig(fn(BV32[@val]) -> u32[bv_bv32_to_int(val)])]
-    fn into(self) -> u32 {
-        self.0
-    }
+/// Defines a wrapper around an unsigned integer of the given size that is refined by a bit-vector.
+/// Arithmetic operations wrap around like the corresponding bit-vector operations.
+///
+/// The arguments are matched as `ident` and `tt` rather than `ty` and `literal` so they are pasted
+/// as plain tokens into the flux attributes.
+macro_rules! bitvec {
+    ($name:ident, $int:ident, $bits:tt) => {
+        #[derive(Debug, Clone, Copy, Hash)]
+        #[opaque]
+        #[refined_by(x: bitvec<$bits>)]
+        pub struct $name($int);
+
+        #[trusted]
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+
+            #[sig(fn(&$name[@x], &$name[@y]) -> bool[bv_ule(x, y)])]
+            fn le(&self, other: &Self) -> bool {
+                self.0 <= other.0
+            }
+
+            #[sig(fn(&$name[@x], &$name[@y]) -> bool[bv_ult(x, y)])]
+            fn lt(&self, other: &Self) -> bool {
+                self.0 < other.0
+            }
+
+            #[sig(fn(&$name[@x], &$name[@y]) -> bool[bv_uge(x, y)])]
+            fn ge(&self, other: &Self) -> bool {
+                self.0 >= other.0
+            }
+
+            #[sig(fn(&$name[@x], &$name[@y]) -> bool[bv_ugt(x, y)])]
+            fn gt(&self, other: &Self) -> bool {
+                self.0 > other.0
+            }
+        }
+
+        #[trusted]
+        impl $name {
+            #[sig(fn($name[@x], $name[@y]) -> $name[bv_add(x, y)])]
+            pub fn wrapping_add(self, other: $name) -> $name {
+                $name(self.0.wrapping_add(other.0))
+            }
+
+            #[sig(fn($int[@val]) -> $name[bv_int_to_bv(val)])]
+            pub const fn new(value: $int) -> $name {
+                $name(value)
+            }
+        }
+
+        impl From<$int> for $name {
+            #[trusted]
+            #[sig(fn($int[@val]) -> $name[bv_int_to_bv(val)])]
+            fn from(value: $int) -> $name {
+                $name(value)
+            }
+        }
+
+        impl From<$name> for $int {
+            #[trusted]
+            #[sig(fn($name[@val]) -> $int[bv_bv_to_int(val)])]
+            fn from(val: $name) -> Self {
+                val.0
+            }
+        }
+
+        impl Not for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@x]) -> $name[bv_not(x)])]
+            fn not(self) -> $name {
+                $name(!self.0)
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@x], $name[@y]) -> $name[bv_and(x, y)])]
+            fn bitand(self, rhs: Self) -> $name {
+                $name(self.0 & rhs.0)
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@x], $name[@y]) -> $name[bv_or(x, y)])]
+            fn bitor(self, rhs: Self) -> $name {
+                $name(self.0 | rhs.0)
+            }
+        }
+
+        impl Shl for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@x], $name[@y]) -> $name[bv_shl(x, y)])]
+            fn shl(self, rhs: Self) -> $name {
+                $name(
+                    u32::try_from(rhs.0)
+                        .ok()
+                        .and_then(|n| self.0.checked_shl(n))
+                        .unwrap_or(0),
+                )
+            }
+        }
+
+        impl Shr for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@x], $name[@y]) -> $name[bv_lshr(x, y)])]
+            fn shr(self, rhs: Self) -> $name {
+                $name(
+                    u32::try_from(rhs.0)
+                        .ok()
+                        .and_then(|n| self.0.checked_shr(n))
+                        .unwrap_or(0),
+                )
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@val1], $name[@val2]) -> $name[bv_add(val1, val2)])]
+            fn add(self, rhs: Self) -> $name {
+                $name(self.0.wrapping_add(rhs.0))
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@val1], $name[@val2]) -> $name[bv_sub(val1, val2)])]
+            fn sub(self, rhs: Self) -> $name {
+                $name(self.0.wrapping_sub(rhs.0))
+            }
+        }
+
+        impl Rem for $name {
+            type Output = $name;
+
+            #[trusted]
+            #[sig(fn($name[@val1], $name[@val2]) -> $name[bv_urem(val1, val2)])]
+            fn rem(self, rhs: Self) -> $name {
+                $name(self.0.checked_rem(rhs.0).unwrap_or(self.0))
+            }
+        }
+
+        #[trusted]
+        impl PartialEq for $name {
+            #[sig(fn(&$name[@val1], &$name[@val2]) -> bool[val1 == val2])]
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+
+            #[sig(fn(&$name[@val1], &$name[@val2]) -> bool[val1 != val2])]
+            fn ne(&self, other: &Self) -> bool {
+                self.0 != other.0
+            }
+        }
+
+        impl Eq for $name {}
+    };
 }
 
-impl Not for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@x]) -> BV32[bv_not(x)])]
-    fn not(self) -> BV32 {
-        BV32(!self.0)
-    }
-}
-
-impl BitAnd for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@x], BV32[@y]) -> BV32[bv_and(x, y)])]
-    fn bitand(self, rhs: Self) -> BV32 {
-        BV32(self.0 & rhs.0)
-    }
-}
-
-impl BitOr for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@x], BV32[@y]) -> BV32[bv_or(x, y)])]
-    fn bitor(self, rhs: Self) -> BV32 {
-        BV32(self.0 | rhs.0)
-    }
-}
-
-impl Shl for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@x], BV32[@y]) -> BV32[bv_shl(x, y)])]
-    fn shl(self, rhs: Self) -> BV32 {
-        BV32(self.0 << rhs.0)
-    }
-}
-
-impl Shr for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@x], BV32[@y]) -> BV32[bv_lshr(x, y)])]
-    fn shr(self, rhs: Self) -> BV32 {
-        BV32(self.0 >> rhs.0)
-    }
-}
-
-impl Add for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@val1], BV32[@val2]) -> BV32[bv_add(val1, val2)])]
-    fn add(self, rhs: Self) -> BV32 {
-        BV32(self.0 + rhs.0)
-    }
-}
-
-impl Sub for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@val1], BV32[@val2]) -> BV32[bv_sub(val1, val2)])]
-    fn sub(self, rhs: Self) -> BV32 {
-        BV32(self.0.wrapping_add(!rhs.0))
-    }
-}
-
-impl Rem for BV32 {
-    type Output = BV32;
-
-    #[trusted]
-    #[sig(fn(BV32[@val1], BV32[@val2]) -> BV32[bv_urem(val1, val2)])]
-    fn rem(self, rhs: Self) -> BV32 {
-        BV32(self.0 & rhs.0)
-    }
+/// Defines lossless conversions from a bit-vector to wider ones by zero extension.
+macro_rules! bitvec_widen {
+    ($from:ident => $($to:ident),+) => {
+        $(
+            impl From<$from> for $to {
+                #[trusted]
+                #[sig(fn($from[@x]) -> $to[bv_zero_extend(x)])]
+                fn from(x: $from) -> $to {
+                    $to(x.0.into())
+                }
+            }
+        )+
+    };
 }
 
-#[trusted]
-impl PartialEq for BV32 {
-    #[sig(fn(&BV32[@val1], &BV32[@val2]) -> bool[val1 == val2])]
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
-    }
-
-    #[sig(fn(&BV32[@val1], &BV32[@val2]) -> bool[val1 != val2])]
-    fn ne(&self, other: &Self) -> bool {
-        self.0 != other.0
-    }
+/// Defines operations to split a bit-vector into two halves and to join them back.
+macro_rules! bitvec_halves {
+    ($name:ident, $int:ident, $half:ident, $half_int:ident, $half_bits:tt) => {
+        #[trusted]
+        impl $name {
+            /// Joins two halves, with `hi` as the most significant bits.
+            #[sig(fn($half[@hi], $half[@lo]) -> $name[bv_concat(hi, lo)])]
+            pub fn from_halves(hi: $half, lo: $half) -> $name {
+                $name(((hi.0 as $int) << $half_bits) | lo.0 as $int)
+            }
+
+            #[sig(fn($name[@x]) -> $half[bv_extract(bv_lshr(x, bv_int_to_bv($half_bits)))])]
+            pub fn high(self) -> $half {
+                $half((self.0 >> $half_bits) as $half_int)
+            }
+
+            #[sig(fn($name[@x]) -> $half[bv_extract(x)])]
+            pub fn low(self) -> $half {
+                $half(self.0 as $half_int)
+            }
+        }
+    };
 }
 
-impl Eq for BV32 {}
+bitvec!(BV8, u8, 8);
+bitvec!(BV16, u16, 16);
+bitvec!(BV32, u32, 32);
+bitvec!(BV64, u64, 64);
+bitvec!(BV128, u128, 128);
+
+bitvec_widen!(BV8 => BV16, BV32, BV64, BV128);
+bitvec_widen!(BV16 => BV32, BV64, BV128);
+bitvec_widen!(BV32 => BV64, BV128);
+bitvec_widen!(BV64 => BV128);
+
+bitvec_halves!(BV16, u16, BV8, u8, 8);
+bitvec_halves!(BV32, u32, BV16, u16, 16);
+bitvec_halves!(BV64, u64, BV32, u32, 32);
+bitvec_halves!(BV128, u128, BV64, u64, 64);
@@ -1,4 +1,5 @@
 pub mod bitvec;
+mod ptr;
 
 pub use flux_attrs::*;
 
@@ -0,0 +1,64 @@
+//! Extern specs for raw pointers. A raw pointer is indexed by its allocation, its offset and the
+//! length of the allocation, both counted in elements. Moving a pointer must keep it within its
+//! allocation or one element past the end, and reading or writing requires it to point to an
+//! element of the allocation.
+//!
+//! These specs are in effect in every crate that uses `flux_rs`. Pointers without refinements,
+//! e.g., obtained by casting an integer, cannot be moved with these methods.
+
+use flux_attrs::*;
+
+#[extern_spec(std::ptr)]
+#[sig(fn() -> *const T[0, 0, 0])]
+fn null<T>() -> *const T;
+
+#[extern_spec(std::ptr)]
+#[sig(fn() -> *mut T[0, 0, 0])]
+fn null_mut<T>() -> *mut T;
+
+#[extern_spec(std::ptr)]
+#[sig(fn(src: *const T[@b, @o, @n]) -> T requires 0 <= o && o < n)]
+unsafe fn read<T>(src: *const T) -> T;
+
+#[extern_spec(std::ptr)]
+#[sig(fn(dst: *mut T[@b, @o, @n], src: T) requires 0 <= o && o < n)]
+unsafe fn write<T>(dst: *mut T, src: T);
+
+#[extern_spec]
+impl<T> *const T {
+    #[sig(fn(*const T[@b, @o, @n], count: usize) -> *const T[b, o + count, n]
+          requires 0 <= o + count && o + count <= n)]
+    unsafe fn add(self, count: usize) -> *const T;
+
+    #[sig(fn(*const T[@b, @o, @n], count: usize) -> *const T[b, o - count, n]
+          requires 0 <= o - count && o - count <= n)]
+    unsafe fn sub(self, count: usize) -> *const T;
+
+    #[sig(fn(*const T[@b, @o, @n], count: isize) -> *const T[b, o + count, n]
+          requires 0 <= o + count && o + count <= n)]
+    unsafe fn offset(self, count: isize) -> *const T;
+}
+
+#[extern_spec]
+impl<T> *mut T {
+    #[sig(fn(*mut T[@b, @o, @n], count: usize) -> *mut T[b, o + count, n]
+          requires 0 <= o + count && o + count <= n)]
+    unsafe fn add(self, count: usize) -> *mut T;
+
+    #[sig(fn(*mut T[@b, @o, @n], count: usize) -> *mut T[b, o - count, n]
+          requires 0 <= o - count && o - count <= n)]
+    unsafe fn sub(self, count: usize) -> *mut T;
+
+    #[sig(fn(*mut T[@b, @o, @n], count: isize) -> *mut T[b, o + count, n]
+          requires 0 <= o + count && o + count <= n)]
+    unsafe fn offset(self, count: isize) -> *mut T;
+}
+
+#[extern_spec]
+impl<T> [T] {
+    #[sig(fn(&[T][@n]) -> {b. *const T[b, 0, n]})]
+    fn as_ptr(&self) -> *const T;
+
+    #[sig(fn(&mut [T][@n]) -> {b. *mut T[b, 0, n]})]
+    fn as_mut_ptr(&mut self) -> *mut T;
+}
@@ -200,6 +200,7 @@ impl<T: Types> Sort<T> {
 pub enum SortCtor<T: Types> {
     Set,
     Map,
+    Seq,
     Data(T::Sort),
 }
 
@@ -258,6 +259,13 @@ pub enum Expr<T: Types> {
     Imp(Box<[Expr<T>; 2]>),
     Iff(Box<[Expr<T>; 2]>),
     Atom(BinRel, Box<[Self; 2]>),
+    Quant(Quantifier, Vec<(T::Var, Sort<T>)>, Box<Self>),
+}
+
+#[derive(Hash, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Quantifier {
+    Forall,
+    Exists,
 }
 
 impl<T: Types> Expr<T> {
@@ -4,8 +4,8 @@ use itertools::Itertools;
 
 use crate::{
     constraint::DEFAULT_QUALIFIERS, BinOp, BinRel, ConstDecl, Constant, Constraint, DataCtor,
-    DataDecl, DataField, Expr, FixpointFmt, Identifier, KVarDecl, Pred, Qualifier, Sort, SortCtor,
-    Task, Types,
+    DataDecl, DataField, Expr, FixpointFmt, FunDef, Identifier, KVarDecl, Pred, Qualifier,
+    Quantifier, Sort, SortCtor, Task, Types,
 };
 
 pub(crate) fn fmt_constraint<T: Types>(
@@ -26,6 +26,10 @@ impl<T: Types> fmt::Display for Task<T> {
         if self.scrape_quals {
             writeln!(f, "(fixpoint \"--scrape=both\")")?;
         }
+        if !self.define_funs.is_empty() {
+            // Definitions are only unfolded when rewriting is enabled
+            writeln!(f, "(fixpoint \"--rewrite\")")?;
+        }
         for line in &self.comments {
             writeln!(f, ";; {line}")?;
         }
@@ -47,6 +51,10 @@ impl<T: Types> fmt::Display for Task<T> {
             writeln!(f, "{cinfo}")?;
         }
 
+        for fun_def in &self.define_funs {
+            writeln!(f, "{fun_def}")?;
+        }
+
         for kvar in &self.kvars {
             writeln!(f, "{kvar}")?;
         }
@@ -78,6 +86,21 @@ impl<T: Types> fmt::Display for ConstDecl<T> {
     }
 }
 
+impl<T: Types> fmt::Display for FunDef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "(define {} ({}) {} ({}))",
+            self.name.display(),
+            self.args.iter().format_with(" ", |(name, sort), f| {
+                f(&format_args!("({} {sort})", name.display()))
+            }),
+            self.out,
+            self.body
+        )
+    }
+}
+
 impl<T: Types> fmt::Debug for Task<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(self, f)
@@ -209,6 +232,7 @@ impl<T: Types> fmt::Display for SortCtor<T> {
         match self {
             SortCtor::Set => write!(f, "Set_Set"),
             SortCtor::Map => write!(f, "Map_t"),
+            SortCtor::Seq => write!(f, "Seq_t"),
             SortCtor::Data(name) => write!(f, "{}", name.display()),
         }
     }
@@ -314,6 +338,24 @@ impl<T: Types> fmt::Display for Expr<T> {
                 let [e1, e2] = &**exprs;
                 write!(f, "({rel} {e1} {e2})")
             }
+            Expr::Quant(quant, vars, body) => {
+                write!(
+                    f,
+                    "({quant} ({}) {body})",
+                    vars.iter().format_with(" ", |(name, sort), f| {
+                        f(&format_args!("({} {sort})", name.display()))
+                    })
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for Quantifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Quantifier::Forall => write!(f, "forall"),
+            Quantifier::Exists => write!(f, "exists"),
         }
     }
 }
@@ -1,12 +1,18 @@
-//! This crate implements an interface to the [liquid-fixpoint] binary
+//! This crate implements an interface to the [liquid-fixpoint] binary and a native solver for
+//! fixpoint tasks that only requires an SMT solver.
 //!
 //! [liquid-fixpoint]: https://github.com/ucsd-progsys/liquid-fixpoint
 
 mod constraint;
 mod format;
+mod limits;
+mod native;
+mod sexp;
+pub mod shrink;
+mod smt;
+mod solution;
 
 use std::{
-    collections::hash_map::DefaultHasher,
     fmt,
     hash::{Hash, Hasher},
     io::{self, BufWriter, Write as IOWrite},
@@ -16,10 +22,13 @@ use std::{
 
 pub use constraint::{
     BinOp, BinRel, Bind, Constant, Constraint, DataCtor, DataDecl, DataField, Expr, Pred,
-    Qualifier, Sort, SortCtor,
+    Qualifier, Quantifier, Sort, SortCtor,
 };
 use derive_where::derive_where;
+pub use limits::ResourceLimits;
 use serde::{de, Deserialize, Serialize};
+use siphasher::sip::SipHasher13;
+pub use solution::{FromSolution, KVarBind, KVarSolution};
 
 pub trait Types {
     type Sort: Identifier + Hash + Clone;
@@ -116,6 +125,7 @@ macro_rules! declare_types {
             pub type Constraint = $crate::Constraint<FixpointTypes>;
             pub type KVarDecl = $crate::KVarDecl<FixpointTypes>;
             pub type ConstDecl = $crate::ConstDecl<FixpointTypes>;
+            pub type FunDef = $crate::FunDef<FixpointTypes>;
             pub type Task = $crate::Task<FixpointTypes>;
             pub type Qualifier = $crate::Qualifier<FixpointTypes>;
             pub type Sort = $crate::Sort<FixpointTypes>;
@@ -125,7 +135,7 @@ macro_rules! declare_types {
             pub type DataField = $crate::DataField<FixpointTypes>;
             pub type Bind = $crate::Bind<FixpointTypes>;
             pub type Constant = $crate::Constant<FixpointTypes>;
-            pub use $crate::{BinOp, BinRel};
+            pub use $crate::{BinOp, BinRel, Quantifier};
         }
 
         impl $crate::Types for fixpoint_generated::FixpointTypes {
@@ -150,17 +160,31 @@ pub struct ConstDecl<T: Types> {
     pub comment: Option<String>,
 }
 
+/// A (possibly recursive) function defined by an equation. The function must also be declared
+/// with a [`ConstDecl`].
+#[derive_where(Hash)]
+pub struct FunDef<T: Types> {
+    pub name: T::Var,
+    pub args: Vec<(T::Var, Sort<T>)>,
+    pub out: Sort<T>,
+    pub body: Expr<T>,
+}
+
 #[derive_where(Hash)]
 pub struct Task<T: Types> {
     #[derive_where(skip)]
     pub comments: Vec<String>,
     pub constants: Vec<ConstDecl<T>>,
+    pub define_funs: Vec<FunDef<T>>,
     pub data_decls: Vec<DataDecl<T>>,
     pub kvars: Vec<KVarDecl<T>>,
     pub constraint: Constraint<T>,
     pub qualifiers: Vec<Qualifier<T>>,
     pub scrape_quals: bool,
     pub solver: SmtSolver,
+    /// Limits don't change the meaning of the task so they are not hashed
+    #[derive_where(skip)]
+    pub limits: ResourceLimits,
 }
 
 #[derive(Clone, Copy, Hash)]
@@ -178,13 +202,51 @@ impl fmt::Display for SmtSolver {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(bound(deserialize = "Tag: FromStr", serialize = "Tag: ToString"))]
+pub struct FixpointResult<Tag> {
+    #[serde(flatten)]
+    pub status: FixpointStatus<Tag>,
+    /// The solution found for each kvar or empty if the solver didn't report a solution.
+    #[serde(default)]
+    pub solution: Vec<KVarBind>,
+}
+
+/// The result as printed by the `fixpoint` binary. When asked for the solution, the status is
+/// printed next to the solution of the kvars that were not eliminated (`solution`) and the ones
+/// that were (`nonCutsSolution`). Otherwise, only the status is printed.
+#[derive(Deserialize)]
+#[serde(untagged, bound(deserialize = "Tag: FromStr"))]
+enum FixpointOutput<Tag> {
+    WithSolution {
+        status: FixpointStatus<Tag>,
+        #[serde(default)]
+        solution: Vec<KVarBind>,
+        #[serde(default, rename = "nonCutsSolution")]
+        non_cuts_solution: Vec<KVarBind>,
+    },
+    Status(FixpointStatus<Tag>),
+}
+
+impl<Tag> From<FixpointOutput<Tag>> for FixpointResult<Tag> {
+    fn from(output: FixpointOutput<Tag>) -> Self {
+        match output {
+            FixpointOutput::WithSolution { status, mut solution, non_cuts_solution } => {
+                solution.extend(non_cuts_solution);
+                FixpointResult { status, solution }
+            }
+            FixpointOutput::Status(status) => FixpointResult { status, solution: vec![] },
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(
     tag = "tag",
     content = "contents",
     bound(deserialize = "Tag: FromStr", serialize = "Tag: ToString")
 )]
-pub enum FixpointResult<Tag> {
+pub enum FixpointStatus<Tag> {
     Safe(Stats),
     Unsafe(Stats, Vec<Error<Tag>>),
     Crash(CrashInfo),
@@ -194,8 +256,18 @@ pub enum FixpointResult<Tag> {
 pub struct Error<Tag> {
     pub id: i32,
     pub tag: Tag,
+    /// A counterexample for the failing clause. This is only produced by the [native] backend
+    /// and it is empty otherwise.
+    ///
+    /// [native]: Task::run_native
+    pub model: Model,
 }
 
+/// Values assigned by the SMT solver to the variables in scope of a failing clause. Variables are
+/// identified by their name as printed in the constraint and values are SMT-LIB2 terms.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Model(pub Vec<(String, String)>);
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Stats {
@@ -217,15 +289,90 @@ pub struct KVarDecl<T: Types> {
 }
 
 impl<T: Types> Task<T> {
-    pub fn hash_with_default(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
-        hasher.finish()
+    /// Returns a hash of the textual representation of the task. See
+    /// [`RenderedTask::stable_hash`].
+    pub fn stable_hash(&self, backend: &str) -> u64 {
+        self.render().stable_hash(backend)
     }
 
+    /// Runs the `fixpoint` binary on the task. See [`RenderedTask::run`].
     pub fn run(&self) -> io::Result<FixpointResult<T::Tag>> {
-        let mut child = Command::new("fixpoint")
-            .arg("-q")
+        self.render().run()
+    }
+
+    /// Renders the task in the format expected by the `fixpoint` binary. The rendered task does
+    /// not borrow from `self` so it can be solved in a different thread.
+    pub fn render(&self) -> RenderedTask {
+        RenderedTask::new(self.to_string(), self.solver, self.limits)
+    }
+
+    /// Solves the task in-process using the [native] backend instead of the external `fixpoint`
+    /// binary. Only the SMT solver is run as a separate process.
+    ///
+    /// Errors are reported as a [`FixpointStatus::Crash`] except when a [resource limit] is
+    /// exceeded.
+    ///
+    /// [native]: crate::native
+    /// [resource limit]: ResourceLimits
+    pub fn run_native(&self) -> io::Result<FixpointResult<T::Tag>> {
+        native::solve(self)
+    }
+}
+
+/// A [`Task`] rendered as text, ready to be sent to the `fixpoint` binary.
+pub struct RenderedTask {
+    text: String,
+    solver: SmtSolver,
+    limits: ResourceLimits,
+    /// Whether the `fixpoint` binary is asked to report the solution of the kvars
+    solution: bool,
+}
+
+impl RenderedTask {
+    /// Creates a task from its text, e.g., a task previously dumped with `FLUX_DUMP_CONSTRAINT`.
+    pub fn new(text: String, solver: SmtSolver, limits: ResourceLimits) -> Self {
+        RenderedTask { text, solver, limits, solution: false }
+    }
+
+    /// Asks the `fixpoint` binary to report the solution it found for the kvars in
+    /// [`FixpointResult::solution`]. This is off by default because printing the solution can be
+    /// expensive for large tasks.
+    pub fn with_solution(mut self) -> Self {
+        self.solution = true;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns a hash of the task's text and solver, and the name of the `backend` solving it.
+    /// Unlike the derived [`Hash`] implementation for [`Task`], which depends on how identifiers
+    /// are represented in memory, this only depends on the content of the task. The bytes are fed
+    /// directly to SipHash-1-3 with fixed keys, so the hash is also stable across toolchain
+    /// versions and can be used to address a persistent cache.
+    ///
+    /// The backend is part of the hash because results are not interchangeable between backends,
+    /// e.g., only the [native] backend reports the solution of kvars and models for failing
+    /// constraints.
+    ///
+    /// [native]: crate::native
+    pub fn stable_hash(&self, backend: &str) -> u64 {
+        let mut hasher = SipHasher13::new_with_keys(0, 0);
+        hasher.write(self.text.as_bytes());
+        hasher.write(self.solver.to_string().as_bytes());
+        hasher.write(backend.as_bytes());
+        hasher.write(&[self.solution as u8]);
+        hasher.finish()
+    }
+
+    /// Runs the `fixpoint` binary on the task. If a [resource limit] is exceeded, this returns an
+    /// error of kind [`io::ErrorKind::TimedOut`] or [`io::ErrorKind::OutOfMemory`].
+    ///
+    /// [resource limit]: ResourceLimits
+    pub fn run<Tag: FromStr>(&self) -> io::Result<FixpointResult<Tag>> {
+        let mut cmd = Command::new("fixpoint");
+        cmd.arg("-q")
             .arg("--stdin")
             .arg("--json")
             .arg("--allowho")
@@ -233,19 +380,30 @@ impl<T: Types> Task<T> {
             .arg(format!("--solver={}", self.solver))
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .spawn()?;
+            .stderr(Stdio::null());
+        if self.solution {
+            cmd.arg("--solution");
+        }
+        self.limits.apply(&mut cmd);
+        let deadline = self.limits.deadline();
+        let mut child = cmd.spawn()?;
         let mut stdin = None;
         std::mem::swap(&mut stdin, &mut child.stdin);
         {
             let mut w = BufWriter::new(stdin.unwrap());
-            writeln!(w, "{self}")?;
+            writeln!(w, "{}", self.text)?;
+        }
+        let (status, stdout) = limits::wait_with_deadline(child, deadline)?;
+
+        match serde_json::from_slice::<FixpointOutput<Tag>>(&stdout) {
+            Ok(output) => Ok(output.into()),
+            // There's no reliable way to know the process ran out of memory, but if it failed
+            // without producing a result under a memory limit, that's the most likely reason.
+            Err(_) if !status.success() && self.limits.memory.is_some() => {
+                Err(limits::out_of_memory())
+            }
+            Err(err) => Err(err.into()),
         }
-        let out = child.wait_with_output()?;
-
-        let result = serde_json::from_slice(&out.stdout)?;
-
-        Ok(result)
     }
 }
 
@@ -256,14 +414,14 @@ impl<T: Types> KVarDecl<T> {
 }
 
 #[derive(Serialize, Deserialize)]
-struct ErrorInner(i32, String);
+struct ErrorInner(i32, String, #[serde(default)] Model);
 
 impl<Tag: ToString> Serialize for Error<Tag> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        ErrorInner(self.id, self.tag.to_string()).serialize(serializer)
+        ErrorInner(self.id, self.tag.to_string(), self.model.clone()).serialize(serializer)
     }
 }
 
@@ -272,10 +430,50 @@ impl<'de, Tag: FromStr> Deserialize<'de> for Error<Tag> {
     where
         D: serde::Deserializer<'de>,
     {
-        let ErrorInner(id, tag) = Deserialize::deserialize(deserializer)?;
+        let ErrorInner(id, tag, model) = Deserialize::deserialize(deserializer)?;
         let tag = tag
             .parse()
             .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&tag), &"valid tag"))?;
-        Ok(Error { id, tag })
+        Ok(Error { id, tag, model })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_output(json: &str) -> FixpointResult<String> {
+        serde_json::from_str::<FixpointOutput<String>>(json)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn fixpoint_output_without_solution() {
+        let result = parse_output(
+            r#"{"tag":"Unsafe","contents":[{"numCstr":2,"numIter":1,"numChck":2,"numVald":1},[[1,"3"]]]}"#,
+        );
+        let FixpointStatus::Unsafe(stats, errors) = result.status else { panic!() };
+        assert_eq!(stats.num_cstr, 2);
+        assert_eq!(errors[0].tag, "3");
+        assert!(result.solution.is_empty());
+    }
+
+    #[test]
+    fn fixpoint_output_with_solution() {
+        let result = parse_output(
+            r#"{
+                "status": {"tag":"Safe","contents":{"numCstr":1,"numIter":1,"numChck":1,"numVald":1}},
+                "solution": [{"kvar":"k0","val":"true"}],
+                "nonCutsSolution": [{"kvar":"k1","val":"(x > 0)"}]
+            }"#,
+        );
+        assert!(matches!(result.status, FixpointStatus::Safe(_)));
+        let kvars = result
+            .solution
+            .iter()
+            .map(|bind| &bind.kvar[..])
+            .collect::<Vec<_>>();
+        assert_eq!(kvars, ["k0", "k1"]);
     }
 }
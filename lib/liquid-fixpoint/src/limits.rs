@@ -0,0 +1,129 @@
+//! Limits on the time and memory spent solving a task.
+//!
+//! The timeout is enforced by killing the solver process once the deadline passes. The memory
+//! limit is enforced by the operating system by limiting the address space of the solver process
+//! (and any process it spawns), so it's only available on unix platforms. In both cases, running
+//! the task fails with an [`io::Error`] of kind [`io::ErrorKind::TimedOut`] or
+//! [`io::ErrorKind::OutOfMemory`] respectively.
+
+use std::{
+    io::{self, Read},
+    process::{Child, Command, ExitStatus},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Limits on the resources used to solve a task. `None` means no limit.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ResourceLimits {
+    pub timeout: Option<Duration>,
+    /// Maximum memory in bytes
+    pub memory: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    /// Configures `cmd` such that the spawned process is subject to the memory limit. The process
+    /// is also put in its own process group, so it can be killed together with its children.
+    pub(crate) fn apply(&self, cmd: &mut Command) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+
+            let memory = self.memory;
+            let kill_group = self.timeout.is_some();
+            // SAFETY: the closure only calls async-signal-safe functions
+            unsafe {
+                cmd.pre_exec(move || {
+                    if kill_group && libc::setpgid(0, 0) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                    if let Some(memory) = memory {
+                        let limit = libc::rlimit {
+                            rlim_cur: memory as libc::rlim_t,
+                            rlim_max: memory as libc::rlim_t,
+                        };
+                        if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                            return Err(io::Error::last_os_error());
+                        }
+                    }
+                    Ok(())
+                });
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = cmd;
+    }
+}
+
+pub(crate) fn timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "solver exceeded the time limit")
+}
+
+pub(crate) fn out_of_memory() -> io::Error {
+    io::Error::new(io::ErrorKind::OutOfMemory, "solver exceeded the memory limit")
+}
+
+/// Waits for `child` to exit and collects its standard output. The child (and every process in
+/// its group) is killed if it doesn't exit before `deadline`. The child's standard input must be
+/// closed before calling this function.
+pub(crate) fn wait_with_deadline(
+    mut child: Child,
+    deadline: Option<Instant>,
+) -> io::Result<(ExitStatus, Vec<u8>)> {
+    let Some(deadline) = deadline else {
+        let out = child.wait_with_output()?;
+        return Ok((out.status, out.stdout));
+    };
+
+    // Read the output in a separate thread, otherwise the child could block writing to a full
+    // pipe while we wait for it to exit. The pipe is closed when the child exits.
+    let mut stdout = child.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let reader = thread::spawn(move || {
+        let mut buf = vec![];
+        let _ = sender.send(stdout.read_to_end(&mut buf).map(|_| buf));
+    });
+
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    match receiver.recv_timeout(timeout) {
+        Ok(output) => {
+            let status = child.wait()?;
+            reader.join().unwrap();
+            Ok((status, output?))
+        }
+        Err(_) => {
+            kill(&mut child);
+            let _ = child.wait();
+            // We don't join the reader, it may be blocked on a process that outlived the child.
+            Err(timed_out())
+        }
+    }
+}
+
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        // The child is the leader of its own process group (see `ResourceLimits::apply`)
+        // SAFETY: `kill` has no memory safety preconditions
+        if unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) } == 0 {
+            return;
+        }
+    }
+    let _ = child.kill();
+}
+
+/// Returns how long a single query can take before reaching `deadline` or `None` if the deadline
+/// already passed.
+pub(crate) fn remaining(deadline: Instant) -> Option<Duration> {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        None
+    } else {
+        Some(remaining)
+    }
+}
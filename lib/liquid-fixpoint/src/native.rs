@@ -0,0 +1,1330 @@
+//! A native solver for fixpoint [tasks] that does not depend on the external `fixpoint` binary.
+//!
+//! The solver infers a solution for the kvars in a task using predicate abstraction: every kvar
+//! starts assigned to the conjunction of all well-sorted instances of the task's [qualifiers]
+//! and clauses with a kvar in head position are used to iteratively weaken the assignment until
+//! a fixpoint is reached. The remaining (concrete) clauses are then checked with the inferred
+//! solution. Validity queries are discharged by talking SMT-LIB2 to a [solver process].
+//!
+//! Expressions that cannot be encoded (e.g., polymorphic functions whose instantiation cannot be
+//! determined) are abstracted as fresh boolean variables. This is sound in both assumptive and
+//! head position, but it may make some valid constraints fail.
+//!
+//! Qualifier scraping (`--scrape=both`) is not supported by this backend and
+//! [`Task::scrape_quals`] is ignored.
+//!
+//! [tasks]: Task
+//! [qualifiers]: Qualifier
+//! [solver process]: SmtProcess
+
+use std::{
+    collections::{HashMap, HashSet},
+    io, iter,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    constraint::DEFAULT_QUALIFIERS,
+    sexp::{Parser, Sexp},
+    smt::{SatResult, SmtProcess, SmtSort},
+    solution::kvar_param_name,
+    BinOp, BinRel, Bind, Constant, Constraint, CrashInfo, Error, Expr, FixpointFmt, FixpointResult,
+    FixpointStatus, FunDef, Identifier, KVarBind, Model, Pred, Qualifier, Quantifier,
+    ResourceLimits, SmtSolver, Sort, SortCtor, Stats, Task, Types,
+};
+
+pub(crate) fn solve<T: Types>(task: &Task<T>) -> io::Result<FixpointResult<T::Tag>> {
+    match Solver::new(task).and_then(|mut solver| solver.solve()) {
+        Ok(result) => Ok(result),
+        Err(err) if matches!(err.kind(), io::ErrorKind::TimedOut | io::ErrorKind::OutOfMemory) => {
+            Err(err)
+        }
+        Err(err) => {
+            let info = CrashInfo(vec![serde_json::Value::String(err.to_string())]);
+            Ok(FixpointResult { status: FixpointStatus::Crash(info), solution: vec![] })
+        }
+    }
+}
+
+/// A flattened Horn clause: a list of binders (the environment) and a single predicate in head
+/// position.
+struct Clause<'a, T: Types> {
+    binds: Vec<&'a Bind<T>>,
+    head: Head<'a, T>,
+    tag: Option<&'a T::Tag>,
+}
+
+enum Head<'a, T: Types> {
+    KVar(usize, &'a [T::Var]),
+    Expr(&'a Expr<T>),
+}
+
+/// A qualifier instantiated for a kvar. `args[i]` is the index of the kvar's argument used to
+/// instantiate the `i`-th argument of the qualifier.
+#[derive(Clone, PartialEq, Eq)]
+struct Candidate {
+    qualifier: QualifierRef,
+    args: Vec<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum QualifierRef {
+    Default(usize),
+    Task(usize),
+}
+
+struct Solver<'a, T: Types> {
+    task: &'a Task<T>,
+    clauses: Vec<Clause<'a, T>>,
+    /// Current assignment for each kvar, indexed by its position in [`Task::kvars`].
+    solution: Vec<Vec<Candidate>>,
+    enc: Encoder,
+}
+
+impl<'a, T: Types> Solver<'a, T> {
+    fn new(task: &'a Task<T>) -> io::Result<Self> {
+        let kvars: HashMap<String, usize> = task
+            .kvars
+            .iter()
+            .enumerate()
+            .map(|(i, decl)| (decl.kvid.display().to_string(), i))
+            .collect();
+
+        let mut clauses = vec![];
+        flatten_constraint(&task.constraint, &kvars, &mut vec![], &mut clauses);
+
+        let mut enc = Encoder::new(task.solver, task.limits)?;
+        enc.declare_task(task)?;
+
+        let solution = task
+            .kvars
+            .iter()
+            .map(|decl| {
+                let sorts = decl.sorts.iter().map(sort_to_smt).collect_vec();
+                initial_candidates(task, &sorts)
+            })
+            .collect();
+
+        Ok(Solver { task, clauses, solution, enc })
+    }
+
+    fn solve(&mut self) -> io::Result<FixpointResult<T::Tag>> {
+        let mut stats = Stats { num_cstr: self.clauses.len() as i32, ..Stats::default() };
+
+        let clauses = std::mem::take(&mut self.clauses);
+
+        // Weaken the solution until all clauses with a kvar in head position are satisfied
+        loop {
+            stats.num_iter += 1;
+            let mut changed = false;
+            for clause in &clauses {
+                let Head::KVar(kvar, args) = clause.head else { continue };
+                if self.solution[kvar].is_empty() {
+                    continue;
+                }
+                self.enc.smt.push()?;
+                let mark = self.enc.scope.len();
+                self.assume_binds(&clause.binds)?;
+
+                let candidates = std::mem::take(&mut self.solution[kvar]);
+                let before = candidates.len();
+                let mut retained = vec![];
+                for cand in candidates {
+                    let valid = match self.candidate_to_smt(&cand, args) {
+                        Some(formula) => self.enc.is_valid(&formula)?,
+                        None => false,
+                    };
+                    if valid {
+                        stats.num_vald += 1;
+                        retained.push(cand);
+                    }
+                }
+                changed |= retained.len() != before;
+                self.solution[kvar] = retained;
+
+                self.enc.scope.truncate(mark);
+                self.enc.smt.pop()?;
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        // Check concrete clauses with the inferred solution
+        let mut errors = vec![];
+        for (id, clause) in clauses.iter().enumerate() {
+            let Head::Expr(head) = clause.head else { continue };
+            self.enc.smt.push()?;
+            let mark = self.enc.scope.len();
+            self.assume_binds(&clause.binds)?;
+            let formula = self.enc.formula(head);
+            let result = self.enc.check_with_model(&formula, mark)?;
+            self.enc.scope.truncate(mark);
+            self.enc.smt.pop()?;
+
+            match result {
+                None => stats.num_vald += 1,
+                Some(model) => {
+                    // A failing clause must never be dropped, so a missing tag makes the task crash
+                    let Some(Ok(tag)) = clause.tag.map(|tag| tag.to_string().parse()) else {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("failing constraint {id} has no valid tag"),
+                        ));
+                    };
+                    errors.push(Error { id: id as i32, tag, model });
+                }
+            }
+        }
+        stats.num_chck = self.enc.smt.num_checks;
+        self.clauses = clauses;
+
+        let status = if errors.is_empty() {
+            FixpointStatus::Safe(stats)
+        } else {
+            FixpointStatus::Unsafe(stats, errors)
+        };
+        Ok(FixpointResult { status, solution: self.kvar_binds()? })
+    }
+
+    /// The current solution in the format reported by the `fixpoint` binary
+    fn kvar_binds(&self) -> io::Result<Vec<KVarBind>> {
+        iter::zip(&self.task.kvars, &self.solution)
+            .map(|(decl, candidates)| {
+                let conjuncts = candidates
+                    .iter()
+                    .map(|cand| {
+                        match cand.qualifier {
+                            QualifierRef::Default(i) => {
+                                instantiate_qualifier(&DEFAULT_QUALIFIERS[i], &cand.args)
+                            }
+                            QualifierRef::Task(i) => {
+                                instantiate_qualifier(&self.task.qualifiers[i], &cand.args)
+                            }
+                        }
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+                let val = match &conjuncts[..] {
+                    [] => "true".to_string(),
+                    [conjunct] => conjunct.to_string(),
+                    _ => format!("(and {})", conjuncts.iter().format(" ")),
+                };
+                Ok(KVarBind { kvar: decl.kvid.display().to_string(), val })
+            })
+            .collect()
+    }
+
+    fn assume_binds(&mut self, binds: &[&Bind<T>]) -> io::Result<()> {
+        for bind in binds {
+            self.enc.declare_binder(&bind.name, &bind.sort)?;
+            self.assume_pred(&bind.pred)?;
+        }
+        Ok(())
+    }
+
+    fn assume_pred(&mut self, pred: &Pred<T>) -> io::Result<()> {
+        match pred {
+            Pred::And(preds) => {
+                for pred in preds {
+                    self.assume_pred(pred)?;
+                }
+            }
+            Pred::KVar(kvid, args) => {
+                let kvid = kvid.display().to_string();
+                let Some(kvar) = self
+                    .task
+                    .kvars
+                    .iter()
+                    .position(|decl| decl.kvid.display().to_string() == kvid)
+                else {
+                    return Ok(());
+                };
+                // Dropping an instance we cannot encode is sound because it only weakens the
+                // assumptions.
+                let formulas = self.solution[kvar]
+                    .clone()
+                    .iter()
+                    .filter_map(|cand| self.candidate_to_smt(cand, args))
+                    .collect_vec();
+                for formula in formulas {
+                    self.enc.smt.assert(&formula)?;
+                }
+            }
+            Pred::Expr(expr) => {
+                let formula = self.enc.formula(expr);
+                self.enc.smt.assert(&formula)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the instance of a candidate qualifier for the given kvar arguments. Returns `None`
+    /// if one of the arguments cannot be encoded.
+    fn candidate_to_smt(&mut self, cand: &Candidate, args: &[T::Var]) -> Option<String> {
+        let actuals = cand
+            .args
+            .iter()
+            .map(|i| self.enc.var(&args[*i]).ok())
+            .collect::<Option<Vec<_>>>()?;
+        let formula = match cand.qualifier {
+            QualifierRef::Default(i) => self.enc.qualifier_body(&DEFAULT_QUALIFIERS[i], actuals),
+            QualifierRef::Task(i) => self.enc.qualifier_body(&self.task.qualifiers[i], actuals),
+        };
+        Some(formula)
+    }
+}
+
+fn flatten_constraint<'a, T: Types>(
+    cstr: &'a Constraint<T>,
+    kvars: &HashMap<String, usize>,
+    binds: &mut Vec<&'a Bind<T>>,
+    clauses: &mut Vec<Clause<'a, T>>,
+) {
+    match cstr {
+        Constraint::Pred(pred, tag) => flatten_head(pred, tag.as_ref(), kvars, binds, clauses),
+        Constraint::Conj(cstrs) => {
+            for cstr in cstrs {
+                flatten_constraint(cstr, kvars, binds, clauses);
+            }
+        }
+        Constraint::ForAll(bind, cstr) => {
+            binds.push(bind);
+            flatten_constraint(cstr, kvars, binds, clauses);
+            binds.pop();
+        }
+    }
+}
+
+fn flatten_head<'a, T: Types>(
+    pred: &'a Pred<T>,
+    tag: Option<&'a T::Tag>,
+    kvars: &HashMap<String, usize>,
+    binds: &[&'a Bind<T>],
+    clauses: &mut Vec<Clause<'a, T>>,
+) {
+    let head = match pred {
+        Pred::And(preds) => {
+            for pred in preds {
+                flatten_head(pred, tag, kvars, binds, clauses);
+            }
+            return;
+        }
+        Pred::KVar(kvid, args) => {
+            let Some(kvar) = kvars.get(&kvid.display().to_string()) else { return };
+            Head::KVar(*kvar, args)
+        }
+        Pred::Expr(expr) => Head::Expr(expr),
+    };
+    clauses.push(Clause { binds: binds.to_vec(), head, tag });
+}
+
+/// The body of `qualifier` with its `i`-th argument replaced by the `args[i]`-th parameter of a
+/// kvar
+fn instantiate_qualifier<U: Types>(qualifier: &Qualifier<U>, args: &[usize]) -> io::Result<Sexp> {
+    let params: HashMap<String, String> = iter::zip(&qualifier.args, args)
+        .map(|((name, _), idx)| (name.display().to_string(), kvar_param_name(*idx)))
+        .collect();
+    let mut body = Parser::new(&qualifier.body.to_string()).parse()?;
+    body.rename_atoms(&|atom| params.get(atom).cloned());
+    Ok(body)
+}
+
+/// Computes all well-sorted instances of the default and task qualifiers for a kvar with
+/// arguments of the given `sorts`.
+fn initial_candidates<T: Types>(task: &Task<T>, sorts: &[Option<SmtSort>]) -> Vec<Candidate> {
+    let mut candidates = vec![];
+    for (i, qualifier) in DEFAULT_QUALIFIERS.iter().enumerate() {
+        for args in qualifier_instances(qualifier, sorts) {
+            candidates.push(Candidate { qualifier: QualifierRef::Default(i), args });
+        }
+    }
+    for (i, qualifier) in task.qualifiers.iter().enumerate() {
+        for args in qualifier_instances(qualifier, sorts) {
+            candidates.push(Candidate { qualifier: QualifierRef::Task(i), args });
+        }
+    }
+    candidates
+}
+
+/// Enumerates all injective assignments from the qualifier's arguments to the kvar's arguments
+/// that respect sorts.
+fn qualifier_instances<U: Types>(
+    qualifier: &Qualifier<U>,
+    sorts: &[Option<SmtSort>],
+) -> Vec<Vec<usize>> {
+    fn go(
+        params: &[Option<SmtSort>],
+        sorts: &[Option<SmtSort>],
+        subst: &[Option<SmtSort>],
+        curr: &mut Vec<usize>,
+        acc: &mut Vec<Vec<usize>>,
+    ) {
+        let Some(param) = params.get(curr.len()) else {
+            acc.push(curr.clone());
+            return;
+        };
+        let Some(param) = param else { return };
+        for (i, sort) in sorts.iter().enumerate() {
+            let Some(sort) = sort else { continue };
+            if curr.contains(&i) {
+                continue;
+            }
+            let mut subst = subst.to_vec();
+            if param.unify(sort, &mut subst) {
+                curr.push(i);
+                go(params, sorts, &subst, curr, acc);
+                curr.pop();
+            }
+        }
+    }
+    let params = qualifier
+        .args
+        .iter()
+        .map(|(_, sort)| sort_to_smt(sort))
+        .collect_vec();
+    let mut acc = vec![];
+    go(&params, sorts, &[], &mut vec![], &mut acc);
+    acc
+}
+
+/// Converts a monomorphic fixpoint sort into an [`SmtSort`]. Polymorphic sorts (i.e., sorts
+/// containing [`Sort::Abs`]) are only supported in the declaration of constants.
+fn sort_to_smt<U: Types>(sort: &Sort<U>) -> Option<SmtSort> {
+    let sort = match sort {
+        Sort::Int => SmtSort::Int,
+        Sort::Bool => SmtSort::Bool,
+        Sort::Real => SmtSort::Real,
+        Sort::Str => SmtSort::Str,
+        Sort::BitVec(size) => {
+            let Sort::BvSize(size) = &**size else { return None };
+            SmtSort::BitVec(*size)
+        }
+        Sort::Var(i) => SmtSort::Param(*i),
+        Sort::Func(..) => {
+            let mut inputs = vec![];
+            let mut curr = sort;
+            while let Sort::Func(input_and_output) = curr {
+                let [input, output] = &**input_and_output;
+                inputs.push(sort_to_smt(input)?);
+                curr = output;
+            }
+            SmtSort::Func(inputs, Box::new(sort_to_smt(curr)?))
+        }
+        Sort::App(SortCtor::Set, args) => {
+            let [elem] = &args[..] else { return None };
+            SmtSort::set(sort_to_smt(elem)?)
+        }
+        Sort::App(SortCtor::Map, args) => {
+            let [k, v] = &args[..] else { return None };
+            SmtSort::Array(Box::new([sort_to_smt(k)?, sort_to_smt(v)?]))
+        }
+        Sort::App(SortCtor::Seq, args) => {
+            let [elem] = &args[..] else { return None };
+            SmtSort::Seq(Box::new(sort_to_smt(elem)?))
+        }
+        Sort::App(SortCtor::Data(name), args) => {
+            let args = args.iter().map(sort_to_smt).collect::<Option<_>>()?;
+            SmtSort::Data(name.display().to_string(), args)
+        }
+        Sort::BvSize(_) | Sort::Abs(..) => return None,
+    };
+    Some(sort)
+}
+
+/// A global symbol: either a declared constant or a function associated with a datatype.
+struct Global {
+    /// Number of sort parameters
+    params: usize,
+    sort: SmtSort,
+    /// Whether the symbol is declared by the solver (i.e., datatype constructors and selectors),
+    /// thus we don't need to declare instances of it when it is polymorphic.
+    builtin: bool,
+}
+
+/// The result of encoding an expression: the SMT-LIB2 term and its sort
+type Term = (String, SmtSort);
+
+/// Marker for expressions that cannot be encoded into SMT-LIB2
+struct Unsupported;
+
+struct Encoder {
+    smt: SmtProcess,
+    solver: SmtSolver,
+    globals: HashMap<String, Global>,
+    /// Instances of polymorphic constants that have already been declared
+    instances: HashSet<String>,
+    /// Variables in scope mapped to their name in the solver and their sort. We look up from the
+    /// end to account for shadowing.
+    scope: Vec<(String, Term)>,
+    fresh: usize,
+    /// Number of subexpressions abstracted as fresh boolean variables so far
+    abstracted: usize,
+}
+
+impl Encoder {
+    fn new(solver: SmtSolver, limits: ResourceLimits) -> io::Result<Self> {
+        Ok(Encoder {
+            smt: SmtProcess::spawn(solver, limits)?,
+            solver,
+            globals: HashMap::new(),
+            instances: HashSet::new(),
+            scope: vec![],
+            fresh: 0,
+            abstracted: 0,
+        })
+    }
+
+    fn declare_task<T: Types>(&mut self, task: &Task<T>) -> io::Result<()> {
+        // All datatypes are declared in a single command so they can be mutually recursive
+        let mut sort_decs = vec![];
+        let mut datatype_decs = vec![];
+        for decl in &task.data_decls {
+            let name = decl.name.display().to_string();
+            let params = (0..decl.vars).map(SmtSort::Param).collect_vec();
+            let data_sort = SmtSort::Data(name.clone(), params.clone());
+            let ctors = decl.ctors.iter().map(|ctor| {
+                let fields = ctor.fields.iter().map(|field| {
+                    let sort = sort_to_smt(&field.sort).unwrap_or(SmtSort::Int);
+                    self.globals.insert(
+                        field.name.display().to_string(),
+                        Global {
+                            params: decl.vars,
+                            sort: SmtSort::Func(vec![data_sort.clone()], Box::new(sort.clone())),
+                            builtin: true,
+                        },
+                    );
+                    (field.name.display().to_string(), sort)
+                });
+                let fields = fields.collect_vec();
+                self.globals.insert(
+                    ctor.name.display().to_string(),
+                    Global {
+                        params: decl.vars,
+                        sort: SmtSort::Func(
+                            fields.iter().map(|(_, sort)| sort.clone()).collect(),
+                            Box::new(data_sort.clone()),
+                        ),
+                        builtin: true,
+                    },
+                );
+                format!(
+                    "({} {})",
+                    ctor.name.display(),
+                    fields
+                        .iter()
+                        .format_with(" ", |(name, sort), f| f(&format_args!("({name} {sort})")))
+                )
+            });
+            let ctors = ctors.collect_vec();
+            sort_decs.push(format!("({name} {})", decl.vars));
+            if decl.vars == 0 {
+                datatype_decs.push(format!("({})", ctors.iter().format(" ")));
+            } else {
+                datatype_decs.push(format!(
+                    "(par ({}) ({}))",
+                    params.iter().format(" "),
+                    ctors.iter().format(" ")
+                ));
+            }
+        }
+        if !sort_decs.is_empty() {
+            self.smt.send(format_args!(
+                "(declare-datatypes ({}) ({}))",
+                sort_decs.iter().format(" "),
+                datatype_decs.iter().format(" ")
+            ))?;
+        }
+
+        let defined: HashSet<String> = task
+            .define_funs
+            .iter()
+            .map(|fun_def| fun_def.name.display().to_string())
+            .collect();
+        for cinfo in &task.constants {
+            let name = cinfo.name.display().to_string();
+            let (params, sort) = cinfo.sort.peel_out_abs();
+            let Some(sort) = sort_to_smt(sort) else { continue };
+            if params == 0 && !defined.contains(&name) {
+                self.declare_fun(&name, &sort)?;
+            }
+            self.globals
+                .insert(name, Global { params, sort, builtin: false });
+        }
+        self.define_funs(&task.define_funs)
+    }
+
+    /// Defines all functions with a single `define-funs-rec` so they can refer to each other. A
+    /// function whose body cannot be encoded is declared without a definition instead.
+    fn define_funs<U: Types>(&mut self, fun_defs: &[FunDef<U>]) -> io::Result<()> {
+        let mut decls = vec![];
+        let mut bodies = vec![];
+        for fun_def in fun_defs {
+            let name = fun_def.name.display().to_string();
+            match self.fun_def(fun_def) {
+                Ok((decl, body)) => {
+                    decls.push(format!("({name} {decl})"));
+                    bodies.push(body);
+                }
+                Err(Unsupported) => {
+                    if let Some(Global { params: 0, sort, .. }) = self.globals.get(&name) {
+                        let sort = sort.clone();
+                        self.declare_fun(&name, &sort)?;
+                    }
+                }
+            }
+        }
+        if !decls.is_empty() {
+            self.smt.send(format_args!(
+                "(define-funs-rec ({}) ({}))",
+                decls.iter().format(" "),
+                bodies.iter().format(" ")
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the signature (parameters and output sort) and the body of a function definition
+    fn fun_def<U: Types>(&mut self, fun_def: &FunDef<U>) -> Result<(String, String), Unsupported> {
+        let out = sort_to_smt(&fun_def.out).ok_or(Unsupported)?;
+        let mark = self.scope.len();
+        let mut binders = vec![];
+        for (name, sort) in &fun_def.args {
+            let Some(sort) = sort_to_smt(sort) else {
+                self.scope.truncate(mark);
+                return Err(Unsupported);
+            };
+            let fresh = self.fresh_name();
+            binders.push(format!("({fresh} {sort})"));
+            self.scope.push((name.display().to_string(), (fresh, sort)));
+        }
+        // Abstracting a subformula would make the definition depend on a global variable
+        let abstracted = self.abstracted;
+        let body = self.expr_with_sort(&fun_def.body, &out);
+        self.scope.truncate(mark);
+        if self.abstracted != abstracted {
+            return Err(Unsupported);
+        }
+        Ok((format!("({}) {out}", binders.join(" ")), body?))
+    }
+
+    fn declare_fun(&mut self, name: &str, sort: &SmtSort) -> io::Result<()> {
+        match sort {
+            SmtSort::Func(inputs, output) => {
+                self.smt.send(format_args!(
+                    "(declare-fun {name} ({}) {output})",
+                    inputs.iter().format(" ")
+                ))
+            }
+            _ => self.smt.send(format_args!("(declare-const {name} {sort})")),
+        }
+    }
+
+    fn fresh_name(&mut self) -> String {
+        let name = format!("v!{}", self.fresh);
+        self.fresh += 1;
+        name
+    }
+
+    /// Declares a fresh constant for a binder and pushes it into scope. Binders whose sort cannot
+    /// be encoded are not put in scope which makes expressions mentioning them unsupported.
+    fn declare_binder<U: Types>(&mut self, name: &U::Var, sort: &Sort<U>) -> io::Result<()> {
+        let Some(sort) = sort_to_smt(sort) else { return Ok(()) };
+        let fresh = self.fresh_name();
+        self.declare_fun(&fresh, &sort)?;
+        self.scope.push((name.display().to_string(), (fresh, sort)));
+        Ok(())
+    }
+
+    fn is_valid(&mut self, formula: &str) -> io::Result<bool> {
+        self.smt.push()?;
+        self.smt.assert(&format!("(not {formula})"))?;
+        let result = self.smt.check_sat()?;
+        self.smt.pop()?;
+        Ok(matches!(result, SatResult::Unsat))
+    }
+
+    /// Checks the validity of `formula`. If the formula is not valid, returns a model with values
+    /// for the variables in scope starting at position `from`. The model is empty if the solver
+    /// cannot produce one.
+    fn check_with_model(&mut self, formula: &str, from: usize) -> io::Result<Option<Model>> {
+        self.smt.push()?;
+        self.smt.assert(&format!("(not {formula})"))?;
+        let model = match self.smt.check_sat()? {
+            SatResult::Unsat => None,
+            SatResult::Unknown => Some(Model::default()),
+            SatResult::Sat => {
+                let vars = self.scope[from..]
+                    .iter()
+                    .filter(|(name, (_, sort))| {
+                        name != "_$"
+                            && matches!(
+                                sort,
+                                SmtSort::Int | SmtSort::Bool | SmtSort::Real | SmtSort::BitVec(_)
+                            )
+                    })
+                    .map(|(name, (smt_name, _))| (name.clone(), smt_name.as_str()))
+                    .collect_vec();
+                let terms = vars.iter().map(|(_, term)| *term).collect_vec();
+                let values = self.smt.get_values(&terms)?;
+                let values = iter::zip(vars, values)
+                    .map(|((name, _), value)| (name, value))
+                    .collect();
+                Some(Model(values))
+            }
+        };
+        self.smt.pop()?;
+        Ok(model)
+    }
+
+    fn qualifier_body<U: Types>(&mut self, qualifier: &Qualifier<U>, actuals: Vec<Term>) -> String {
+        let mark = self.scope.len();
+        for ((name, _), actual) in iter::zip(&qualifier.args, actuals) {
+            self.scope.push((name.display().to_string(), actual));
+        }
+        let formula = self.formula(&qualifier.body);
+        self.scope.truncate(mark);
+        formula
+    }
+
+    /// Encodes an expression of sort bool. Subexpressions that cannot be encoded are replaced by a
+    /// fresh boolean variable.
+    fn formula<U: Types>(&mut self, expr: &Expr<U>) -> String {
+        match expr {
+            Expr::And(exprs) => {
+                if exprs.is_empty() {
+                    return "true".to_string();
+                }
+                format!("(and {})", exprs.iter().map(|e| self.formula(e)).join(" "))
+            }
+            Expr::Or(exprs) => {
+                if exprs.is_empty() {
+                    return "false".to_string();
+                }
+                format!("(or {})", exprs.iter().map(|e| self.formula(e)).join(" "))
+            }
+            Expr::Not(e) => format!("(not {})", self.formula(e)),
+            Expr::Imp(exprs) => {
+                let [e1, e2] = &**exprs;
+                format!("(=> {} {})", self.formula(e1), self.formula(e2))
+            }
+            Expr::Iff(exprs) => {
+                let [e1, e2] = &**exprs;
+                format!("(= {} {})", self.formula(e1), self.formula(e2))
+            }
+            _ => {
+                match self.expr(expr) {
+                    Ok((term, SmtSort::Bool)) => term,
+                    _ => self.abstract_bool(),
+                }
+            }
+        }
+    }
+
+    fn abstract_bool(&mut self) -> String {
+        self.abstracted += 1;
+        let fresh = self.fresh_name();
+        // This cannot fail in practice and if it does the error will be reported by the next
+        // `check-sat`.
+        let _ = self.declare_fun(&fresh, &SmtSort::Bool);
+        fresh
+    }
+
+    fn var(&mut self, var: &impl Identifier) -> Result<Term, Unsupported> {
+        let name = var.display().to_string();
+        if let Some((_, term)) = self.scope.iter().rev().find(|(x, _)| *x == name) {
+            return Ok(term.clone());
+        }
+        match self.globals.get(&name) {
+            // Nullary datatype constructors are used as constants
+            Some(Global { params: 0, sort: SmtSort::Func(inputs, output), builtin: true })
+                if inputs.is_empty() =>
+            {
+                Ok((name, (**output).clone()))
+            }
+            Some(global) if global.params == 0 => Ok((name, global.sort.clone())),
+            _ => Err(Unsupported),
+        }
+    }
+
+    fn expr<U: Types>(&mut self, expr: &Expr<U>) -> Result<Term, Unsupported> {
+        let term = match expr {
+            Expr::Constant(c) => {
+                match c {
+                    Constant::Numeral(n) => (n.display().to_string(), SmtSort::Int),
+                    Constant::Decimal(r) => (r.display().to_string(), SmtSort::Real),
+                    Constant::Boolean(b) => (b.to_string(), SmtSort::Bool),
+                    Constant::String(s) => (s.display().to_string(), SmtSort::Str),
+                }
+            }
+            Expr::Var(x) => self.var(x)?,
+            Expr::App(func, args) => self.app(func, args)?,
+            Expr::Neg(e) => {
+                let (e, sort) = self.expr(e)?;
+                if !matches!(sort, SmtSort::Int | SmtSort::Real) {
+                    return Err(Unsupported);
+                }
+                (format!("(- {e})"), sort)
+            }
+            Expr::BinaryOp(op, exprs) => {
+                let [e1, e2] = &**exprs;
+                let (e1, sort) = self.expr(e1)?;
+                let e2 = self.expr_with_sort(e2, &sort)?;
+                let op = match (op, &sort) {
+                    (BinOp::Add, SmtSort::Int | SmtSort::Real) => "+",
+                    (BinOp::Sub, SmtSort::Int | SmtSort::Real) => "-",
+                    (BinOp::Mul, SmtSort::Int | SmtSort::Real) => "*",
+                    (BinOp::Div, SmtSort::Int) => "div",
+                    (BinOp::Div, SmtSort::Real) => "/",
+                    (BinOp::Mod, SmtSort::Int) => "mod",
+                    _ => return Err(Unsupported),
+                };
+                (format!("({op} {e1} {e2})"), sort)
+            }
+            Expr::IfThenElse(exprs) => {
+                let [p, e1, e2] = &**exprs;
+                let p = self.formula(p);
+                let (e1, sort) = self.expr(e1)?;
+                let e2 = self.expr_with_sort(e2, &sort)?;
+                (format!("(ite {p} {e1} {e2})"), sort)
+            }
+            Expr::And(_) | Expr::Or(_) | Expr::Not(_) | Expr::Imp(_) | Expr::Iff(_) => {
+                (self.formula(expr), SmtSort::Bool)
+            }
+            Expr::Atom(rel, exprs) => {
+                let [e1, e2] = &**exprs;
+                let (e1, e2, sort) = match self.expr(e1) {
+                    Ok((e1, sort)) => {
+                        let e2 = self.expr_with_sort(e2, &sort)?;
+                        (e1, e2, sort)
+                    }
+                    Err(Unsupported) => {
+                        let (e2, sort) = self.expr(e2)?;
+                        let e1 = self.expr_with_sort(e1, &sort)?;
+                        (e1, e2, sort)
+                    }
+                };
+                let atom = match (rel, &sort) {
+                    (BinRel::Eq, _) => format!("(= {e1} {e2})"),
+                    (BinRel::Ne, _) => format!("(not (= {e1} {e2}))"),
+                    (_, SmtSort::Int | SmtSort::Real) => format!("({rel} {e1} {e2})"),
+                    _ => return Err(Unsupported),
+                };
+                (atom, SmtSort::Bool)
+            }
+            Expr::Quant(quant, vars, body) => (self.quant(*quant, vars, body)?, SmtSort::Bool),
+        };
+        Ok(term)
+    }
+
+    /// Encodes a quantified formula. The body cannot be partially abstracted because the fresh
+    /// variables would escape the binder, thus the entire quantifier is unsupported if any part
+    /// of the body is.
+    fn quant<U: Types>(
+        &mut self,
+        quant: Quantifier,
+        vars: &[(U::Var, Sort<U>)],
+        body: &Expr<U>,
+    ) -> Result<String, Unsupported> {
+        let mark = self.scope.len();
+        let mut binders = vec![];
+        for (name, sort) in vars {
+            let Some(sort) = sort_to_smt(sort) else {
+                self.scope.truncate(mark);
+                return Err(Unsupported);
+            };
+            let fresh = self.fresh_name();
+            binders.push(format!("({fresh} {sort})"));
+            self.scope.push((name.display().to_string(), (fresh, sort)));
+        }
+        let abstracted = self.abstracted;
+        let body = self.formula(body);
+        self.scope.truncate(mark);
+        if self.abstracted != abstracted {
+            return Err(Unsupported);
+        }
+        if binders.is_empty() {
+            return Ok(body);
+        }
+        Ok(format!("({quant} ({}) {body})", binders.join(" ")))
+    }
+
+    /// Encodes an expression that is expected to have the given sort. This is needed for
+    /// polymorphic constants like the empty set whose sort cannot be inferred bottom-up.
+    fn expr_with_sort<U: Types>(
+        &mut self,
+        expr: &Expr<U>,
+        expected: &SmtSort,
+    ) -> Result<String, Unsupported> {
+        if let Expr::App(func, args) = expr {
+            let Expr::Var(func) = &**func else { return Err(Unsupported) };
+            match (func.display().to_string().as_str(), expected, &args[..]) {
+                ("Set_empty", SmtSort::Array(..), [_]) => {
+                    return Ok(format!("((as const {expected}) false)"));
+                }
+                ("Seq_empty", SmtSort::Seq(_), [_]) => {
+                    return Ok(format!("(as seq.empty {expected})"));
+                }
+                ("Map_default", SmtSort::Array(..), [v]) => {
+                    let (v, _) = self.expr(v)?;
+                    return Ok(format!("((as const {expected}) {v})"));
+                }
+                _ => {}
+            }
+        }
+        let (term, sort) = self.expr(expr)?;
+        if sort == *expected {
+            Ok(term)
+        } else {
+            Err(Unsupported)
+        }
+    }
+
+    fn app<U: Types>(&mut self, func: &Expr<U>, args: &[Expr<U>]) -> Result<Term, Unsupported> {
+        let Expr::Var(func) = func else { return Err(Unsupported) };
+        let name = func.display().to_string();
+
+        // Functions in scope are monomorphic
+        if let Some((_, (func, SmtSort::Func(inputs, output)))) =
+            self.scope.iter().rev().find(|(x, _)| *x == name).cloned()
+        {
+            if inputs.len() != args.len() {
+                return Err(Unsupported);
+            }
+            let args = iter::zip(args, &inputs)
+                .map(|(arg, sort)| self.expr_with_sort(arg, sort))
+                .try_collect::<_, Vec<_>, _>()?;
+            return Ok((format!("({func} {})", args.iter().join(" ")), *output));
+        }
+
+        if let Some(global) = self.globals.get(&name) {
+            let SmtSort::Func(inputs, output) = global.sort.clone() else {
+                return Err(Unsupported);
+            };
+            if inputs.len() != args.len() {
+                return Err(Unsupported);
+            }
+            let (params, builtin) = (global.params, global.builtin);
+            if params == 0 {
+                let args = iter::zip(args, &inputs)
+                    .map(|(arg, sort)| self.expr_with_sort(arg, sort))
+                    .try_collect::<_, Vec<_>, _>()?;
+                return Ok((format!("({name} {})", args.iter().join(" ")), *output));
+            }
+
+            // Polymorphic function: infer the instantiation from the sort of the arguments
+            let args = args
+                .iter()
+                .map(|arg| self.expr(arg))
+                .try_collect::<_, Vec<_>, _>()?;
+            let mut subst = vec![];
+            for ((_, sort), input) in iter::zip(&args, &inputs) {
+                if !input.unify(sort, &mut subst) {
+                    return Err(Unsupported);
+                }
+            }
+            subst.resize(params, None);
+            let subst = subst
+                .into_iter()
+                .collect::<Option<Vec<_>>>()
+                .ok_or(Unsupported)?;
+            let output = output.subst(&subst);
+            let func = if builtin {
+                name
+            } else {
+                let instance = format!("|{name}@{}|", subst.iter().join(","));
+                if !self.instances.contains(&instance) {
+                    let sort = SmtSort::Func(
+                        inputs.iter().map(|s| s.subst(&subst)).collect(),
+                        Box::new(output.clone()),
+                    );
+                    self.declare_fun(&instance, &sort)
+                        .map_err(|_| Unsupported)?;
+                    self.instances.insert(instance.clone());
+                }
+                instance
+            };
+            return Ok((format!("({func} {})", args.iter().map(|(a, _)| a).join(" ")), output));
+        }
+
+        // Datatype testers are named after the constructor prefixed with `is$`
+        if let (Some(ctor), [arg]) = (name.strip_prefix("is$"), args) {
+            if let Some(Global { params: 0, sort: SmtSort::Func(_, output), builtin: true }) =
+                self.globals.get(ctor)
+            {
+                let output = (**output).clone();
+                let arg = self.expr_with_sort(arg, &output)?;
+                return Ok((format!("((_ is {ctor}) {arg})"), SmtSort::Bool));
+            }
+        }
+
+        let args = args
+            .iter()
+            .map(|arg| self.expr(arg))
+            .try_collect::<_, Vec<_>, _>()?;
+        self.theory_app(&name, args)
+    }
+
+    /// Encodes an application of an interpreted theory function
+    fn theory_app(&mut self, name: &str, args: Vec<Term>) -> Result<Term, Unsupported> {
+        let term = match (name, &args[..]) {
+            ("strLen", [(s, SmtSort::Str)]) => (format!("(str.len {s})"), SmtSort::Int),
+            ("strConcat", [(s1, SmtSort::Str), (s2, SmtSort::Str)]) => {
+                (format!("(str.++ {s1} {s2})"), SmtSort::Str)
+            }
+            ("strPrefixOf", [(s1, SmtSort::Str), (s2, SmtSort::Str)]) => {
+                (format!("(str.prefixof {s1} {s2})"), SmtSort::Bool)
+            }
+            ("strContains", [(s1, SmtSort::Str), (s2, SmtSort::Str)]) => {
+                (format!("(str.contains {s1} {s2})"), SmtSort::Bool)
+            }
+            ("strAt", [(s, SmtSort::Str), (i, SmtSort::Int)]) => {
+                (format!("(str.at {s} {i})"), SmtSort::Str)
+            }
+            ("subString", [(s, SmtSort::Str), (i, SmtSort::Int), (n, SmtSort::Int)]) => {
+                (format!("(str.substr {s} {i} {n})"), SmtSort::Str)
+            }
+            ("concat", [(a, SmtSort::BitVec(n)), (b, SmtSort::BitVec(m))]) => {
+                (format!("(concat {a} {b})"), SmtSort::BitVec(n + m))
+            }
+            (
+                "bvadd" | "bvsub" | "bvmul" | "bvudiv" | "bvurem" | "bvsdiv" | "bvsrem" | "bvsmod"
+                | "bvshl" | "bvlshr" | "bvashr" | "bvand" | "bvor" | "bvxor",
+                [(a, SmtSort::BitVec(n)), (b, SmtSort::BitVec(m))],
+            ) if n == m => (format!("({name} {a} {b})"), SmtSort::BitVec(*n)),
+            ("bvneg" | "bvnot", [(a, SmtSort::BitVec(n))]) => {
+                (format!("({name} {a})"), SmtSort::BitVec(*n))
+            }
+            (
+                "bvule" | "bvuge" | "bvugt" | "bvult",
+                [(a, SmtSort::BitVec(n)), (b, SmtSort::BitVec(m))],
+            ) if n == m => (format!("({name} {a} {b})"), SmtSort::Bool),
+            ("Set_sng", [(x, sort)]) => {
+                let set = SmtSort::set(sort.clone());
+                (format!("(store ((as const {set}) false) {x} true)"), set)
+            }
+            ("Set_cup", [(s1, sort @ SmtSort::Array(_)), (s2, sort2)])
+                if sort == sort2 && matches!(self.solver, SmtSolver::Z3) =>
+            {
+                (format!("((_ map or) {s1} {s2})"), sort.clone())
+            }
+            ("Set_mem", [(x, elem), (s, SmtSort::Array(sorts))]) if sorts[0] == *elem => {
+                (format!("(select {s} {x})"), SmtSort::Bool)
+            }
+            ("Map_select", [(m, SmtSort::Array(sorts)), (k, sort)]) if sorts[0] == *sort => {
+                (format!("(select {m} {k})"), sorts[1].clone())
+            }
+            ("Map_store", [(m, map @ SmtSort::Array(sorts)), (k, ksort), (v, vsort)])
+                if sorts[0] == *ksort && sorts[1] == *vsort =>
+            {
+                (format!("(store {m} {k} {v})"), map.clone())
+            }
+            ("Seq_unit", [(x, sort)]) => {
+                (format!("(seq.unit {x})"), SmtSort::Seq(Box::new(sort.clone())))
+            }
+            ("Seq_len", [(s, SmtSort::Seq(_))]) => (format!("(seq.len {s})"), SmtSort::Int),
+            ("Seq_nth", [(s, SmtSort::Seq(elem)), (i, SmtSort::Int)]) => {
+                (format!("(seq.nth {s} {i})"), (**elem).clone())
+            }
+            ("Seq_concat", [(s1, sort @ SmtSort::Seq(_)), (s2, sort2)]) if sort == sort2 => {
+                (format!("(seq.++ {s1} {s2})"), sort.clone())
+            }
+            (
+                "Seq_extract",
+                [(s, sort @ SmtSort::Seq(_)), (i, SmtSort::Int), (n, SmtSort::Int)],
+            ) => (format!("(seq.extract {s} {i} {n})"), sort.clone()),
+            ("Seq_contains", [(s, SmtSort::Seq(elem)), (x, sort)]) if **elem == *sort => {
+                (format!("(seq.contains {s} (seq.unit {x}))"), SmtSort::Bool)
+            }
+            ("Seq_update", [(s, sort @ SmtSort::Seq(elem)), (i, SmtSort::Int), (x, sort2)])
+                if **elem == *sort2 =>
+            {
+                // Updating an index out of bounds leaves the sequence unchanged
+                let updated = format!(
+                    "(seq.++ (seq.extract {s} 0 {i}) (seq.unit {x}) (seq.extract {s} (+ {i} 1) (- (seq.len {s}) {i} 1)))"
+                );
+                (
+                    format!("(ite (and (<= 0 {i}) (< {i} (seq.len {s}))) {updated} {s})"),
+                    sort.clone(),
+                )
+            }
+            _ => return Self::sized_bv_app(name, &args),
+        };
+        Ok(term)
+    }
+
+    /// Encodes conversions whose name depends on the sizes of the bit-vectors involved, i.e.,
+    /// `int_to_bvN`, `bvN_to_int` and the indexed operations `app (_ zero_extend k)`,
+    /// `app (_ sign_extend k)` and `app (_ extract i j)`.
+    fn sized_bv_app(name: &str, args: &[Term]) -> Result<Term, Unsupported> {
+        let parse = |n: &str| n.trim().parse::<usize>().map_err(|_| Unsupported);
+        if let Some(size) = name.strip_prefix("int_to_bv") {
+            let [(n, SmtSort::Int)] = args else { return Err(Unsupported) };
+            let size = parse(size)?;
+            return Ok((format!("((_ int2bv {size}) {n})"), SmtSort::BitVec(size)));
+        }
+        if let Some(size) = name
+            .strip_prefix("bv")
+            .and_then(|s| s.strip_suffix("_to_int"))
+        {
+            let [(bv, SmtSort::BitVec(n))] = args else { return Err(Unsupported) };
+            if *n != parse(size)? {
+                return Err(Unsupported);
+            }
+            return Ok((format!("(bv2nat {bv})"), SmtSort::Int));
+        }
+        let Some(op) = name.strip_prefix("app ") else { return Err(Unsupported) };
+        let [(bv, SmtSort::BitVec(size))] = args else { return Err(Unsupported) };
+        let indices = op
+            .strip_prefix("(_ ")
+            .and_then(|op| op.strip_suffix(")"))
+            .ok_or(Unsupported)?;
+        let size = match indices.split_whitespace().collect::<Vec<_>>()[..] {
+            ["zero_extend" | "sign_extend", n] => size + parse(n)?,
+            ["extract", i, j] => {
+                let (i, j) = (parse(i)?, parse(j)?);
+                if j > i || i >= *size {
+                    return Err(Unsupported);
+                }
+                i - j + 1
+            }
+            _ => return Err(Unsupported),
+        };
+        Ok((format!("({op} {bv})"), SmtSort::BitVec(size)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DefaultTypes;
+
+    type E = Expr<DefaultTypes>;
+
+    fn var(x: &'static str) -> E {
+        Expr::Var(x)
+    }
+
+    fn atom(rel: BinRel, e1: E, e2: E) -> E {
+        Expr::Atom(rel, Box::new([e1, e2]))
+    }
+
+    fn bind(name: &'static str, pred: Pred<DefaultTypes>) -> Bind<DefaultTypes> {
+        Bind { name, sort: Sort::Int, pred }
+    }
+
+    fn term(term: &str, sort: SmtSort) -> Option<Term> {
+        Some((term.to_string(), sort))
+    }
+
+    #[test]
+    fn flatten_nested_constraint() {
+        // forall a. a > 0 => ($k0 a && $k9 a && forall b. (a + b > 0 && b > 0))
+        let body = Constraint::Conj(vec![
+            Constraint::Pred(Pred::KVar("k0", vec!["a"]), None),
+            Constraint::Pred(Pred::KVar("k9", vec!["a"]), None),
+            Constraint::ForAll(
+                bind("b", Pred::TRUE),
+                Box::new(Constraint::Pred(
+                    Pred::And(vec![
+                        Pred::Expr(atom(
+                            BinRel::Gt,
+                            Expr::BinaryOp(BinOp::Add, Box::new([var("a"), var("b")])),
+                            Expr::int(0),
+                        )),
+                        Pred::Expr(atom(BinRel::Gt, var("b"), Expr::int(0))),
+                    ]),
+                    Some("1".to_string()),
+                )),
+            ),
+        ]);
+        let cstr = Constraint::ForAll(
+            bind("a", Pred::Expr(atom(BinRel::Gt, var("a"), Expr::int(0)))),
+            Box::new(body),
+        );
+        let kvars = HashMap::from([("k0".to_string(), 0)]);
+
+        let mut clauses = vec![];
+        flatten_constraint(&cstr, &kvars, &mut vec![], &mut clauses);
+
+        // Unknown kvars in head position are dropped and conjunctions are split
+        assert_eq!(clauses.len(), 3);
+        let binds =
+            |clause: &Clause<DefaultTypes>| clause.binds.iter().map(|b| b.name).collect_vec();
+        assert!(matches!(clauses[0].head, Head::KVar(0, ["a"])));
+        assert_eq!(binds(&clauses[0]), ["a"]);
+        for clause in &clauses[1..] {
+            assert!(matches!(clause.head, Head::Expr(_)));
+            assert_eq!(binds(clause), ["a", "b"]);
+            assert_eq!(clause.tag.map(String::as_str), Some("1"));
+        }
+    }
+
+    #[test]
+    fn instantiate_qualifier_with_kvar_params() {
+        let qualifier = Qualifier::<DefaultTypes> {
+            name: "Lt".to_string(),
+            args: vec![("a", Sort::Int), ("b", Sort::Int)],
+            body: atom(BinRel::Lt, var("a"), var("b")),
+        };
+        let body = instantiate_qualifier(&qualifier, &[2, 0]).unwrap();
+        assert_eq!(body.to_string(), "(< karg$2 karg$0)");
+    }
+
+    #[test]
+    fn qualifier_instances_respect_sorts() {
+        // Both arguments must be instantiated with the same sort and different kvar arguments
+        let qualifier = Qualifier::<DefaultTypes> {
+            name: "Eq".to_string(),
+            args: vec![("a", Sort::Var(0)), ("b", Sort::Var(0))],
+            body: atom(BinRel::Eq, var("a"), var("b")),
+        };
+        let sorts = [Some(SmtSort::Int), Some(SmtSort::Bool), None, Some(SmtSort::Int)];
+        assert_eq!(qualifier_instances(&qualifier, &sorts), [[0, 3], [3, 0]]);
+
+        let qualifier = Qualifier::<DefaultTypes> {
+            name: "Pos".to_string(),
+            args: vec![("a", Sort::Int)],
+            body: atom(BinRel::Gt, var("a"), Expr::int(0)),
+        };
+        assert_eq!(qualifier_instances(&qualifier, &sorts), [[0], [3]]);
+        assert!(qualifier_instances(&qualifier, &[Some(SmtSort::Real)]).is_empty());
+    }
+
+    #[cfg(unix)]
+    fn encoder() -> Encoder {
+        Encoder {
+            smt: SmtProcess::fake("echo unsat", ResourceLimits::default()).unwrap(),
+            solver: SmtSolver::Z3,
+            globals: HashMap::new(),
+            instances: HashSet::new(),
+            scope: vec![],
+            fresh: 0,
+            abstracted: 0,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn encode_expr() {
+        let mut enc = encoder();
+        enc.declare_binder::<DefaultTypes>(&"x", &Sort::Int)
+            .unwrap();
+        enc.declare_binder::<DefaultTypes>(&"s", &Sort::Str)
+            .unwrap();
+
+        let e = atom(
+            BinRel::Ge,
+            Expr::BinaryOp(BinOp::Add, Box::new([var("x"), Expr::int(1)])),
+            Expr::int(-2),
+        );
+        assert_eq!(enc.expr(&e).ok(), term("(>= (+ v!0 1) (- 2))", SmtSort::Bool));
+
+        let e = atom(BinRel::Ne, var("s"), Expr::Constant(Constant::String("a".to_string())));
+        assert_eq!(enc.expr(&e).ok(), term("(not (= v!1 \"a\"))", SmtSort::Bool));
+
+        // Ill-sorted and unbound expressions cannot be encoded
+        assert!(enc.expr(&atom(BinRel::Eq, var("x"), var("s"))).is_err());
+        assert!(enc.expr(&atom(BinRel::Lt, var("s"), var("s"))).is_err());
+        assert!(enc.expr(&var("y")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn abstract_unsupported_subformulas() {
+        let mut enc = encoder();
+        enc.declare_binder::<DefaultTypes>(&"x", &Sort::Int)
+            .unwrap();
+        let e = Expr::And(vec![
+            atom(BinRel::Gt, var("x"), Expr::int(0)),
+            atom(BinRel::Gt, var("y"), Expr::int(0)),
+        ]);
+        assert_eq!(enc.formula(&e), "(and (> v!0 0) v!1)");
+        assert_eq!(enc.abstracted, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn encode_quant() {
+        let mut enc = encoder();
+        enc.declare_binder::<DefaultTypes>(&"x", &Sort::Int)
+            .unwrap();
+
+        let body = atom(BinRel::Ge, var("i"), var("x"));
+        let vars: [(_, Sort<DefaultTypes>); 1] = [("i", Sort::Int)];
+        assert_eq!(
+            enc.quant(Quantifier::Forall, &vars, &body).ok().as_deref(),
+            Some("(forall ((v!1 Int)) (>= v!1 v!0))")
+        );
+        assert_eq!(enc.scope.len(), 1);
+
+        // The body cannot be partially abstracted
+        let body = Expr::Or(vec![body, atom(BinRel::Gt, var("y"), Expr::int(0))]);
+        assert!(enc.quant(Quantifier::Exists, &vars, &body).is_err());
+        assert_eq!(enc.scope.len(), 1);
+
+        // Binders whose sort cannot be encoded
+        let vars: [(_, Sort<DefaultTypes>); 1] = [("i", Sort::Abs(0, Box::new(Sort::Var(0))))];
+        assert!(enc
+            .quant(Quantifier::Forall, &vars, &Expr::Constant(Constant::Boolean(true)))
+            .is_err());
+        assert_eq!(enc.scope.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn encode_theory_app() {
+        let mut enc = encoder();
+        let str = || ("s".to_string(), SmtSort::Str);
+        let int = || ("n".to_string(), SmtSort::Int);
+        let bv = |size| ("b".to_string(), SmtSort::BitVec(size));
+
+        assert_eq!(enc.theory_app("strLen", vec![str()]).ok(), term("(str.len s)", SmtSort::Int));
+        assert_eq!(
+            enc.theory_app("subString", vec![str(), int(), int()]).ok(),
+            term("(str.substr s n n)", SmtSort::Str)
+        );
+        assert!(enc.theory_app("strLen", vec![int()]).is_err());
+
+        assert_eq!(
+            enc.theory_app("bvadd", vec![bv(8), bv(8)]).ok(),
+            term("(bvadd b b)", SmtSort::BitVec(8))
+        );
+        assert_eq!(
+            enc.theory_app("concat", vec![bv(8), bv(16)]).ok(),
+            term("(concat b b)", SmtSort::BitVec(24))
+        );
+        assert!(enc.theory_app("bvadd", vec![bv(8), bv(16)]).is_err());
+
+        assert_eq!(
+            enc.theory_app("Set_sng", vec![int()]).ok(),
+            term("(store ((as const (Array Int Bool)) false) n true)", SmtSort::set(SmtSort::Int))
+        );
+        assert!(enc.theory_app("unknown", vec![int()]).is_err());
+    }
+
+    #[test]
+    fn encode_sized_bv_app() {
+        let int = ("n".to_string(), SmtSort::Int);
+        let bv = |size| ("b".to_string(), SmtSort::BitVec(size));
+        let app = |name, arg| Encoder::sized_bv_app(name, &[arg]).ok();
+
+        assert_eq!(app("int_to_bv8", int.clone()), term("((_ int2bv 8) n)", SmtSort::BitVec(8)));
+        assert_eq!(app("bv16_to_int", bv(16)), term("(bv2nat b)", SmtSort::Int));
+        assert_eq!(
+            app("app (_ zero_extend 24)", bv(8)),
+            term("((_ zero_extend 24) b)", SmtSort::BitVec(32))
+        );
+        assert_eq!(
+            app("app (_ extract 15 8)", bv(32)),
+            term("((_ extract 15 8) b)", SmtSort::BitVec(8))
+        );
+
+        // Sizes must agree with the sort of the argument
+        assert!(app("bv16_to_int", bv(32)).is_none());
+        assert!(app("int_to_bv8", bv(8)).is_none());
+        assert!(app("app (_ extract 32 0)", bv(32)).is_none());
+        assert!(app("app (_ extract 0 1)", bv(32)).is_none());
+        assert!(app("int_to_bvx", int).is_none());
+    }
+}
@@ -0,0 +1,165 @@
+//! A parser for the s-expressions used in the textual format of fixpoint tasks and solutions.
+
+use std::{fmt, io};
+
+use itertools::Itertools;
+
+/// An s-expression in the fixpoint format. String literals are kept as atoms including the quotes.
+#[derive(Clone)]
+pub(crate) enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+impl fmt::Display for Sexp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sexp::Atom(atom) => write!(f, "{atom}"),
+            Sexp::List(items) => write!(f, "({})", items.iter().format(" ")),
+        }
+    }
+}
+
+impl Sexp {
+    /// Replaces every atom for which `f` returns `Some`
+    pub(crate) fn rename_atoms(&mut self, f: &impl Fn(&str) -> Option<String>) {
+        match self {
+            Sexp::Atom(atom) => {
+                if let Some(new) = f(atom) {
+                    *atom = new;
+                }
+            }
+            Sexp::List(items) => {
+                for item in items {
+                    item.rename_atoms(f);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        Parser { text, pos: 0 }
+    }
+
+    /// Parses a single s-expression
+    pub(crate) fn parse(&mut self) -> io::Result<Sexp> {
+        self.skip_trivia();
+        match self.peek() {
+            None => Err(invalid_data("unexpected end of input")),
+            Some(')') => Err(invalid_data("unbalanced parentheses")),
+            Some('(') => {
+                self.pos += 1;
+                let mut items = vec![];
+                loop {
+                    self.skip_trivia();
+                    if self.peek() == Some(')') {
+                        self.pos += 1;
+                        return Ok(Sexp::List(items));
+                    }
+                    items.push(self.parse()?);
+                }
+            }
+            Some('"') => {
+                let start = self.pos;
+                self.pos += 1;
+                let len = self.text[self.pos..]
+                    .find('"')
+                    .ok_or_else(|| invalid_data("unterminated string literal"))?;
+                self.pos += len + 1;
+                Ok(Sexp::Atom(self.text[start..self.pos].to_string()))
+            }
+            Some(_) => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';') {
+                        break;
+                    }
+                    self.pos += c.len_utf8();
+                }
+                Ok(Sexp::Atom(self.text[start..self.pos].to_string()))
+            }
+        }
+    }
+
+    /// Skips whitespace and comments returning the position of the next token
+    pub(crate) fn skip_trivia(&mut self) -> usize {
+        while let Some(c) = self.peek() {
+            if c == ';' {
+                let rest = &self.text[self.pos..];
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        self.pos
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("malformed s-expression: {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> io::Result<String> {
+        Ok(Parser::new(text).parse()?.to_string())
+    }
+
+    #[test]
+    fn atoms_and_lists() {
+        assert_eq!(parse("x").unwrap(), "x");
+        assert_eq!(parse("  (f (g x)   y)").unwrap(), "(f (g x) y)");
+        assert_eq!(parse("(karg$0>=0)").unwrap(), "(karg$0>=0)");
+        assert_eq!(parse("()").unwrap(), "()");
+    }
+
+    #[test]
+    fn string_literals_keep_quotes() {
+        assert_eq!(parse(r#"(= x "a (b) ;c")"#).unwrap(), r#"(= x "a (b) ;c")"#);
+        assert_eq!(parse(r#"(f"a"x)"#).unwrap(), r#"(f "a" x)"#);
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        let text = "; a comment\n(f ; another one\n x)";
+        assert_eq!(parse(text).unwrap(), "(f x)");
+    }
+
+    #[test]
+    fn parse_several() {
+        let mut parser = Parser::new("(a b) c\n;; end");
+        assert_eq!(parser.parse().unwrap().to_string(), "(a b)");
+        assert_eq!(parser.parse().unwrap().to_string(), "c");
+        assert_eq!(parser.skip_trivia(), "(a b) c\n;; end".len());
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn malformed() {
+        for text in ["", "(a b", ")", r#"(a "b)"#] {
+            assert_eq!(parse(text).unwrap_err().kind(), io::ErrorKind::InvalidData, "{text}");
+        }
+    }
+
+    #[test]
+    fn rename() {
+        let mut sexp = Parser::new("(f x (g x y))").parse().unwrap();
+        sexp.rename_atoms(&|atom| (atom == "x").then(|| "z".to_string()));
+        assert_eq!(sexp.to_string(), "(f z (g z y))");
+    }
+}
@@ -0,0 +1,289 @@
+//! Delta debugging for fixpoint tasks.
+//!
+//! Given the textual representation of a [`Task`] and a predicate deciding whether a task is
+//! *interesting* (e.g., it makes `fixpoint` crash), [`shrink`] searches for a smaller task that is
+//! still interesting. Only the constraint is reduced: declarations, qualifiers and kvars are kept
+//! as they are. The constraint is reduced by
+//! * removing conjuncts of `and` nodes, and
+//! * weakening the hypothesis of `forall` nodes to `true`.
+//!
+//! The search is greedy: a reduction is kept as soon as the resulting task is interesting and we
+//! stop when no single reduction is interesting anymore.
+//!
+//! [`Task`]: crate::Task
+
+use std::{fmt, io};
+
+use crate::sexp::{Parser, Sexp};
+
+/// Shrinks the task in `text` while `is_interesting` holds. The task in `text` is assumed to be
+/// interesting. Returns the text of the smallest task found.
+pub fn shrink(text: &str, mut is_interesting: impl FnMut(&str) -> bool) -> io::Result<String> {
+    let (prefix, mut cstr) = split_task(text)?;
+    let mut test = |cstr: &Node| is_interesting(&format!("{prefix}{}", Constraint(cstr)));
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let mut idx = 0;
+        while let Some(node) = cstr.nth(idx) {
+            match node {
+                Node::And(children) => {
+                    let len = children.len();
+                    changed |= shrink_conj(&mut cstr, idx, len, &mut test);
+                }
+                Node::ForAll(_, pred, _) if !is_true(pred) => {
+                    let mut candidate = cstr.clone();
+                    if let Some(Node::ForAll(_, pred, _)) = candidate.nth_mut(idx) {
+                        *pred = truth();
+                    }
+                    if test(&candidate) {
+                        cstr = candidate;
+                        changed = true;
+                    }
+                }
+                _ => {}
+            }
+            idx += 1;
+        }
+    }
+    Ok(format!("{prefix}{}", Constraint(&cstr)))
+}
+
+/// Removes chunks of conjuncts from the `and` node at position `idx`, starting with chunks of half
+/// the size of the conjunction and halving the size when no chunk can be removed.
+fn shrink_conj(
+    cstr: &mut Node,
+    idx: usize,
+    len: usize,
+    test: &mut impl FnMut(&Node) -> bool,
+) -> bool {
+    let mut changed = false;
+    let mut chunk = usize::max(len / 2, 1);
+    loop {
+        let mut start = 0;
+        while start < conj_len(cstr, idx) {
+            let mut candidate = cstr.clone();
+            if let Some(Node::And(children)) = candidate.nth_mut(idx) {
+                let end = usize::min(start + chunk, children.len());
+                children.drain(start..end);
+            }
+            if test(&candidate) {
+                *cstr = candidate;
+                changed = true;
+            } else {
+                start += chunk;
+            }
+        }
+        if chunk == 1 {
+            return changed;
+        }
+        chunk /= 2;
+    }
+}
+
+fn conj_len(cstr: &Node, idx: usize) -> usize {
+    match cstr.nth(idx) {
+        Some(Node::And(children)) => children.len(),
+        _ => 0,
+    }
+}
+
+/// Splits the task into the text preceding the constraint and the constraint itself
+fn split_task(text: &str) -> io::Result<(&str, Node)> {
+    let mut parser = Parser::new(text);
+    loop {
+        let start = parser.skip_trivia();
+        if start >= text.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "malformed task: the task doesn't have a constraint",
+            ));
+        }
+        if let Sexp::List(items) = parser.parse()? {
+            if let [Sexp::Atom(head), cstr] = &items[..] {
+                if head == "constraint" {
+                    return Ok((&text[..start], Node::from_sexp(cstr)));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Node {
+    And(Vec<Node>),
+    /// A binder (variable and sort), the hypothesis, and the body
+    ForAll(Sexp, Sexp, Box<Node>),
+    Head(Sexp),
+}
+
+impl Node {
+    fn from_sexp(sexp: &Sexp) -> Node {
+        if let Sexp::List(items) = sexp {
+            match &items[..] {
+                [Sexp::Atom(head), conjuncts @ ..] if head == "and" => {
+                    return Node::And(conjuncts.iter().map(Node::from_sexp).collect());
+                }
+                [Sexp::Atom(head), Sexp::List(binder), body] if head == "forall" => {
+                    if let [var, pred] = &binder[..] {
+                        return Node::ForAll(
+                            var.clone(),
+                            pred.clone(),
+                            Box::new(Node::from_sexp(body)),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        Node::Head(sexp.clone())
+    }
+
+    /// Returns the `n`-th node in preorder
+    fn nth(&self, n: usize) -> Option<&Node> {
+        self.find(&mut { n })
+    }
+
+    fn find(&self, n: &mut usize) -> Option<&Node> {
+        if *n == 0 {
+            return Some(self);
+        }
+        *n -= 1;
+        match self {
+            Node::And(children) => children.iter().find_map(|child| child.find(n)),
+            Node::ForAll(_, _, body) => body.find(n),
+            Node::Head(_) => None,
+        }
+    }
+
+    fn nth_mut(&mut self, n: usize) -> Option<&mut Node> {
+        self.find_mut(&mut { n })
+    }
+
+    fn find_mut(&mut self, n: &mut usize) -> Option<&mut Node> {
+        if *n == 0 {
+            return Some(self);
+        }
+        *n -= 1;
+        match self {
+            Node::And(children) => children.iter_mut().find_map(|child| child.find_mut(n)),
+            Node::ForAll(_, _, body) => body.find_mut(n),
+            Node::Head(_) => None,
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, level: usize) -> fmt::Result {
+        match self {
+            Node::And(children) => {
+                match &children[..] {
+                    [] => write!(f, "{}", truth()),
+                    [child] => child.fmt_indented(f, level),
+                    children => {
+                        write!(f, "(and")?;
+                        for child in children {
+                            newline(f, level + 1)?;
+                            child.fmt_indented(f, level + 1)?;
+                        }
+                        write!(f, ")")
+                    }
+                }
+            }
+            Node::ForAll(var, pred, body) => {
+                write!(f, "(forall ({var} {pred})")?;
+                newline(f, level + 1)?;
+                body.fmt_indented(f, level + 1)?;
+                write!(f, ")")
+            }
+            Node::Head(sexp) => write!(f, "{sexp}"),
+        }
+    }
+}
+
+/// Indents like the [`Display`] implementation for [`Task`] so an unchanged task prints back the
+/// same.
+///
+/// [`Display`]: fmt::Display
+/// [`Task`]: crate::Task
+fn newline(f: &mut fmt::Formatter<'_>, level: usize) -> fmt::Result {
+    writeln!(f)?;
+    write!(f, "{:1$}", "", level)
+}
+
+fn is_true(pred: &Sexp) -> bool {
+    pred.to_string() == truth().to_string()
+}
+
+/// The predicate `true` as printed by the [`Display`] implementation for [`Pred`].
+///
+/// [`Display`]: fmt::Display
+/// [`Pred`]: crate::Pred
+fn truth() -> Sexp {
+    Sexp::List(vec![Sexp::List(vec![Sexp::Atom("true".to_string())])])
+}
+
+struct Constraint<'a>(&'a Node);
+
+impl fmt::Display for Constraint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(constraint")?;
+        newline(f, 1)?;
+        self.0.fmt_indented(f, 1)?;
+        writeln!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASK: &str = "\
+(fixpoint \"--rewrite\")
+;; Tag 0: (a1 > 0)
+
+(qualif EqZero ((v int)) ((v = 0)))
+(constant f (func 0 (int) int))
+(var $k0 (int)) ;; orig: a0
+
+(constraint
+ (forall ((a0 int) ((true)))
+  (forall ((a1 int) ((a1 > 0)))
+   (and
+    (tag ((a1 > 0)) \"0\")
+    (tag ((a0 = a0)) \"1\")
+    ((f a1) > 0)))))
+";
+
+    #[test]
+    fn split_and_print_round_trip() {
+        let (prefix, cstr) = split_task(TASK).unwrap();
+        assert!(prefix.ends_with(";; orig: a0\n\n"));
+        assert_eq!(format!("{prefix}{}", Constraint(&cstr)), TASK);
+    }
+
+    #[test]
+    fn split_without_constraint() {
+        let err = split_task("(constant f int)\n").err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn shrink_to_interesting_conjunct() {
+        let shrunk = shrink(TASK, |task| task.contains("\"1\"")).unwrap();
+        let (prefix, _) = split_task(TASK).unwrap();
+        let expected = "\
+(constraint
+ (forall ((a0 int) ((true)))
+  (forall ((a1 int) ((true)))
+   (tag ((a0 = a0)) \"1\"))))
+";
+        assert_eq!(shrunk, format!("{prefix}{expected}"));
+    }
+
+    #[test]
+    fn shrink_without_interesting_reductions() {
+        // Every reduction changes the task, so nothing can be removed
+        let shrunk = shrink(TASK, |task| task == TASK).unwrap();
+        assert_eq!(shrunk, TASK);
+    }
+}
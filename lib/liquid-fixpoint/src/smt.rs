@@ -0,0 +1,405 @@
+//! A minimal interface to an SMT solver process speaking [SMT-LIB2]. This is used by the [native]
+//! backend to discharge validity queries.
+//!
+//! [SMT-LIB2]: https://smtlib.cs.uiowa.edu/language.shtml
+//! [native]: crate::native
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    time::Instant,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    limits,
+    sexp::{Parser, Sexp},
+    ResourceLimits, SmtSolver,
+};
+
+/// A sort in the SMT-LIB2 language. Parametric sorts are only used to describe the signature of
+/// polymorphic functions and datatypes and they are always instantiated before being sent to the
+/// solver.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) enum SmtSort {
+    Int,
+    Bool,
+    Real,
+    Str,
+    BitVec(usize),
+    Array(Box<[SmtSort; 2]>),
+    Seq(Box<SmtSort>),
+    Data(String, Vec<SmtSort>),
+    Func(Vec<SmtSort>, Box<SmtSort>),
+    Param(usize),
+}
+
+impl SmtSort {
+    pub(crate) fn set(elem: SmtSort) -> Self {
+        SmtSort::Array(Box::new([elem, SmtSort::Bool]))
+    }
+
+    /// Substitutes [`SmtSort::Param`] with the corresponding element in `args`
+    pub(crate) fn subst(&self, args: &[SmtSort]) -> SmtSort {
+        match self {
+            SmtSort::Param(i) => args[*i].clone(),
+            SmtSort::Array(sorts) => {
+                let [s1, s2] = &**sorts;
+                SmtSort::Array(Box::new([s1.subst(args), s2.subst(args)]))
+            }
+            SmtSort::Seq(elem) => SmtSort::Seq(Box::new(elem.subst(args))),
+            SmtSort::Data(name, sorts) => {
+                SmtSort::Data(name.clone(), sorts.iter().map(|s| s.subst(args)).collect())
+            }
+            SmtSort::Func(inputs, output) => {
+                SmtSort::Func(
+                    inputs.iter().map(|s| s.subst(args)).collect(),
+                    Box::new(output.subst(args)),
+                )
+            }
+            SmtSort::Int | SmtSort::Bool | SmtSort::Real | SmtSort::Str | SmtSort::BitVec(_) => {
+                self.clone()
+            }
+        }
+    }
+
+    /// Matches `self` (which may contain parameters) against a concrete `sort`, extending `subst`
+    /// with an assignment for the parameters. Returns `false` if the sorts cannot be matched.
+    pub(crate) fn unify(&self, sort: &SmtSort, subst: &mut Vec<Option<SmtSort>>) -> bool {
+        match (self, sort) {
+            (SmtSort::Param(i), _) => {
+                if subst.len() <= *i {
+                    subst.resize(*i + 1, None);
+                }
+                match &subst[*i] {
+                    Some(s) => s == sort,
+                    None => {
+                        subst[*i] = Some(sort.clone());
+                        true
+                    }
+                }
+            }
+            (SmtSort::Array(sorts1), SmtSort::Array(sorts2)) => {
+                iter_unify(sorts1.iter(), sorts2.iter(), subst)
+            }
+            (SmtSort::Seq(elem1), SmtSort::Seq(elem2)) => elem1.unify(elem2, subst),
+            (SmtSort::Data(name1, sorts1), SmtSort::Data(name2, sorts2)) => {
+                name1 == name2
+                    && sorts1.len() == sorts2.len()
+                    && iter_unify(sorts1.iter(), sorts2.iter(), subst)
+            }
+            (SmtSort::Func(inputs1, output1), SmtSort::Func(inputs2, output2)) => {
+                inputs1.len() == inputs2.len()
+                    && iter_unify(inputs1.iter(), inputs2.iter(), subst)
+                    && output1.unify(output2, subst)
+            }
+            _ => self == sort,
+        }
+    }
+}
+
+fn iter_unify<'a>(
+    sorts1: impl Iterator<Item = &'a SmtSort>,
+    sorts2: impl Iterator<Item = &'a SmtSort>,
+    subst: &mut Vec<Option<SmtSort>>,
+) -> bool {
+    std::iter::zip(sorts1, sorts2).all(|(s1, s2)| s1.unify(s2, subst))
+}
+
+impl fmt::Display for SmtSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SmtSort::Int => write!(f, "Int"),
+            SmtSort::Bool => write!(f, "Bool"),
+            SmtSort::Real => write!(f, "Real"),
+            SmtSort::Str => write!(f, "String"),
+            SmtSort::BitVec(size) => write!(f, "(_ BitVec {size})"),
+            SmtSort::Array(sorts) => {
+                let [s1, s2] = &**sorts;
+                write!(f, "(Array {s1} {s2})")
+            }
+            SmtSort::Seq(elem) => write!(f, "(Seq {elem})"),
+            SmtSort::Data(name, sorts) => {
+                if sorts.is_empty() {
+                    write!(f, "{name}")
+                } else {
+                    write!(f, "({name} {})", sorts.iter().format(" "))
+                }
+            }
+            SmtSort::Func(inputs, output) => {
+                write!(f, "(-> {} {output})", inputs.iter().format(" "))
+            }
+            SmtSort::Param(i) => write!(f, "T{i}"),
+        }
+    }
+}
+
+pub(crate) enum SatResult {
+    Sat,
+    Unsat,
+    Unknown,
+}
+
+/// A running SMT solver accepting commands on its standard input.
+pub(crate) struct SmtProcess {
+    child: Child,
+    stdin: BufWriter<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    solver: SmtSolver,
+    /// Time after which `check-sat` commands fail with [`io::ErrorKind::TimedOut`]
+    deadline: Option<Instant>,
+    memory_limited: bool,
+    /// Number of `check-sat` commands issued so far
+    pub(crate) num_checks: i32,
+}
+
+impl SmtProcess {
+    pub(crate) fn spawn(solver: SmtSolver, limits: ResourceLimits) -> io::Result<Self> {
+        let cmd = match solver {
+            SmtSolver::Z3 => {
+                let mut cmd = Command::new("z3");
+                cmd.arg("-in").arg("-smt2");
+                cmd
+            }
+            SmtSolver::CVC5 => {
+                let mut cmd = Command::new("cvc5");
+                cmd.arg("--lang=smt2").arg("--incremental");
+                cmd
+            }
+        };
+        Self::spawn_command(cmd, solver, limits)
+    }
+
+    /// Spawns `cmd` as the solver process. `solver` determines the dialect used for options.
+    fn spawn_command(
+        mut cmd: Command,
+        solver: SmtSolver,
+        limits: ResourceLimits,
+    ) -> io::Result<Self> {
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        limits.apply(&mut cmd);
+        let mut child = cmd.spawn()?;
+        let stdin = BufWriter::new(child.stdin.take().unwrap());
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut smt = SmtProcess {
+            child,
+            stdin,
+            stdout,
+            solver,
+            deadline: limits.deadline(),
+            memory_limited: limits.memory.is_some(),
+            num_checks: 0,
+        };
+
+        // Declarations must survive `pop` because binders are declared lazily while checking each
+        // clause and we reuse fresh names across the whole task.
+        smt.send("(set-option :global-declarations true)")?;
+        smt.send("(set-option :produce-models true)")?;
+        smt.send("(set-logic ALL)")?;
+        Ok(smt)
+    }
+
+    pub(crate) fn send(&mut self, cmd: impl fmt::Display) -> io::Result<()> {
+        writeln!(self.stdin, "{cmd}")
+    }
+
+    pub(crate) fn push(&mut self) -> io::Result<()> {
+        self.send("(push 1)")
+    }
+
+    pub(crate) fn pop(&mut self) -> io::Result<()> {
+        self.send("(pop 1)")
+    }
+
+    pub(crate) fn assert(&mut self, formula: &str) -> io::Result<()> {
+        self.send(format_args!("(assert {formula})"))
+    }
+
+    pub(crate) fn check_sat(&mut self) -> io::Result<SatResult> {
+        // The solver gives up on a query that takes longer than the time left, so the deadline
+        // is respected even if a single query doesn't terminate.
+        if let Some(deadline) = self.deadline {
+            let remaining = limits::remaining(deadline).ok_or_else(limits::timed_out)?;
+            let millis = remaining.as_millis();
+            match self.solver {
+                SmtSolver::Z3 => self.send(format_args!("(set-option :timeout {millis})"))?,
+                SmtSolver::CVC5 => self.send(format_args!("(set-option :tlimit-per {millis})"))?,
+            }
+        }
+        self.num_checks += 1;
+        self.send("(check-sat)")?;
+        self.stdin.flush()?;
+        loop {
+            let line = self.read_line()?;
+            match line.trim() {
+                "sat" => return Ok(SatResult::Sat),
+                "unsat" => return Ok(SatResult::Unsat),
+                "unknown"
+                    if self
+                        .deadline
+                        .is_some_and(|d| limits::remaining(d).is_none()) =>
+                {
+                    return Err(limits::timed_out());
+                }
+                "unknown" => return Ok(SatResult::Unknown),
+                "" => {}
+                line if line.starts_with("(error") && line.contains("out of memory") => {
+                    return Err(limits::out_of_memory());
+                }
+                line if line.starts_with("(error") => {
+                    return Err(io::Error::other(format!("smt solver error: {line}")));
+                }
+                line => {
+                    return Err(io::Error::other(format!("unexpected smt solver output: {line}")));
+                }
+            }
+        }
+    }
+
+    /// Returns the value of each term in the model produced by the last `check-sat`. This must
+    /// only be called after a `check-sat` returned [`SatResult::Sat`].
+    pub(crate) fn get_values(&mut self, terms: &[&str]) -> io::Result<Vec<String>> {
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+        self.send(format_args!("(get-value ({}))", terms.iter().format(" ")))?;
+        self.stdin.flush()?;
+
+        // The response may span multiple lines so we read until parentheses are balanced
+        let mut response = String::new();
+        let mut depth = 0;
+        loop {
+            let line = self.read_line()?;
+            if response.is_empty() && line.trim().starts_with("(error") {
+                return Err(io::Error::other(format!("smt solver error: {}", line.trim())));
+            }
+            for c in line.chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+            }
+            response.push_str(&line);
+            if depth <= 0 && !response.trim().is_empty() {
+                break;
+            }
+        }
+
+        // The response has the form `((t1 v1) (t2 v2) ...)`
+        let Sexp::List(pairs) = Parser::new(&response).parse()? else {
+            return Err(io::Error::other(format!("unexpected smt solver output: {response}")));
+        };
+        pairs
+            .into_iter()
+            .map(|pair| {
+                match pair {
+                    Sexp::List(mut pair) if pair.len() == 2 => Ok(pair.pop().unwrap().to_string()),
+                    _ => Err(io::Error::other(format!("unexpected smt solver output: {response}"))),
+                }
+            })
+            .collect()
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            // As with `fixpoint`, running out of memory is the most likely reason for the solver
+            // to die under a memory limit.
+            if self.memory_limited {
+                return Err(limits::out_of_memory());
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "smt solver terminated unexpectedly",
+            ));
+        }
+        Ok(line)
+    }
+}
+
+/// A fake solver for tests: a shell script that runs `on_check_sat` for every `check-sat` command
+/// and ignores every other command.
+#[cfg(all(test, unix))]
+impl SmtProcess {
+    pub(crate) fn fake(on_check_sat: &str, limits: ResourceLimits) -> io::Result<Self> {
+        let script = format!(
+            r#"while read -r cmd; do
+                case "$cmd" in
+                    "(exit)") exit 0 ;;
+                    "(check-sat)") {on_check_sat} ;;
+                esac
+            done"#
+        );
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script);
+        Self::spawn_command(cmd, SmtSolver::Z3, limits)
+    }
+}
+
+impl Drop for SmtProcess {
+    fn drop(&mut self) {
+        let _ = self.send("(exit)");
+        let _ = self.stdin.flush();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn timeout(millis: u64) -> ResourceLimits {
+        ResourceLimits { timeout: Some(Duration::from_millis(millis)), memory: None }
+    }
+
+    #[test]
+    fn check_sat_results() {
+        let mut smt = SmtProcess::fake("echo unsat", ResourceLimits::default()).unwrap();
+        assert!(matches!(smt.check_sat().unwrap(), SatResult::Unsat));
+        assert!(matches!(smt.check_sat().unwrap(), SatResult::Unsat));
+        assert_eq!(smt.num_checks, 2);
+
+        let mut smt = SmtProcess::fake("echo sat", ResourceLimits::default()).unwrap();
+        assert!(matches!(smt.check_sat().unwrap(), SatResult::Sat));
+    }
+
+    #[test]
+    fn unknown_before_deadline() {
+        let mut smt = SmtProcess::fake("echo unknown", timeout(60_000)).unwrap();
+        assert!(matches!(smt.check_sat().unwrap(), SatResult::Unknown));
+    }
+
+    #[test]
+    fn unknown_after_deadline_times_out() {
+        // The solver gives up exactly when the deadline passes
+        let mut smt = SmtProcess::fake("sleep 0.2; echo unknown", timeout(100)).unwrap();
+        let err = smt.check_sat().err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn deadline_already_passed() {
+        let mut smt = SmtProcess::fake("echo unsat", timeout(0)).unwrap();
+        let err = smt.check_sat().err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(smt.num_checks, 0);
+    }
+
+    #[test]
+    fn solver_errors() {
+        let mut smt =
+            SmtProcess::fake("echo '(error \"out of memory\")'", ResourceLimits::default())
+                .unwrap();
+        assert_eq!(smt.check_sat().err().unwrap().kind(), io::ErrorKind::OutOfMemory);
+
+        let mut smt = SmtProcess::fake("exit 1", ResourceLimits::default()).unwrap();
+        assert_eq!(smt.check_sat().err().unwrap().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,319 @@
+//! The solution found for the kvars in a task.
+//!
+//! A solution is reported as a [`KVarBind`] per kvar: the name of the kvar and a predicate in the
+//! same syntax used to print a [`Task`]. The native backend names the `i`-th parameter of the kvar
+//! `karg$i`, and the `fixpoint` binary uses names starting with `lq_karg$` and ending with `i`.
+//! Binary operators may be written prefix or infix, and the `fixpoint` binary writes conjunctions
+//! and disjunctions with `&&` and `||`. [`KVarBind::parse`] turns this textual representation back
+//! into an [`Expr`] using a [`FromSolution`] implementation to map names and literals to the types
+//! of the task.
+//!
+//! [`Task`]: crate::Task
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sexp::{Parser, Sexp},
+    BinOp, BinRel, Constant, Expr, Types,
+};
+
+const KVAR_PARAM_PREFIX: &str = "karg$";
+
+/// The prefix of the names used by the `fixpoint` binary for the parameters of a kvar
+const FIXPOINT_KVAR_PARAM_PREFIX: &str = "lq_karg$";
+
+/// The name used for the `idx`-th parameter of a kvar in a [`KVarBind`]
+pub(crate) fn kvar_param_name(idx: usize) -> String {
+    format!("{KVAR_PARAM_PREFIX}{idx}")
+}
+
+/// The solution for a kvar as reported by the solver. `val` is a predicate in the syntax used to
+/// print a [`Task`] (see the [module docs] for how parameters are named).
+///
+/// [module docs]: self
+/// [`Task`]: crate::Task
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KVarBind {
+    pub kvar: String,
+    pub val: String,
+}
+
+/// The solution for a kvar parsed into the types of a task
+pub struct KVarSolution<T: Types> {
+    pub kvar: T::KVar,
+    /// The solution is the conjunction of these expressions. An empty list means `true`.
+    pub conjuncts: Vec<Expr<T>>,
+}
+
+/// Maps the names and literals in a solution back to the types of a task. Returning `None`
+/// means the name or literal cannot be represented, which makes parsing fail.
+pub trait FromSolution<T: Types> {
+    fn kvar(&mut self, name: &str) -> Option<T::KVar>;
+
+    /// The `idx`-th parameter of the kvar whose solution is being parsed
+    fn kvar_param(&mut self, idx: usize) -> Option<T::Var>;
+
+    fn var(&mut self, name: &str) -> Option<T::Var>;
+
+    fn numeral(&mut self, digits: &str) -> Option<T::Numeral>;
+
+    fn decimal(&mut self, digits: &str) -> Option<T::Decimal>;
+
+    /// The contents of a string literal without the quotes
+    fn string(&mut self, s: &str) -> Option<T::String>;
+}
+
+impl KVarBind {
+    pub fn parse<T: Types>(&self, cx: &mut impl FromSolution<T>) -> io::Result<KVarSolution<T>> {
+        let kvar = cx
+            .kvar(&self.kvar)
+            .ok_or_else(|| invalid_solution(format!("unknown kvar `{}`", self.kvar)))?;
+        let sexp = Parser::new(&self.val).parse()?;
+        let conjuncts = match expr_from_sexp(&sexp, cx)? {
+            Expr::And(conjuncts) => conjuncts,
+            Expr::Constant(Constant::Boolean(true)) => vec![],
+            expr => vec![expr],
+        };
+        Ok(KVarSolution { kvar, conjuncts })
+    }
+}
+
+/// Removes redundant parentheses, e.g., `((x > 0))` is parsed as `x > 0`.
+fn unparen(mut sexp: &Sexp) -> &Sexp {
+    while let Sexp::List(items) = sexp {
+        let [inner] = &items[..] else { break };
+        sexp = inner;
+    }
+    sexp
+}
+
+fn expr_from_sexp<T: Types>(sexp: &Sexp, cx: &mut impl FromSolution<T>) -> io::Result<Expr<T>> {
+    match unparen(sexp) {
+        Sexp::Atom(atom) => atom_from_str(atom, cx),
+        Sexp::List(items) => {
+            // `fixpoint` prints conjunctions and disjunctions infix, e.g., `(p && q && r)`
+            if let Some(op) = infix_chain(items) {
+                let args = items
+                    .iter()
+                    .step_by(2)
+                    .map(|arg| expr_from_sexp(arg, cx))
+                    .collect::<io::Result<_>>()?;
+                return Ok(if op == "&&" { Expr::And(args) } else { Expr::Or(args) });
+            }
+            // `fixpoint` prints binary operators infix, e.g., `(x >= 0)`
+            if let [lhs, Sexp::Atom(op), rhs] = &items[..] {
+                if !matches!(lhs, Sexp::Atom(head) if is_operator(head)) && is_operator(op) {
+                    let args = vec![expr_from_sexp(lhs, cx)?, expr_from_sexp(rhs, cx)?];
+                    return operator_app(op, args)
+                        .map_err(|_| invalid_solution(format!("invalid operator `{op}`")));
+                }
+            }
+            let Some((head, args)) = items.split_first() else {
+                return Err(invalid_solution("empty application".to_string()));
+            };
+            let args = args
+                .iter()
+                .map(|arg| expr_from_sexp(arg, cx))
+                .collect::<io::Result<Vec<_>>>()?;
+            match head {
+                Sexp::Atom(op) => {
+                    operator_app(op, args)
+                        .or_else(|args| Ok(Expr::App(Box::new(atom_from_str(op, cx)?), args)))
+                }
+                Sexp::List(_) => Ok(Expr::App(Box::new(expr_from_sexp(head, cx)?), args)),
+            }
+        }
+    }
+}
+
+/// If `items` are operands separated by the same `&&` or `||` operator, returns the operator.
+fn infix_chain(items: &[Sexp]) -> Option<&str> {
+    let Some(Sexp::Atom(op)) = items.get(1) else { return None };
+    let op = op.as_str();
+    let is_chain = matches!(op, "&&" | "||")
+        && items.len() % 2 == 1
+        && items
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .all(|item| matches!(item, Sexp::Atom(atom) if atom == op));
+    is_chain.then_some(op)
+}
+
+/// Builds the application of a built-in operator or gives back the arguments if `op` is not an
+/// operator taking that many arguments.
+fn operator_app<T: Types>(op: &str, args: Vec<Expr<T>>) -> Result<Expr<T>, Vec<Expr<T>>> {
+    let expr = match (op, args.len()) {
+        ("and", _) => Expr::And(args),
+        ("or", _) => Expr::Or(args),
+        ("not" | "~", 1) => {
+            let [e] = to_array(args);
+            Expr::Not(Box::new(e))
+        }
+        ("-", 1) => {
+            let [e] = to_array(args);
+            Expr::Neg(Box::new(e))
+        }
+        ("=>", 2) => Expr::Imp(Box::new(to_array(args))),
+        ("<=>", 2) => Expr::Iff(Box::new(to_array(args))),
+        ("if", 3) => Expr::IfThenElse(Box::new(to_array(args))),
+        ("+", 2) => Expr::BinaryOp(BinOp::Add, Box::new(to_array(args))),
+        ("-", 2) => Expr::BinaryOp(BinOp::Sub, Box::new(to_array(args))),
+        ("*", 2) => Expr::BinaryOp(BinOp::Mul, Box::new(to_array(args))),
+        ("/", 2) => Expr::BinaryOp(BinOp::Div, Box::new(to_array(args))),
+        ("mod", 2) => Expr::BinaryOp(BinOp::Mod, Box::new(to_array(args))),
+        ("=" | "==", 2) => Expr::Atom(BinRel::Eq, Box::new(to_array(args))),
+        ("!=", 2) => Expr::Atom(BinRel::Ne, Box::new(to_array(args))),
+        (">", 2) => Expr::Atom(BinRel::Gt, Box::new(to_array(args))),
+        (">=", 2) => Expr::Atom(BinRel::Ge, Box::new(to_array(args))),
+        ("<", 2) => Expr::Atom(BinRel::Lt, Box::new(to_array(args))),
+        ("<=", 2) => Expr::Atom(BinRel::Le, Box::new(to_array(args))),
+        _ => return Err(args),
+    };
+    Ok(expr)
+}
+
+fn is_operator(atom: &str) -> bool {
+    matches!(
+        atom,
+        "+" | "-" | "*" | "/" | "mod" | "=>" | "<=>" | "=" | "==" | "!=" | ">" | ">=" | "<" | "<="
+    )
+}
+
+/// Converts the arguments of an operator into an array. The arity is checked by [`operator_app`].
+fn to_array<T: Types, const N: usize>(args: Vec<Expr<T>>) -> [Expr<T>; N] {
+    <[_; N]>::try_from(args).unwrap_or_else(|_| unreachable!())
+}
+
+fn atom_from_str<T: Types>(atom: &str, cx: &mut impl FromSolution<T>) -> io::Result<Expr<T>> {
+    let unsupported = || invalid_solution(format!("cannot interpret `{atom}`"));
+    let expr = match atom {
+        "true" => Expr::Constant(Constant::Boolean(true)),
+        "false" => Expr::Constant(Constant::Boolean(false)),
+        _ if atom.starts_with('"') => {
+            let s = atom.trim_matches('"');
+            Expr::Constant(Constant::String(cx.string(s).ok_or_else(unsupported)?))
+        }
+        _ if atom.bytes().all(|b| b.is_ascii_digit()) => {
+            Expr::Constant(Constant::Numeral(cx.numeral(atom).ok_or_else(unsupported)?))
+        }
+        _ if atom.bytes().all(|b| b.is_ascii_digit() || b == b'.') => {
+            Expr::Constant(Constant::Decimal(cx.decimal(atom).ok_or_else(unsupported)?))
+        }
+        _ => {
+            let var = match kvar_param_idx(atom) {
+                Some(idx) => cx.kvar_param(idx),
+                None => cx.var(atom),
+            };
+            Expr::Var(var.ok_or_else(unsupported)?)
+        }
+    };
+    Ok(expr)
+}
+
+/// The index of the kvar parameter named `atom` if it is one
+fn kvar_param_idx(atom: &str) -> Option<usize> {
+    if let Some(idx) = atom.strip_prefix(KVAR_PARAM_PREFIX) {
+        return idx.parse().ok();
+    }
+    let rest = atom.strip_prefix(FIXPOINT_KVAR_PARAM_PREFIX)?;
+    let digits = rest.len() - rest.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    rest[rest.len() - digits..].parse().ok()
+}
+
+fn invalid_solution(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("malformed solution: {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DefaultTypes;
+
+    struct Cx;
+
+    impl FromSolution<DefaultTypes> for Cx {
+        fn kvar(&mut self, name: &str) -> Option<&'static str> {
+            ["k0", "k1"].into_iter().find(|kvar| *kvar == name)
+        }
+
+        fn kvar_param(&mut self, idx: usize) -> Option<&'static str> {
+            ["p0", "p1", "p2"].get(idx).copied()
+        }
+
+        fn var(&mut self, name: &str) -> Option<&'static str> {
+            ["x", "f"].into_iter().find(|var| *var == name)
+        }
+
+        fn numeral(&mut self, digits: &str) -> Option<i128> {
+            digits.parse().ok()
+        }
+
+        fn decimal(&mut self, _: &str) -> Option<i128> {
+            None
+        }
+
+        fn string(&mut self, s: &str) -> Option<String> {
+            Some(s.to_string())
+        }
+    }
+
+    fn parse(val: &str) -> io::Result<Vec<String>> {
+        let bind = KVarBind { kvar: "k0".to_string(), val: val.to_string() };
+        let sol = bind.parse(&mut Cx)?;
+        assert_eq!(sol.kvar, "k0");
+        Ok(sol.conjuncts.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn native_solution() {
+        assert_eq!(
+            parse("(and (>= karg$0 0) (= karg$1 (+ karg$0 1)))").unwrap(),
+            ["(>= p0 0)", "(= p1 (+ p0 1))"]
+        );
+    }
+
+    #[test]
+    fn fixpoint_solution() {
+        assert_eq!(
+            parse("((lq_karg$nnf_arg$##k0##0 >= 0) && (lq_karg$nnf_arg$##k0##2 != x))").unwrap(),
+            ["(>= p0 0)", "(!= p2 x)"]
+        );
+        assert_eq!(
+            parse("((~ (lq_karg$nnf_arg$##k0##1 < 0)) || (f x))").unwrap(),
+            ["(or (not (< p1 0)) (f x))"]
+        );
+    }
+
+    #[test]
+    fn trivial_solution() {
+        assert!(parse("((true))").unwrap().is_empty());
+        assert_eq!(parse("false").unwrap(), ["false"]);
+        assert_eq!(parse("(\"a b\" = \"a b\")").unwrap(), ["(= \"a b\" \"a b\")"]);
+    }
+
+    #[test]
+    fn mixed_connectives_are_not_a_chain() {
+        // Applications of an unknown function named `&&` are fine, mixing them with `||` is not
+        assert!(parse("(x && x || x)").is_err());
+    }
+
+    #[test]
+    fn malformed_solutions() {
+        for val in ["(y > 0)", "(karg$9 > 0)", "(1.5 > 0)", "(x > ", "()"] {
+            assert_eq!(parse(val).unwrap_err().kind(), io::ErrorKind::InvalidData, "{val}");
+        }
+        let bind = KVarBind { kvar: "k2".to_string(), val: "true".to_string() };
+        assert!(bind.parse(&mut Cx).is_err());
+    }
+
+    #[test]
+    fn kvar_param_names() {
+        assert_eq!(kvar_param_idx("karg$3"), Some(3));
+        assert_eq!(kvar_param_idx("lq_karg$nnf_arg$##k10##12"), Some(12));
+        assert_eq!(kvar_param_idx("lq_karg$"), None);
+        assert_eq!(kvar_param_idx("a0"), None);
+    }
+}
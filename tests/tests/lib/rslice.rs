@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use super::RVec;
+use super::{RVec, SeqVec};
 
 #[flux::opaque]
 #[flux::refined_by(len: int, valid: (int, int) -> bool)]
@@ -45,3 +45,12 @@ impl<'a, T> RSlice<'a, T> {
         unsafe { std::slice::from_raw_parts(self.data, self.len) }
     }
 }
+
+#[flux::generics(T as base)]
+impl<'a, T> RSlice<'a, T> {
+    #[flux::trusted]
+    #[flux::sig(fn(vec: &mut SeqVec<T>[@s]) -> RSlice<T>[seq_len(s), |i,j| true])]
+    pub fn from_seq_vec(vec: &mut SeqVec<T>) -> RSlice<T> {
+        RSlice::from_slice(vec.as_mut_slice())
+    }
+}
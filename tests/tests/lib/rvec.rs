@@ -1,4 +1,12 @@
 #![allow(dead_code)]
+#![flux::defs {
+    fn seq_sorted(s: Seq<int>) -> bool {
+        forall i: int, j: int. 0 <= i && i <= j && j < seq_len(s) => seq_nth(s, i) <= seq_nth(s, j)
+    }
+    fn seq_swap<T>(s: Seq<T>, i: int, j: int) -> Seq<T> {
+        seq_update(seq_update(s, i, seq_nth(s, j)), j, seq_nth(s, i))
+    }
+}]
 
 pub mod rslice;
 
@@ -191,3 +199,77 @@ impl<T> std::ops::IndexMut<usize> for RVec<T> {
         self.get_mut(index)
     }
 }
+
+/// A vector refined by the sequence of its elements. Reasoning about sequences requires the
+/// native fixpoint backend, i.e., `#![flux::opts(fixpoint_backend = "native")]`. Functions using
+/// it with the default backend are reported as errors.
+#[flux::opaque]
+#[flux::refined_by(elems: Seq<T>)]
+#[flux::invariant(0 <= seq_len(elems))]
+pub struct SeqVec<T> {
+    inner: Vec<T>,
+}
+
+#[flux::generics(T as base)]
+impl<T> SeqVec<T> {
+    #[flux::trusted]
+    #[flux::sig(fn() -> SeqVec<T>[seq_empty(0)])]
+    pub fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(self: &strg SeqVec<T>[@s], x: T) ensures self: SeqVec<T>[seq_concat(s, seq_unit(x))])]
+    pub fn push(&mut self, x: T) {
+        self.inner.push(x);
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(self: &strg SeqVec<T>[@s]) -> T[seq_nth(s, seq_len(s) - 1)]
+            requires seq_len(s) > 0
+            ensures self: SeqVec<T>[seq_extract(s, 0, seq_len(s) - 1)])]
+    pub fn pop(&mut self) -> T {
+        self.inner.pop().unwrap()
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(&SeqVec<T>[@s]) -> usize[seq_len(s)])]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(&SeqVec<T>[@s], i: usize{i < seq_len(s)}) -> &T[seq_nth(s, i)])]
+    pub fn get(&self, i: usize) -> &T {
+        &self.inner[i]
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(self: &strg SeqVec<T>[@s], i: usize{i < seq_len(s)}, x: T)
+            ensures self: SeqVec<T>[seq_update(s, i, x)])]
+    pub fn set(&mut self, i: usize, x: T) {
+        self.inner[i] = x;
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(self: &strg SeqVec<T>[@s], i: usize{i < seq_len(s)}, j: usize{j < seq_len(s)})
+            ensures self: SeqVec<T>[seq_swap(s, i, j)])]
+    pub fn swap(&mut self, i: usize, j: usize) {
+        self.inner.swap(i, j);
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(&SeqVec<T>[@s], &T[@x]) -> bool[seq_contains(s, x)])]
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.inner.contains(x)
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(&mut SeqVec<T>[@s]) -> &mut [T][seq_len(s)])]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.inner.as_mut_slice()
+    }
+}
@@ -0,0 +1,6 @@
+#![flux::opts(timeout = "soon")] //~ ERROR invalid attribute: incorrect type in value for setting `timeout`, expected u64
+
+#[flux::sig(fn(x: i32) -> i32)]
+pub fn test(x: i32) -> i32 {
+    x
+}
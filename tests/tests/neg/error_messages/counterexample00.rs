@@ -0,0 +1,11 @@
+#![flux::opts(fixpoint_backend = "native")]
+
+// Counterexamples are only reported by the native backend, the `fixpoint` binary doesn't
+// produce models for failing constraints.
+
+#[flux::sig(fn(x: i32{x <= 0 && x >= -1}) -> i32{v: v >= 0})] //~ NOTE this is the condition
+pub fn neg(x: i32) -> i32 {
+    x //~ ERROR refinement type
+      //~| NOTE a postcondition cannot be proved
+      //~| NOTE the constraint fails when `x = -1`
+}
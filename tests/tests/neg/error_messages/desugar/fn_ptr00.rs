@@ -0,0 +1,6 @@
+#![flux::defs {
+    fn g(x: int) -> bool { <fn(i32) -> i32 as MyTrait>::f(x) } //~ ERROR unsupported function pointer type
+}]
+
+#[flux::assoc(fn f(x: int) -> bool)]
+pub trait MyTrait {}
@@ -1,2 +1,5 @@
 #[flux::sig(fn(i32[0asd]))] //~ ERROR invalid suffix `asd` for number literal
 fn test00(x: i32) {}
+
+#[flux::sig(fn(f64[0.5f64]))] //~ ERROR invalid suffix `f64` for number literal
+fn test01(x: f64) {}
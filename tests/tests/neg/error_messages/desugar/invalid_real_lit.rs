@@ -0,0 +1,2 @@
+#[flux::sig(fn(f64[1e3]))] //~ ERROR invalid real literal
+fn test00(x: f64) {}
@@ -1,5 +1,5 @@
 #![flux::defs {
-    fn sum(n: int) -> int { n + sum(n-1) } //~ ERROR cycle
+    fn sum(n: int) -> int { n + sum(n-1) } //~ ERROR cannot prove termination
 }]
 
 #[flux::sig(fn(x:i32) -> i32[x+1])]
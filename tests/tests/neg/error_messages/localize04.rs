@@ -0,0 +1,15 @@
+// Only the conjunct that cannot be proved is reported as the failing condition
+
+#[flux::sig(fn(n: i32{n > 0}) -> i32{v: v >= 0 && v < n})] //~ NOTE this is the condition
+pub fn post(n: i32) -> i32 {
+    n //~ ERROR refinement type
+      //~| NOTE a postcondition cannot be proved
+}
+
+#[flux::sig(fn(x: i32, y: i32{y > 0 && y > x}))] //~ NOTE this is the condition
+pub fn pre(_x: i32, _y: i32) {}
+
+pub fn test_pre() {
+    pre(10, 5); //~ ERROR refinement type
+                //~| NOTE a precondition cannot be proved
+}
@@ -0,0 +1,25 @@
+#[flux::reflect]
+fn sum(n: u32) -> u32 {
+    let mut s = 0;
+    let mut i = 0;
+    while i < n { //~ ERROR cannot reflect `sum`
+        s += i;
+        i += 1;
+    }
+    s
+}
+
+#[flux::reflect]
+fn first(xs: &[i32]) -> i32 { //~ ERROR cannot reflect `first`
+    xs[0]
+}
+
+#[flux::reflect]
+fn fact(n: u32) -> u32 {
+    if n == 0 { 1 } else { n * fact(n - 1) } //~ ERROR cannot reflect `fact`
+}
+
+#[flux::reflect]
+fn id<T>(x: T) -> T { //~ ERROR cannot reflect `id`
+    x
+}
@@ -0,0 +1,14 @@
+#[flux::reflect]
+#[flux::sig(fn(x: i32) -> i32)]
+fn with_sig(x: i32) -> i32 { //~ ERROR reflected functions cannot have a refinement signature
+    x
+}
+
+struct S;
+
+impl S {
+    #[flux::reflect]
+    fn method(x: i32) -> i32 { //~ ERROR only free functions can be reflected
+        x
+    }
+}
@@ -0,0 +1,9 @@
+#[flux::sig(fn(x: i32) -> i32{v: (let y = x + 1; v == y) && v > y})] //~ ERROR cannot find value `y` in this scope
+pub fn incr(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: let y = y + 1; v == y})] //~ ERROR cannot find value `y` in this scope
+pub fn incr2(x: i32) -> i32 {
+    x + 1
+}
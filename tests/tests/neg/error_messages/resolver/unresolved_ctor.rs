@@ -0,0 +1,13 @@
+#![flux::defs {
+    sort Status {
+        Idle,
+        Running(int),
+    }
+
+    fn is_idle(s: Status) -> bool {
+        match s {
+            Idle => true,
+            Stopped => false, //~ ERROR cannot find variant `Stopped`
+        }
+    }
+}]
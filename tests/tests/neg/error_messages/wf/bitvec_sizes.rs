@@ -0,0 +1,31 @@
+#[flux::opaque]
+#[flux::refined_by(x: bitvec<8>)]
+pub struct BV8(u8);
+
+#[flux::opaque]
+#[flux::refined_by(x: bitvec<16>)]
+pub struct BV16(u16);
+
+#[flux::trusted]
+#[flux::sig(fn(BV16[@x]) -> BV8[bv_zero_extend(x)])] //~ ERROR invalid bit-vector sizes
+fn shrink(x: BV16) -> BV8 {
+    BV8(x.0 as u8)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV8[@x]) -> BV16[bv_extract(x)])] //~ ERROR invalid bit-vector sizes
+fn grow(x: BV8) -> BV16 {
+    BV16(x.0 as u16)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV8[@x], BV8[@y]) -> BV8[bv_concat(x, y)])] //~ ERROR invalid bit-vector sizes
+fn concat(x: BV8, _y: BV8) -> BV8 {
+    x
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV8[@x]) -> bool[bv_bv_to_int(bv_zero_extend(x)) > 0])] //~ ERROR sort annotation needed
+fn unknown(x: BV8) -> bool {
+    x.0 > 0
+}
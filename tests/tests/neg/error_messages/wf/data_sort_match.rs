@@ -0,0 +1,28 @@
+#![flux::defs {
+    sort Status {
+        Idle,
+        Running(int),
+        Done(bool, int),
+    }
+
+    fn steps(s: Status) -> int {
+        match s { //~ ERROR non-exhaustive patterns
+            Idle => 0,
+            Running(n) => n,
+        }
+    }
+
+    fn is_done(s: Status) -> bool {
+        match s {
+            Done(b) => b, //~ ERROR this variant takes 2 refinement arguments
+            _ => false,
+        }
+    }
+
+    fn bad_field(s: Status) -> bool {
+        match s {
+            Running(n) => n, //~ ERROR mismatched sorts
+            _ => false,
+        }
+    }
+}]
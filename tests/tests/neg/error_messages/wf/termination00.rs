@@ -0,0 +1,21 @@
+#![flux::defs {
+    sort IntList {
+        Nil,
+        Cons(int, IntList),
+    }
+
+    fn len(l: IntList) -> int {
+        match l {
+            Nil => 0,
+            Cons(_, tl) => 1 + len(Cons(0, tl)), //~ ERROR cannot prove termination
+        }
+    }
+
+    fn sum(n: int) -> int {
+        if n >= 0 { n + sum(n - 1) } else { 0 } //~ ERROR cannot prove termination
+    }
+
+    fn id<T>(x: T, n: int) -> T {
+        if n > 0 { id(x, n - 1) } else { x } //~ ERROR cannot be polymorphic
+    }
+}]
@@ -0,0 +1,22 @@
+#![flux::opts(precise_bit_ops = true)]
+
+#[flux::sig(fn(u32, u32[@m]) -> u32{v: v < m})]
+pub fn mask(x: u32, m: u32) -> u32 {
+    x & m //~ ERROR refinement type
+}
+
+#[flux::sig(fn(u32[1], u32[@n]) -> u32{v: v > 0})]
+pub fn shl(x: u32, n: u32) -> u32 {
+    x << n //~ ERROR refinement type
+}
+
+#[flux::sig(fn(i32[@x], i32[@y]) -> i32{v: v >= x})]
+pub fn or_signed(x: i32, y: i32) -> i32 {
+    x | y //~ ERROR refinement type
+}
+
+#[flux::opts(precise_bit_ops = false)]
+#[flux::sig(fn(u32, u32[@m]) -> u32{v: v <= m})]
+pub fn mask_imprecise(x: u32, m: u32) -> u32 {
+    x & m //~ ERROR refinement type
+}
@@ -0,0 +1,39 @@
+#![flux::defs {
+    sort Status {
+        Idle,
+        Running(int),
+        Done(bool, int),
+    }
+
+    fn steps(s: Status) -> int {
+        match s {
+            Idle => 0,
+            Running(n) => n,
+            Done(_, n) => n,
+        }
+    }
+}]
+
+#[flux::refined_by(s: Status)]
+pub enum State {
+    #[flux::variant(State[Idle])]
+    Idle,
+    #[flux::variant((i32[@n]) -> State[Running(n)])]
+    Running(i32),
+    #[flux::variant((bool[@ok], i32[@n]) -> State[Done(ok, n)])]
+    Done(bool, i32),
+}
+
+#[flux::sig(fn(&State[@s]) -> i32[steps(s)])]
+pub fn steps(state: &State) -> i32 {
+    match state {
+        State::Idle => 0,
+        State::Running(n) => *n,
+        State::Done(_, _) => 0, //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(State[@s]) -> State[Done(true, steps(s))])]
+pub fn finish(state: State) -> State {
+    State::Done(false, steps(&state)) //~ ERROR refinement type
+}
@@ -0,0 +1,35 @@
+pub trait Validator {
+    #[flux::sig(fn(&Self, x: i32) -> bool{b: b => x >= 0})]
+    fn validate(&self, x: i32) -> bool;
+}
+
+pub struct NonNeg;
+
+impl Validator for NonNeg {
+    #[flux::sig(fn(&Self, x: i32) -> bool[x >= 0])]
+    fn validate(&self, x: i32) -> bool {
+        x >= 0
+    }
+}
+
+pub struct Any;
+
+impl Validator for Any {
+    fn validate(&self, _x: i32) -> bool {
+        //~^ ERROR refinement type
+        true
+    }
+}
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+pub fn check_box(v: Box<dyn Validator>, x: i32) {
+    if v.validate(x) {
+        assert(x > 0); //~ ERROR refinement type
+    }
+}
+
+pub fn make(strict: bool) -> Box<dyn Validator> {
+    if strict { Box::new(NonNeg) } else { Box::new(Any) }
+}
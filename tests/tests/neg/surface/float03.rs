@@ -0,0 +1,66 @@
+#[flux::sig(fn(x: f64) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn clamp01(x: f64) -> f64 {
+    if x < 0.0 {
+        0.0
+    } else {
+        x //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(x: f64{0.0 <= x && x <= 1.0}, y: f64{y >= 0.0}) -> f64{v: v <= 1.0})]
+pub fn mul01(x: f64, y: f64) -> f64 {
+    x * y //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: f32, y: f32) -> f32{v: v >= x})]
+pub fn add(x: f32, y: f32) -> f32 {
+    x + y //~ ERROR refinement type
+}
+
+// Floating-point addition rounds its result, so this doesn't hold in general
+#[flux::sig(fn(x: f64, y: f64) -> f64[x + y])]
+pub fn add_exact(x: f64, y: f64) -> f64 {
+    x + y //~ ERROR refinement type
+}
+
+// The literal `0.1` is not exactly representable as a float
+#[flux::sig(fn() -> f64[0.1])]
+pub fn tenth() -> f64 {
+    0.1 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: f64) -> bool[true])]
+pub fn lt(x: f64) -> bool {
+    x < 1.0 //~ ERROR refinement type
+}
+
+// `x == x` doesn't hold if `x` is NaN
+#[flux::sig(fn(x: f64) -> bool[true])]
+pub fn eq_self(x: f64) -> bool {
+    x == x //~ ERROR refinement type
+}
+
+pub fn eq_self_else(x: f64) {
+    if x == x {
+    } else {
+        assert(false); //~ ERROR refinement type
+    }
+}
+
+// If `x` is NaN, `x < 1.0` doesn't hold but neither does `x >= 1.0`
+#[flux::sig(fn(x: f64) -> f64{v: v >= 1.0})]
+pub fn not_lt(x: f64) -> f64 {
+    if x < 1.0 {
+        1.0
+    } else {
+        x //~ ERROR refinement type
+    }
+}
+
+#[flux::sig(fn(x: f64, y: f64) -> bool[x != y])]
+pub fn ne(x: f64, y: f64) -> bool {
+    x != y //~ ERROR refinement type
+}
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
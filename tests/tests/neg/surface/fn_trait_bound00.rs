@@ -0,0 +1,37 @@
+#[flux::sig(fn(f: F) -> i32 where F: Fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn apply_zero<F: Fn(i32) -> i32>(f: F) -> i32 {
+    f(0) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(f: F) -> i32{v: v > 0} where F: Fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn apply_pos<F: Fn(i32) -> i32>(f: F) -> i32 {
+    f(1)
+} //~ ERROR refinement type
+
+#[flux::sig(fn(f: F) -> i32{v: v >= 0} where F: Fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn apply<F: Fn(i32) -> i32>(f: F) -> i32 {
+    f(1)
+}
+
+#[flux::sig(fn(i32) -> i32)]
+pub fn id(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(g: G) -> i32 where G: Fn(i32) -> i32)]
+pub fn pass<G: Fn(i32) -> i32>(g: G) -> i32 {
+    apply(g) //~ ERROR refinement type
+}
+
+pub fn test_closure() -> i32 {
+    apply(|x| x - 2) //~ ERROR refinement type
+}
+
+pub fn test_fn_def() -> i32 {
+    apply(id) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: Option<i32>, f: F) -> Option<i32> where F: Fn(i32{v: v > 0}) -> i32)]
+pub fn map_opt<F: Fn(i32) -> i32>(x: Option<i32>, f: F) -> Option<i32> {
+    x.map(|n| f(n)) //~ ERROR refinement type
+}
@@ -0,0 +1,47 @@
+#![allow(dead_code)]
+
+#[flux::sig(fn(f: fn(i32{v: v > 0}) -> i32{v: v >= 0}, x: i32{v: v > 0}) -> i32{v: v >= 0})]
+fn apply(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+#[flux::sig(fn(i32{v: v > 10}) -> i32{v: v >= 0})]
+fn dec10(x: i32) -> i32 {
+    x - 10
+}
+
+fn id(x: i32) -> i32 {
+    x
+}
+
+fn test_pre() -> i32 {
+    apply(dec10, 5) //~ ERROR refinement type
+}
+
+fn test_post() -> i32 {
+    apply(id, 5) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(f: fn(i32{v: v > 0}) -> i32, x: i32) -> i32)]
+fn call_bad(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(f: fn(i32[@n]) -> i32{v: v > n}, x: i32) -> i32{v: v > x + 1})]
+fn apply_above(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(table: &[fn(i32) -> i32{v: v >= 0}], usize) -> i32{v: v >= 0})]
+fn dispatch(table: &[fn(i32) -> i32], i: usize) -> i32 {
+    if i < table.len() {
+        table[i](i as i32)
+    } else {
+        0
+    }
+}
+
+fn test_dispatch() -> i32 {
+    let table: [fn(i32) -> i32; 1] = [id];
+    dispatch(&table, 0) //~ ERROR refinement type
+}
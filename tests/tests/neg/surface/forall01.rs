@@ -6,8 +6,8 @@ fn assert(_: bool) {}
     requires forall y. y >= 0 => y > x
 )]
 fn requires_negative(x: i32) {
-    // this should be provable, but we are currently dropping the quantified assumption
-    assert(x <= 0); //~ ERROR refinement type
+    // the assumption only implies `x < 0`
+    assert(x < -1); //~ ERROR refinement type
 }
 
 fn test2() {
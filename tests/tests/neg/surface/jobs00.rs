@@ -0,0 +1,33 @@
+//@rustc-env:FLUX_JOBS=4
+
+// Queries are solved in the background but errors are reported as if they were solved one at a
+// time: each function gets its own errors and the body of a method is not checked if the method
+// is not a subtype of the trait method.
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+pub fn ok(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn err00(x: i32) -> i32 {
+    x //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > x})]
+pub fn err01(x: i32) -> i32 {
+    x - 1 //~ ERROR refinement type
+}
+
+pub trait Silly {
+    #[flux::sig(fn(&Self) -> i32{v: 100 < v})]
+    fn bloop(&self) -> i32;
+}
+
+impl Silly for i32 {
+    #[flux::sig(fn(&Self) -> i32[20])]
+    fn bloop(&self) -> i32 {
+        //~^ ERROR refinement type
+        10
+    }
+}
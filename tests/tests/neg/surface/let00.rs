@@ -0,0 +1,18 @@
+#![flux::defs {
+    fn in_range(x: int, lo: int, len: int) -> bool {
+        let hi = lo + len;
+        lo <= x && x < hi
+    }
+}]
+
+#[flux::sig(fn(x: i32) -> i32{v: let y = x + 1; v == y})]
+fn incr(x: i32) -> i32 {
+    x + 2 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: i32{in_range(x, 0, 10)}))]
+fn test_range(_x: i32) {}
+
+fn test() {
+    test_range(10); //~ ERROR refinement type
+}
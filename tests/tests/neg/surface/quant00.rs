@@ -0,0 +1,23 @@
+#![flux::defs {
+    fn is_even(x: int) -> bool { exists k: int. x == 2 * k }
+}]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::sig(fn(x: i32) -> i32{v: is_even(v)})]
+fn incr(x: i32) -> i32 {
+    x + 1 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: i32{exists k: int. k > 0 && x == k}))]
+fn positive(x: i32) {
+    assert(x > 1); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: i32) ensures forall i: int. i < 0 => x > i)]
+fn ensures_quant(_x: i32) {} //~ ERROR refinement type
+
+fn test() {
+    positive(0); //~ ERROR refinement type
+}
@@ -0,0 +1,39 @@
+#![flux::opts(check_raw_pointers = true)]
+
+// Load the extern specs for raw pointers shipped with `flux_rs`
+extern crate flux_rs;
+
+pub unsafe fn deref(p: *const i32) -> i32 {
+    *p //~ ERROR raw pointer may be dereferenced out of bounds
+}
+
+#[flux::sig(fn(p: *const i32[@b, 0, @n], i: usize{i <= n}) -> i32)]
+pub unsafe fn get(p: *const i32, i: usize) -> i32 {
+    unsafe { *p.add(i) } //~ ERROR raw pointer may be dereferenced out of bounds
+}
+
+#[flux::sig(fn(p: *const i32[@b, 0, @n], i: usize{i <= n + 1}) -> *const i32)]
+pub unsafe fn move_past_end(p: *const i32, i: usize) -> *const i32 {
+    unsafe { p.add(i) } //~ ERROR refinement type
+}
+
+#[flux::sig(fn(p: *mut i32[@b, 0, @n]) -> *mut i32)]
+pub unsafe fn move_before_start(p: *mut i32) -> *mut i32 {
+    unsafe { p.sub(1) } //~ ERROR refinement type
+}
+
+#[flux::sig(fn(p: *const i32[@b, @o, @n]) -> *const i32 requires o < n)]
+pub unsafe fn offset_back(p: *const i32) -> *const i32 {
+    unsafe { p.offset(-1) } //~ ERROR refinement type
+}
+
+pub fn local() -> i32 {
+    let x = 0;
+    let p = &raw const x;
+    unsafe { *p.add(1) } //~ ERROR raw pointer may be dereferenced out of bounds
+}
+
+pub fn deref_null() -> i32 {
+    let p = std::ptr::null();
+    unsafe { *p } //~ ERROR raw pointer may be dereferenced out of bounds
+}
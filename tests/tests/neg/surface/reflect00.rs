@@ -0,0 +1,26 @@
+#[flux::reflect]
+fn max(a: i32, b: i32) -> i32 {
+    if a > b { a } else { b }
+}
+
+#[flux::reflect]
+fn is_small(x: u32) -> bool {
+    x < 10
+}
+
+#[flux::sig(fn(a: i32, b: i32) -> i32{v: v == max(a, b)})]
+fn call_max(a: i32, b: i32) -> i32 {
+    a //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: u32{is_small(x)}) -> u32{v: v < 5})]
+fn small(x: u32) -> u32 {
+    x //~ ERROR refinement type
+}
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+pub fn test(x: u32) {
+    assert(is_small(x)); //~ ERROR refinement type
+}
@@ -0,0 +1,30 @@
+#![flux::opts(fixpoint_backend = "native")]
+
+#[path = "../../lib/rvec.rs"]
+mod rvec;
+use rvec::SeqVec;
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+fn test00() {
+    let mut v = SeqVec::new();
+    v.push(1);
+    v.push(2);
+    assert(*v.get(0) == 1);
+    assert(*v.get(1) == 1); //~ ERROR refinement type
+    v.get(2); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(v: &strg SeqVec<i32>[@s], i: usize{i < seq_len(s)}, j: usize{j < seq_len(s)})
+        ensures v: SeqVec<i32>[s])]
+fn swap(v: &mut SeqVec<i32>, i: usize, j: usize) {
+    v.swap(i, j);
+} //~ ERROR refinement type
+
+#[flux::sig(fn(v: &strg SeqVec<i32>{s: seq_len(s) == 2}) ensures v: SeqVec<i32>{s: seq_sorted(s)})]
+fn sort2(v: &mut SeqVec<i32>) {
+    if *v.get(0) < *v.get(1) {
+        v.swap(0, 1);
+    }
+} //~ ERROR refinement type
@@ -0,0 +1,11 @@
+// Sequences require the native backend. Using them with the default backend is an error instead of
+// silently encoding them as uninterpreted functions.
+#[path = "../../lib/rvec.rs"]
+mod rvec;
+use rvec::SeqVec;
+
+pub fn test00() -> i32 { //~ ERROR sequences are not supported
+    let mut v = SeqVec::new();
+    v.push(1);
+    *v.get(0)
+}
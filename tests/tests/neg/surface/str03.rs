@@ -0,0 +1,30 @@
+#![flux::opts(fixpoint_backend = "native")]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::trusted]
+#[flux::sig(fn(&str[@s], &str[@p]) -> bool[str_prefix_of(p, s)])]
+fn starts_with(s: &str, p: &str) -> bool {
+    s.starts_with(p)
+}
+
+#[flux::sig(fn(&str{s: str_prefix_of("GET ", s)}))]
+fn handle_get(_req: &str) {}
+
+fn dispatch(req: &str) {
+    if starts_with(req, "POST ") {
+        handle_get(req); //~ ERROR refinement type
+    }
+}
+
+fn literals() {
+    assert(starts_with("GET /index.html", "POST ")); //~ ERROR refinement type
+}
+
+#[flux::sig(fn(&str{s: str_at(s, 0) == "\""}))]
+fn quoted(_s: &str) {}
+
+fn test_quoted() {
+    quoted("abc\""); //~ ERROR refinement type
+}
@@ -0,0 +1,52 @@
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::opts(assume_panic_abort = true)]
+pub fn test00(v: Vec<i32>, x: i32) { //~ WARN cleanup code in `test00` is not checked
+    assert(x > 0); //~ ERROR refinement type
+    drop(v);
+}
+
+#[flux::opts(assume_panic_abort = true)]
+pub fn test01(x: i32) {
+    assert(x > 0); //~ ERROR refinement type
+}
+
+pub struct Pos {
+    #[flux::field(i32{v: v > 0})]
+    x: i32,
+}
+
+fn may_panic() {}
+
+#[flux::sig(fn(x: &strg i32) ensures x: i32{v: v > 0})]
+fn reset(x: &mut i32) {
+    *x = 1;
+}
+
+#[flux::sig(fn(n: i32) -> i32{v: n > 0})]
+fn check_pos(n: i32) -> i32 {
+    if n <= 0 {
+        panic!()
+    }
+    n
+}
+
+// `reset` may panic before establishing its postcondition, and then the invariant of `Pos` is
+// broken when unwinding
+#[flux::sig(fn(p: &strg Pos, v: Vec<i32>) ensures p: Pos)]
+pub fn test02(p: &mut Pos, v: Vec<i32>) { //~ ERROR type invariant may not hold
+    p.x = 0;
+    reset(&mut p.x);
+    drop(v);
+}
+
+// The cleanup code is shared by both calls, but we don't know that `n > 0` when unwinding from
+// `check_pos`
+#[flux::sig(fn(p: &strg Pos, n: i32, v: Vec<i32>) ensures p: Pos)]
+pub fn test03(p: &mut Pos, n: i32, v: Vec<i32>) { //~ ERROR type invariant may not hold
+    p.x = n;
+    check_pos(n);
+    may_panic();
+    drop(v);
+}
@@ -0,0 +1,66 @@
+#![flux::opts(precise_bit_ops = true)]
+
+#[flux::sig(fn(u32, u32[@m]) -> u32{v: v <= m})]
+pub fn mask(x: u32, m: u32) -> u32 {
+    x & m
+}
+
+#[flux::sig(fn(usize) -> usize{v: v < 64})]
+pub fn wrap(i: usize) -> usize {
+    i & 63
+}
+
+#[flux::sig(fn(u32[@x], u32[@y]) -> u32{v: v >= x && v >= y})]
+pub fn union(x: u32, y: u32) -> u32 {
+    x | y
+}
+
+#[flux::sig(fn(u64[@x]) -> u64{v: v <= x})]
+pub fn half(x: u64) -> u64 {
+    x >> 1
+}
+
+#[flux::sig(fn(u32[@x]) -> u32[4294967295 - x])]
+pub fn flip(x: u32) -> u32 {
+    !x
+}
+
+#[flux::sig(fn(i32[@x]) -> i32[-x - 1])]
+pub fn flip_signed(x: i32) -> i32 {
+    !x
+}
+
+#[flux::sig(fn(i32[@x], i32) -> i32{v: 0 <= v && v <= x} requires x >= 0)]
+pub fn mask_signed(x: i32, y: i32) -> i32 {
+    x & y
+}
+
+#[flux::sig(fn(u32[12], u32[10]) -> u32[8])]
+pub fn and_const(x: u32, y: u32) -> u32 {
+    x & y
+}
+
+#[flux::sig(fn(u32[@x]) -> u32[0])]
+pub fn xor_self(x: u32) -> u32 {
+    x ^ x
+}
+
+#[flux::sig(fn(u32[1], u32[4]) -> u32[16])]
+pub fn shl_const(x: u32, n: u32) -> u32 {
+    x << n
+}
+
+#[flux::sig(fn(i64[-8], u32[1]) -> i64[-4])]
+pub fn shr_signed(x: i64, n: u32) -> i64 {
+    x >> n
+}
+
+#[flux::sig(fn(i32[-1], i32[0]) -> i32[-1])]
+pub fn or_signed(x: i32, y: i32) -> i32 {
+    x | y
+}
+
+#[flux::sig(fn(bool[@a], bool[@b]) -> bool[a && b])]
+pub fn and_bool(a: bool, b: bool) -> bool {
+    a & b
+}
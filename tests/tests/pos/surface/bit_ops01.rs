@@ -0,0 +1,21 @@
+#![flux::opts(precise_bit_ops = true, fixpoint_backend = "native")]
+
+#[flux::sig(fn(u8[240], u8[60]) -> u8[48])]
+pub fn and_u8(x: u8, y: u8) -> u8 {
+    x & y
+}
+
+#[flux::sig(fn(i16[-2], i16[1]) -> i16[-1])]
+pub fn or_i16(x: i16, y: i16) -> i16 {
+    x | y
+}
+
+#[flux::sig(fn(u8[128], u32[1]) -> u8[64])]
+pub fn shr_u8(x: u8, n: u32) -> u8 {
+    x >> n
+}
+
+#[flux::sig(fn(u128[1], u32[100]) -> u128{v: v > 0})]
+pub fn shl_u128(x: u128, n: u32) -> u128 {
+    x << n
+}
@@ -0,0 +1,72 @@
+#![flux::opts(fixpoint_backend = "native")]
+
+#[flux::opaque]
+#[flux::refined_by(x: bitvec<8>)]
+#[derive(Clone, Copy)]
+pub struct BV8(u8);
+
+#[flux::opaque]
+#[flux::refined_by(x: bitvec<16>)]
+#[derive(Clone, Copy)]
+pub struct BV16(u16);
+
+#[flux::opaque]
+#[flux::refined_by(x: bitvec<64>)]
+#[derive(Clone, Copy)]
+pub struct BV64(u64);
+
+#[flux::trusted]
+#[flux::sig(fn(u8[@n]) -> BV8[bv_int_to_bv(n)])]
+fn bv8(n: u8) -> BV8 {
+    BV8(n)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV16[@x]) -> u16[bv_bv_to_int(x)])]
+fn to_u16(x: BV16) -> u16 {
+    x.0
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV8[@hi], BV8[@lo]) -> BV16[bv_concat(hi, lo)])]
+fn from_be_bytes(hi: BV8, lo: BV8) -> BV16 {
+    BV16(u16::from_be_bytes([hi.0, lo.0]))
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV16[@x]) -> BV8[bv_extract(x)])]
+fn low(x: BV16) -> BV8 {
+    BV8(x.0 as u8)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV8[@x]) -> BV64[bv_zero_extend(x)])]
+fn widen(x: BV8) -> BV64 {
+    BV64(x.0 as u64)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(BV8[@x]) -> BV16[bv_sign_extend(x)])]
+fn sign_extend(x: BV8) -> BV16 {
+    BV16(x.0 as i8 as i16 as u16)
+}
+
+#[flux::sig(fn(u8[@hi], u8[@lo]) -> u16[256 * hi + lo])]
+fn read_u16(hi: u8, lo: u8) -> u16 {
+    to_u16(from_be_bytes(bv8(hi), bv8(lo)))
+}
+
+#[flux::sig(fn(BV8[@hi], BV8[@lo]) -> BV8[lo])]
+fn low_of_concat(hi: BV8, lo: BV8) -> BV8 {
+    low(from_be_bytes(hi, lo))
+}
+
+#[flux::sig(fn(BV8[@x]) -> BV64{v: bv_ule(v, bv_int_to_bv(255))})]
+fn widen_bounded(x: BV8) -> BV64 {
+    widen(x)
+}
+
+#[flux::sig(fn() -> BV16[bv_int_to_bv(65535)])]
+fn minus_one() -> BV16 {
+    sign_extend(bv8(255))
+}
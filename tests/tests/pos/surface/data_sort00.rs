@@ -0,0 +1,71 @@
+#![flux::defs {
+    sort Status {
+        Idle,
+        Running(int),
+        Done(bool, int),
+    }
+
+    fn is_active(s: Status) -> bool {
+        match s {
+            Running(_) => true,
+            _ => false,
+        }
+    }
+
+    fn steps(s: Status) -> int {
+        match s {
+            Idle => 0,
+            Running(n) => n,
+            Done(_, n) => n,
+        }
+    }
+}]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::refined_by(s: Status)]
+pub enum State {
+    #[flux::variant(State[Idle])]
+    Idle,
+    #[flux::variant((i32[@n]) -> State[Running(n)])]
+    Running(i32),
+    #[flux::variant((bool[@ok], i32[@n]) -> State[Done(ok, n)])]
+    Done(bool, i32),
+}
+
+#[flux::sig(fn(&State[@s]) -> i32[steps(s)])]
+pub fn steps(state: &State) -> i32 {
+    match state {
+        State::Idle => 0,
+        State::Running(n) => *n,
+        State::Done(_, n) => *n,
+    }
+}
+
+#[flux::sig(fn(&State[@s]) -> bool[is_active(s)])]
+pub fn is_active(state: &State) -> bool {
+    match state {
+        State::Idle => false,
+        State::Running(_) => true,
+        State::Done(..) => false,
+    }
+}
+
+#[flux::sig(fn(&State[@s]) -> State{v: steps(v) == steps(s) + 1} requires is_active(s))]
+pub fn step(state: &State) -> State {
+    match state {
+        State::Running(n) => State::Running(*n + 1),
+        State::Idle => State::Idle,
+        State::Done(..) => State::Idle,
+    }
+}
+
+pub fn test() {
+    let state = State::Running(0);
+    assert(steps(&state) == 0);
+    assert(is_active(&state));
+    let state = step(&state);
+    assert(steps(&state) == 1);
+    assert(!is_active(&State::Done(true, 3)));
+}
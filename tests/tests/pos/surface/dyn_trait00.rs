@@ -0,0 +1,41 @@
+pub trait Validator {
+    #[flux::sig(fn(&Self, x: i32) -> bool{b: b => x >= 0})]
+    fn validate(&self, x: i32) -> bool;
+}
+
+pub struct NonNeg;
+
+impl Validator for NonNeg {
+    #[flux::sig(fn(&Self, x: i32) -> bool[x >= 0])]
+    fn validate(&self, x: i32) -> bool {
+        x >= 0
+    }
+}
+
+pub struct Positive;
+
+impl Validator for Positive {
+    #[flux::sig(fn(&Self, x: i32) -> bool[x > 0])]
+    fn validate(&self, x: i32) -> bool {
+        x > 0
+    }
+}
+
+#[flux::sig(fn(v: _, x: i32) -> Option<i32{r: r >= 0}>)]
+pub fn check_ref(v: &dyn Validator, x: i32) -> Option<i32> {
+    if v.validate(x) { Some(x) } else { None }
+}
+
+#[flux::sig(fn(v: _, x: i32) -> Option<i32{r: r >= 0}>)]
+pub fn check_box(v: Box<dyn Validator>, x: i32) -> Option<i32> {
+    if v.validate(x) { Some(x) } else { None }
+}
+
+pub fn make(strict: bool) -> Box<dyn Validator> {
+    if strict { Box::new(Positive) } else { Box::new(NonNeg) }
+}
+
+pub fn test(x: i32) -> Option<i32> {
+    check_ref(&NonNeg, x)?;
+    check_box(make(true), x)
+}
@@ -0,0 +1,84 @@
+// NaN fails both comparisons so it is mapped to `0.0`
+#[flux::sig(fn(x: f64) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn clamp01(x: f64) -> f64 {
+    if 0.0 <= x && x <= 1.0 {
+        x
+    } else if x > 1.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+#[flux::sig(fn(x: f64{0.0 <= x && x <= 1.0}, y: f64{0.0 <= y && y <= 1.0}) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn mul01(x: f64, y: f64) -> f64 {
+    x * y
+}
+
+#[flux::sig(fn(x: f64{0.0 <= x && x <= 1.0}) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn complement(x: f64) -> f64 {
+    1.0 - x
+}
+
+#[flux::sig(fn(x: f32{x >= 0.0}, y: f32{y >= 0.0}) -> f32{v: v >= x && v >= y})]
+pub fn add_nonneg(x: f32, y: f32) -> f32 {
+    x + y
+}
+
+#[flux::sig(fn(x: f64{x >= 0.0}, n: f64{n >= 1.0}) -> f64{v: 0.0 <= v && v <= x})]
+pub fn scale_down(x: f64, n: f64) -> f64 {
+    x / n
+}
+
+#[flux::sig(fn(x: f64) -> f64[-x])]
+pub fn neg(x: f64) -> f64 {
+    -x
+}
+
+#[flux::sig(fn() -> f64[0.5])]
+pub fn half() -> f64 {
+    0.5
+}
+
+#[flux::sig(fn(x: f64, y: f64) -> bool{b: b => x < y})]
+pub fn lt(x: f64, y: f64) -> bool {
+    x < y
+}
+
+#[flux::sig(fn(x: f32, y: f32) -> bool{b: b => x == y})]
+pub fn eq(x: f32, y: f32) -> bool {
+    x == y
+}
+
+#[flux::sig(fn(x: f64, y: f64) -> f64{v: v <= y})]
+pub fn min(x: f64, y: f64) -> f64 {
+    if x <= y { x } else { y }
+}
+
+// `x != y` doesn't hold only if both are numbers and they are equal
+#[flux::sig(fn(x: f64, y: f64) -> f64{v: v == y || v == 0.0})]
+pub fn eq_or_zero(x: f64, y: f64) -> f64 {
+    if x != y { 0.0 } else { x }
+}
+
+#[flux::sig(fn(p: f64{0.0 <= p && p <= 1.0}) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn bernoulli_variance(p: f64) -> f64 {
+    p * complement(p)
+}
+
+pub fn test() {
+    let p = clamp01(0.3);
+    let q = mul01(p, p);
+    if q > 1.0 {
+        assert(false);
+    }
+    if half() > 0.5 {
+        assert(false);
+    }
+    if neg(2.0) >= 0.0 {
+        assert(false);
+    }
+}
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
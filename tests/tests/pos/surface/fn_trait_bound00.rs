@@ -0,0 +1,89 @@
+#[flux::sig(fn(f: F) -> i32{v: v >= 0} where F: Fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn apply<F>(f: F) -> i32
+where
+    F: Fn(i32) -> i32,
+{
+    f(1)
+}
+
+#[flux::sig(fn(f: F) -> i32{v: v >= 0} where F: FnMut(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn apply_mut<F>(mut f: F) -> i32
+where
+    F: FnMut(i32) -> i32,
+{
+    f(1) + f(2)
+}
+
+#[flux::sig(fn(f: F) -> i32{v: v >= 0} where F: FnOnce(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn apply_once<F>(f: F) -> i32
+where
+    F: FnOnce(i32) -> i32,
+{
+    f(1)
+}
+
+#[flux::sig(fn(f: F, x: i32{x > 0}) where F: Fn(i32{v: v > 0}))]
+pub fn apply_unit<F: Fn(i32)>(f: F, x: i32) {
+    f(x);
+}
+
+#[flux::sig(fn(f: F, n: i32) -> i32{v: v > n} where F: Fn(i32{v: v > n}) -> i32{v: v > n})]
+pub fn apply_above<F: Fn(i32) -> i32>(f: F, n: i32) -> i32 {
+    f(n + 1)
+}
+
+#[flux::sig(fn(x: Option<i32{v: v > 0}>, f: F) -> Option<i32{v: v >= 0}> where F: Fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn map_opt<F: Fn(i32) -> i32>(x: Option<i32>, f: F) -> Option<i32> {
+    x.map(|n| f(n))
+}
+
+#[flux::sig(fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+pub fn dec(x: i32) -> i32 {
+    x - 1
+}
+
+#[flux::sig(fn(i32) -> i32{v: v >= 0})]
+pub fn abs(x: i32) -> i32 {
+    if x < 0 { 0 - x } else { x }
+}
+
+#[flux::sig(fn(g: G) -> i32{v: v >= 0} where G: Fn(i32{v: v >= 0}) -> i32{v: v > 0})]
+pub fn pass<G: Fn(i32) -> i32>(g: G) -> i32 {
+    apply(g)
+}
+
+pub fn test_closure() -> i32 {
+    apply(|x| x - 1)
+}
+
+pub fn test_fn_def() -> i32 {
+    apply(dec) + apply(abs)
+}
+
+pub fn test_mut() -> i32 {
+    let mut count = 0;
+    apply_mut(|x| {
+        count += 1;
+        x
+    })
+}
+
+pub fn test_once() -> i32 {
+    let s = String::new();
+    apply_once(move |x| {
+        drop(s);
+        x
+    })
+}
+
+#[flux::sig(fn(n: i32) -> i32{v: v > n})]
+pub fn test_above(n: i32) -> i32 {
+    apply_above(|x| x, n)
+}
+
+pub fn test_unit() {
+    apply_unit(|x| assert(x > 0), 5);
+}
+
+#[flux::sig(fn(bool[true]))]
+pub fn assert(_: bool) {}
@@ -0,0 +1,125 @@
+#![allow(dead_code)]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::sig(fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+fn dec(x: i32) -> i32 {
+    x - 1
+}
+
+#[flux::sig(fn(i32) -> i32{v: v >= 0})]
+fn zero(_: i32) -> i32 {
+    0
+}
+
+#[flux::sig(fn(i32[@n]) -> i32[n + 1])]
+fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::sig(fn(f: fn(i32{v: v > 0}) -> i32{v: v >= 0}, x: i32{v: v > 0}) -> i32{v: v >= 0})]
+fn apply(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+#[flux::sig(fn(f: fn(i32[@n]) -> i32{v: v > n}, x: i32) -> i32{v: v > x})]
+fn apply_above(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+#[flux::sig(fn(f: fn[a: int, b: int](i32[a], i32[b]) -> i32[a + b]) -> i32[3])]
+fn apply_add(f: fn(i32, i32) -> i32) -> i32 {
+    f(1, 2)
+}
+
+#[flux::sig(fn(i32[@a], i32[@b]) -> i32[a + b])]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn test_reify() -> i32 {
+    apply(dec, 10) + apply(zero, 1)
+}
+
+fn test_above() {
+    let r = apply_above(inc, 5);
+    assert(r > 5);
+}
+
+fn test_add() {
+    assert(apply_add(add) == 3);
+}
+
+// A function pointer can be passed where a less precise one is expected
+#[flux::sig(fn(f: fn(i32{v: v > 0}) -> i32{v: v >= 0}) -> i32{v: v >= 0})]
+fn pass(f: fn(i32) -> i32) -> i32 {
+    apply(f, 1)
+}
+
+#[flux::sig(fn(f: fn(i32) -> i32{v: v > 0}) -> i32{v: v >= 0})]
+fn pass_stronger(f: fn(i32) -> i32) -> i32 {
+    pass(f)
+}
+
+// Dispatch tables
+#[flux::sig(fn(table: &[fn(i32) -> i32{v: v >= 0}], usize) -> i32{v: v >= 0})]
+fn dispatch(table: &[fn(i32) -> i32], i: usize) -> i32 {
+    if i < table.len() {
+        table[i](i as i32)
+    } else {
+        0
+    }
+}
+
+#[flux::sig(fn(i32) -> i32{v: v >= 0})]
+fn abs(x: i32) -> i32 {
+    if x < 0 {
+        0 - x
+    } else {
+        x
+    }
+}
+
+fn test_dispatch() -> i32 {
+    let table: [fn(i32) -> i32; 2] = [abs, zero];
+    dispatch(&table, 1)
+}
+
+// Refined function pointers in fields
+struct Handler {
+    #[flux::field(fn(i32{v: v > 0}) -> i32{v: v >= 0})]
+    f: fn(i32) -> i32,
+}
+
+#[flux::sig(fn(&Handler) -> i32{v: v >= 0})]
+fn run(handler: &Handler) -> i32 {
+    (handler.f)(5)
+}
+
+fn test_handler() -> i32 {
+    run(&Handler { f: dec })
+}
+
+// Calls through unrefined and packed function pointers
+fn call_unrefined(f: fn(i32) -> i32) -> i32 {
+    f(0)
+}
+
+fn call_opt(f: Option<fn(i32) -> i32>) -> i32 {
+    match f {
+        Some(f) => f(1),
+        None => 0,
+    }
+}
+
+// Functions taking strong references are checked against the unrefined signature, instantiating
+// their own refinement parameters
+#[flux::sig(fn[hrn p: int -> bool](x: &strg i32{v: p(v)}) ensures x: i32{v: p(v)})]
+fn keep(_: &mut i32) {}
+
+fn test_strg_early() {
+    let f: fn(&mut i32) = keep;
+    let mut x = 0;
+    f(&mut x);
+}
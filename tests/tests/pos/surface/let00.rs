@@ -0,0 +1,39 @@
+#![flux::defs {
+    fn sq_dist(x: int, y: int) -> int {
+        let d = x - y;
+        d * d
+    }
+
+    fn in_range(x: int, lo: int, len: int) -> bool {
+        let hi: int = lo + len;
+        lo <= x && x < hi
+    }
+}]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::sig(fn(x: i32, y: i32) -> i32{v: let d = x - y; v == if d >= 0 { d } else { -d }})]
+fn dist(x: i32, y: i32) -> i32 {
+    if x >= y {
+        x - y
+    } else {
+        y - x
+    }
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: let y = x + 1; let z = y + 1; v == z})]
+fn incr2(x: i32) -> i32 {
+    x + 2
+}
+
+#[flux::sig(fn(x: i32{in_range(x, 0, 10)}) -> i32[sq_dist(x, x)])]
+fn zero(_x: i32) -> i32 {
+    0
+}
+
+fn test() {
+    assert(dist(1, 3) == 2);
+    assert(incr2(0) == 2);
+    zero(5);
+}
@@ -0,0 +1,47 @@
+#![flux::defs {
+    fn is_even(x: int) -> bool { exists k: int. x == 2 * k }
+}]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::sig(fn(x: i32) -> i32{v: is_even(v)})]
+fn double(x: i32) -> i32 {
+    x + x
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: exists k. v == k + 1 && k == x})]
+fn incr(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::sig(
+    fn(x: i32)
+    requires forall y. y >= 0 => y > x
+)]
+fn requires_negative(x: i32) {
+    assert(x < 0);
+}
+
+#[flux::sig(fn(x: i32{exists k: int. k > 0 && x == k}))]
+fn positive(x: i32) {
+    assert(x > 0);
+}
+
+#[flux::sig(fn(x: i32) ensures x <= 0 || (forall i: int. i < 0 => x > i))]
+fn ensures_quant(_x: i32) {}
+
+#[flux::refined_by(lo: int, hi: int)]
+#[flux::invariant(forall i: int. lo <= i && i < hi => i >= 0)]
+pub struct Range {
+    #[flux::field(u32[lo])]
+    lo: u32,
+    #[flux::field(u32[hi])]
+    hi: u32,
+}
+
+fn test() {
+    requires_negative(-1);
+    positive(incr(0));
+    double(1);
+}
@@ -0,0 +1,47 @@
+#![flux::opts(check_raw_pointers = true)]
+
+// Load the extern specs for raw pointers shipped with `flux_rs`
+extern crate flux_rs;
+
+#[flux::sig(fn(p: *const i32[@b, @o, @n]) -> i32 requires 0 <= o && o < n)]
+pub unsafe fn deref(p: *const i32) -> i32 {
+    *p
+}
+
+#[flux::sig(fn(p: *mut i32[@b, @o, @n], v: i32) requires 0 <= o && o < n)]
+pub unsafe fn store(p: *mut i32, v: i32) {
+    *p = v;
+}
+
+#[flux::sig(fn(p: *const i32[@b, 0, @n], i: usize{i < n}) -> i32)]
+pub unsafe fn get(p: *const i32, i: usize) -> i32 {
+    unsafe { *p.add(i) }
+}
+
+#[flux::sig(fn(p: *const i32[@b, 0, @n], i: usize{i < n}) -> i32)]
+pub unsafe fn read(p: *const i32, i: usize) -> i32 {
+    unsafe { std::ptr::read(p.add(i)) }
+}
+
+#[flux::sig(fn(p: *mut i32[@b, 0, @n], i: usize{0 < i && i <= n}, v: i32))]
+pub unsafe fn write_back(p: *mut i32, i: usize, v: i32) {
+    unsafe { std::ptr::write(p.add(i).sub(1), v) }
+}
+
+pub fn local() -> i32 {
+    let mut x = 0;
+    let p = &raw mut x;
+    unsafe {
+        *p = 1;
+        *p
+    }
+}
+
+#[flux::sig(fn(s: &[i32][@n], i: usize{i < n}) -> i32)]
+pub fn slice_get(s: &[i32], i: usize) -> i32 {
+    unsafe { *s.as_ptr().add(i) }
+}
+
+pub fn null_is_unchecked() -> *const i32 {
+    std::ptr::null()
+}
@@ -0,0 +1,68 @@
+#![flux::defs {
+    sort IntList {
+        Nil,
+        Cons(int, IntList),
+    }
+
+    fn len(l: IntList) -> int {
+        match l {
+            Nil => 0,
+            Cons(_, tl) => 1 + len(tl),
+        }
+    }
+
+    fn sum(l: IntList) -> int {
+        match l {
+            Nil => 0,
+            Cons(x, tl) => x + sum(tl),
+        }
+    }
+
+    fn triangle(n: int) -> int {
+        if n <= 0 { 0 } else { n + triangle(n - 1) }
+    }
+}]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::refined_by(elems: IntList)]
+pub enum List {
+    #[flux::variant(List[Nil])]
+    Nil,
+    #[flux::variant((i32[@x], Box<List[@tl]>) -> List[Cons(x, tl)])]
+    Cons(i32, Box<List>),
+}
+
+#[flux::sig(fn(&List[@l]) -> usize[len(l)])]
+pub fn len(list: &List) -> usize {
+    match list {
+        List::Nil => 0,
+        List::Cons(_, tl) => 1 + len(tl),
+    }
+}
+
+#[flux::sig(fn(&List[@l]) -> i32[sum(l)])]
+pub fn sum(list: &List) -> i32 {
+    match list {
+        List::Nil => 0,
+        List::Cons(x, tl) => x + sum(tl),
+    }
+}
+
+#[flux::sig(fn(n: i32{n >= 0}) -> i32[triangle(n)])]
+pub fn triangle(n: i32) -> i32 {
+    let mut i = 0;
+    let mut acc = 0;
+    while i < n {
+        i += 1;
+        acc += i;
+    }
+    acc
+}
+
+pub fn test() {
+    let list = List::Cons(1, Box::new(List::Cons(2, Box::new(List::Nil))));
+    assert(len(&list) == 2);
+    assert(sum(&list) == 3);
+}
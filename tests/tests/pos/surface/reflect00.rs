@@ -0,0 +1,72 @@
+#[flux::reflect]
+fn is_digit(b: u8) -> bool {
+    b >= 48 && b <= 57
+}
+
+#[flux::reflect]
+fn max(a: i32, b: i32) -> i32 {
+    if a > b { a } else { b }
+}
+
+#[flux::reflect]
+fn clamp(x: i32, lo: i32, hi: i32) -> i32 {
+    let y = max(x, lo);
+    if y > hi { hi } else { y }
+}
+
+#[flux::reflect]
+fn sign(x: i64) -> i64 {
+    if x > 0 {
+        1
+    } else if x < 0 {
+        -1
+    } else {
+        0
+    }
+}
+
+#[flux::reflect]
+fn weekday(d: u32) -> bool {
+    match d {
+        0 | 6 => false,
+        _ => true,
+    }
+}
+
+#[flux::sig(fn(a: i32, b: i32) -> i32{v: v == max(a, b)})]
+fn call_max(a: i32, b: i32) -> i32 {
+    max(a, b)
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: 0 <= v && v <= 10})]
+fn clamp_to_ten(x: i32) -> i32 {
+    clamp(x, 0, 10)
+}
+
+#[flux::sig(fn(x: i64{x < 0}) -> i64[-1])]
+fn negative_sign(x: i64) -> i64 {
+    sign(x)
+}
+
+#[flux::sig(fn(b: u8{is_digit(b)}) -> u8{v: v <= 9})]
+fn digit_value(b: u8) -> u8 {
+    b - 48
+}
+
+#[flux::sig(fn(d: u32{weekday(d)}) -> u32{v: v > 0})]
+fn weekday_index(d: u32) -> u32 {
+    d
+}
+
+#[flux::sig(fn(i32{v: max(v, 0) == v}) -> i32{v: v >= 0})]
+fn non_negative(x: i32) -> i32 {
+    x
+}
+
+pub fn test() {
+    let n = non_negative(max(-5, 3));
+    assert_pos(n + 1);
+}
+
+#[flux::sig(fn(i32{v: v > 0}))]
+fn assert_pos(_: i32) {}
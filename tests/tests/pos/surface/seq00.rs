@@ -0,0 +1,49 @@
+#![flux::opts(fixpoint_backend = "native")]
+
+#[path = "../../lib/rvec.rs"]
+mod rvec;
+use rvec::SeqVec;
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+fn test00() {
+    let mut v = SeqVec::new();
+    v.push(1);
+    v.push(2);
+    v.push(3);
+    assert(v.len() == 3);
+    assert(*v.get(0) == 1);
+    assert(*v.get(2) == 3);
+    assert(v.contains(&2));
+    assert(v.pop() == 3);
+    assert(v.len() == 2);
+}
+
+#[flux::sig(fn(v: &strg SeqVec<i32>[@s], i: usize{i < seq_len(s)}, j: usize{j < seq_len(s)})
+        ensures v: SeqVec<i32>[seq_swap(s, i, j)])]
+fn swap_twice(v: &mut SeqVec<i32>, i: usize, j: usize) {
+    v.swap(i, j);
+    v.swap(j, i);
+    v.swap(i, j);
+}
+
+#[flux::sig(fn(v: &strg SeqVec<i32>[@s], i: usize{i < seq_len(s)})
+        ensures v: SeqVec<i32>[s])]
+fn set_same(v: &mut SeqVec<i32>, i: usize) {
+    let x = *v.get(i);
+    v.set(i, x);
+}
+
+#[flux::sig(fn(v: &strg SeqVec<i32>{s: seq_len(s) == 2}) ensures v: SeqVec<i32>{s: seq_sorted(s)})]
+fn sort2(v: &mut SeqVec<i32>) {
+    if *v.get(0) > *v.get(1) {
+        v.swap(0, 1);
+    }
+}
+
+#[flux::sig(fn(&SeqVec<i32>[@s]) -> i32{r: forall i: int. 0 <= i && i < seq_len(s) => r <= seq_nth(s, i)}
+        requires seq_sorted(s) && seq_len(s) > 0)]
+fn min(v: &SeqVec<i32>) -> i32 {
+    *v.get(0)
+}
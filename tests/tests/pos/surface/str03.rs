@@ -0,0 +1,57 @@
+#![flux::opts(fixpoint_backend = "native")]
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::trusted]
+#[flux::sig(fn(&str[@s], &str[@p]) -> bool[str_prefix_of(p, s)])]
+fn starts_with(s: &str, p: &str) -> bool {
+    s.starts_with(p)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(&str[@s], &str[@p]) -> bool[str_contains(s, p)])]
+fn contains(s: &str, p: &str) -> bool {
+    s.contains(p)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(&str[@s], &str[@p]) -> Option<&str{r: s == str_concat(p, r)}>)]
+fn strip_prefix<'a>(s: &'a str, p: &str) -> Option<&'a str> {
+    s.strip_prefix(p)
+}
+
+#[flux::sig(fn(&str{s: str_prefix_of("GET ", s)}))]
+fn handle_get(_req: &str) {}
+
+fn dispatch(req: &str) {
+    if starts_with(req, "GET ") {
+        handle_get(req);
+    }
+}
+
+#[flux::sig(fn(&str{s: str_contains(s, ":")}))]
+fn parse_pair(_s: &str) {}
+
+fn check_delimiter(s: &str) {
+    if contains(s, ":") {
+        parse_pair(s);
+    }
+}
+
+#[flux::sig(fn(&str[@s]) -> Option<&str{r: s == str_concat("GET ", r)}>)]
+fn path(req: &str) -> Option<&str> {
+    strip_prefix(req, "GET ")
+}
+
+fn literals() {
+    assert(starts_with("GET /index.html", "GET "));
+    assert(contains("key:value", ":"));
+}
+
+#[flux::sig(fn(&str{s: str_len(s) > 2 && str_at(s, 0) == "\"" && str_substr(s, 1, 1) == "a"}))]
+fn quoted(_s: &str) {}
+
+fn test_quoted() {
+    quoted("\"abc\"");
+}
@@ -0,0 +1,51 @@
+// Same as `str03.rs` but with the default backend, where string functions other than `str_len` are
+// uninterpreted. Facts can still be passed around as long as they don't depend on their meaning.
+
+#[flux::trusted]
+#[flux::sig(fn(&str[@s], &str[@p]) -> bool[str_prefix_of(p, s)])]
+fn starts_with(s: &str, p: &str) -> bool {
+    s.starts_with(p)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(&str[@s], &str[@p]) -> bool[str_contains(s, p)])]
+fn contains(s: &str, p: &str) -> bool {
+    s.contains(p)
+}
+
+#[flux::trusted]
+#[flux::sig(fn(&str[@s], &str[@p]) -> Option<&str{r: s == str_concat(p, r)}>)]
+fn strip_prefix<'a>(s: &'a str, p: &str) -> Option<&'a str> {
+    s.strip_prefix(p)
+}
+
+#[flux::sig(fn(&str{s: str_prefix_of("GET ", s)}))]
+fn handle_get(_req: &str) {}
+
+pub fn dispatch(req: &str) {
+    if starts_with(req, "GET ") {
+        handle_get(req);
+    }
+}
+
+#[flux::sig(fn(&str{s: str_contains(s, ":")}))]
+fn parse_pair(_s: &str) {}
+
+pub fn check_delimiter(s: &str) {
+    if contains(s, ":") {
+        parse_pair(s);
+    }
+}
+
+#[flux::sig(fn(&str[@s]) -> Option<&str{r: s == str_concat("GET ", r)}>)]
+pub fn path(req: &str) -> Option<&str> {
+    strip_prefix(req, "GET ")
+}
+
+#[flux::sig(fn(&str{s: str_at(s, 0) == "\"" && str_substr(s, 1, 1) == "a"}))]
+fn quoted(_s: &str) {}
+
+#[flux::sig(fn(&str{s: str_at(s, 0) == "\"" && str_substr(s, 1, 1) == "a"}))]
+pub fn forward_quoted(s: &str) {
+    quoted(s);
+}
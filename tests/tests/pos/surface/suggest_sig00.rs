@@ -0,0 +1,17 @@
+// The suggested signatures are checked in `suggest_sig00.stderr`. Conjuncts that are implied by
+// another one are not printed, e.g., `x < v` or `v >= x` for `inc`.
+#![flux::opts(suggest_sigs = true)]
+
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+pub fn max(x: i32, y: i32) -> i32 {
+    if x > y { x } else { y }
+}
+
+// No signature is suggested for functions that already have one
+#[flux::sig(fn(x: i32) -> i32{v: v < x})]
+pub fn dec(x: i32) -> i32 {
+    x - 1
+}
@@ -0,0 +1,10 @@
+// Same as `suggest_sig00.rs` but the solution is found by the native backend
+#![flux::opts(suggest_sigs = true, fixpoint_backend = "native")]
+
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+pub fn max(x: i32, y: i32) -> i32 {
+    if x > y { x } else { y }
+}
@@ -0,0 +1,50 @@
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+fn may_panic(x: i32) {
+    if x < 0 {
+        panic!("negative")
+    }
+}
+
+pub struct Counter {
+    count: i32,
+    log: Vec<i32>,
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {}
+}
+
+#[flux::sig(fn(n: i32{n > 0}) -> i32{v: v > 0})]
+pub fn test00(n: i32) -> i32 {
+    let mut c = Counter { count: 0, log: Vec::new() };
+    let r = &mut c.count;
+    *r += 1;
+    c.log.push(n);
+    may_panic(n);
+    assert(n > 0);
+    n
+}
+
+#[flux::sig(fn(xs: &mut Vec<i32>, n: i32{n >= 0}))]
+pub fn test01(xs: &mut Vec<i32>, n: i32) {
+    let mut i = 0;
+    let tmp = Box::new(n);
+    while i < n {
+        xs.push(i);
+        may_panic(*tmp);
+        i += 1;
+    }
+    assert(i >= 0);
+}
+
+pub fn test02(opt: Option<Vec<i32>>) -> usize {
+    match opt {
+        Some(v) => {
+            may_panic(0);
+            v.len()
+        }
+        None => 0,
+    }
+}
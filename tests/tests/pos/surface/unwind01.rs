@@ -0,0 +1,19 @@
+#![flux::opts(assume_panic_abort = true)]
+
+pub struct Pos {
+    #[flux::field(i32{v: v > 0})]
+    x: i32,
+}
+
+#[flux::sig(fn(x: &strg i32) ensures x: i32{v: v > 0})]
+fn reset(x: &mut i32) {
+    *x = 1;
+}
+
+// The invariant of `Pos` is only broken if `reset` panics, see `neg/surface/unwind00.rs`
+#[flux::sig(fn(p: &strg Pos, v: Vec<i32>) ensures p: Pos)]
+pub fn test00(p: &mut Pos, v: Vec<i32>) {
+    p.x = 0;
+    reset(&mut p.x);
+    drop(v);
+}
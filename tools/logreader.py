@@ -48,10 +48,14 @@ def main() -> None:
     events_by_def_id_and_mode: DefaultDict[str, DefaultDict[
         str, List[Any]]] = defaultdict(lambda: defaultdict(list))
     bb_envs: Dict[str, Any] = {}
+    solutions: DefaultDict[str, List[Any]] = defaultdict(list)
     for line in open(args.file):
         event = json.loads(line)
         def_id = event['span']['def_id']
         mode = event['span']['name']
+        if mode == 'solution':
+            solutions[def_id].append(event['fields'])
+            continue
         events_by_def_id_and_mode[def_id][mode].append(event)
         if bb_envs.get(def_id) is None:
             bb_envs[def_id] = event['span'].get('bb_envs')
@@ -68,6 +72,7 @@ def main() -> None:
             buf.print(bold(f'{mode.upper()} {def_id}'))
             if args.mode == "refine" and bb_envs[def_id] is not None:
                 buf.print(bb_envs[def_id])
+                buf.print_solution(solutions[def_id])
             buf.print_rule('═')
             buf.print()
             buf.print_mode(events, args.filter)
@@ -151,6 +156,13 @@ class Buff:
                 self.print(fields['bb_env'])
                 self.print_rule()
 
+    def print_solution(self, solution: List[dict]) -> None:
+        if not solution:
+            return
+        self.print_rule()
+        for fields in solution:
+            self.print(f'{fields["kvar"]} := {fields["solution"]}')
+
     def print_rule(self, c='─') -> None:
         self.print(Rule(c))
 